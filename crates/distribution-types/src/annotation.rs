@@ -17,6 +17,15 @@ pub enum SourceAnnotation {
     Requirement(RequirementOrigin),
 }
 
+impl SourceAnnotation {
+    /// Return the [`RequirementOrigin`] of the annotation.
+    pub fn origin(&self) -> &RequirementOrigin {
+        match self {
+            Self::Constraint(origin) | Self::Override(origin) | Self::Requirement(origin) => origin,
+        }
+    }
+}
+
 impl std::fmt::Display for SourceAnnotation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -61,8 +61,10 @@ impl std::fmt::Display for BuildableSource<'_> {
 pub enum SourceUrl<'a> {
     Direct(DirectSourceUrl<'a>),
     Git(GitSourceUrl<'a>),
+    Vcs(VcsSourceUrl<'a>),
     Path(PathSourceUrl<'a>),
     Directory(DirectorySourceUrl<'a>),
+    Script(ScriptSourceUrl<'a>),
 }
 
 impl<'a> SourceUrl<'a> {
@@ -71,8 +73,10 @@ impl<'a> SourceUrl<'a> {
         match self {
             Self::Direct(dist) => dist.url,
             Self::Git(dist) => dist.url,
+            Self::Vcs(dist) => dist.url,
             Self::Path(dist) => dist.url,
             Self::Directory(dist) => dist.url,
+            Self::Script(dist) => dist.url,
         }
     }
 }
@@ -82,8 +86,10 @@ impl std::fmt::Display for SourceUrl<'_> {
         match self {
             Self::Direct(url) => write!(f, "{url}"),
             Self::Git(url) => write!(f, "{url}"),
+            Self::Vcs(url) => write!(f, "{url}"),
             Self::Path(url) => write!(f, "{url}"),
             Self::Directory(url) => write!(f, "{url}"),
+            Self::Script(url) => write!(f, "{url}"),
         }
     }
 }
@@ -116,6 +122,35 @@ impl<'a> From<&'a GitSourceDist> for GitSourceUrl<'a> {
     }
 }
 
+/// A version control system, other than Git, from which a source distribution can be fetched.
+///
+/// Git is handled separately (via [`SourceUrl::Git`]), as it's also supported for named
+/// requirements.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Vcs {
+    Mercurial,
+}
+
+impl std::fmt::Display for Vcs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mercurial => write!(f, "hg"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VcsSourceUrl<'a> {
+    pub url: &'a Url,
+    pub vcs: Vcs,
+}
+
+impl std::fmt::Display for VcsSourceUrl<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{url}", url = self.url)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PathSourceUrl<'a> {
     pub url: &'a Url,
@@ -157,3 +192,17 @@ impl<'a> From<&'a DirectorySourceDist> for DirectorySourceUrl<'a> {
         }
     }
 }
+
+/// A reference to a standalone Python script, with dependencies declared via inline script
+/// metadata (PEP 723), rather than a buildable project.
+#[derive(Debug, Clone)]
+pub struct ScriptSourceUrl<'a> {
+    pub url: &'a Url,
+    pub path: Cow<'a, Path>,
+}
+
+impl std::fmt::Display for ScriptSourceUrl<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{url}", url = self.url)
+    }
+}
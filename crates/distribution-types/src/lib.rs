@@ -1087,6 +1087,16 @@ impl Identifier for GitSourceUrl<'_> {
     }
 }
 
+impl Identifier for VcsSourceUrl<'_> {
+    fn distribution_id(&self) -> DistributionId {
+        self.url.distribution_id()
+    }
+
+    fn resource_id(&self) -> ResourceId {
+        self.url.resource_id()
+    }
+}
+
 impl Identifier for PathSourceUrl<'_> {
     fn distribution_id(&self) -> DistributionId {
         self.url.distribution_id()
@@ -1107,13 +1117,25 @@ impl Identifier for DirectorySourceUrl<'_> {
     }
 }
 
+impl Identifier for ScriptSourceUrl<'_> {
+    fn distribution_id(&self) -> DistributionId {
+        self.url.distribution_id()
+    }
+
+    fn resource_id(&self) -> ResourceId {
+        self.url.resource_id()
+    }
+}
+
 impl Identifier for SourceUrl<'_> {
     fn distribution_id(&self) -> DistributionId {
         match self {
             Self::Direct(url) => url.distribution_id(),
             Self::Git(url) => url.distribution_id(),
+            Self::Vcs(url) => url.distribution_id(),
             Self::Path(url) => url.distribution_id(),
             Self::Directory(url) => url.distribution_id(),
+            Self::Script(url) => url.distribution_id(),
         }
     }
 
@@ -1121,8 +1143,10 @@ impl Identifier for SourceUrl<'_> {
         match self {
             Self::Direct(url) => url.resource_id(),
             Self::Git(url) => url.resource_id(),
+            Self::Vcs(url) => url.resource_id(),
             Self::Path(url) => url.resource_id(),
             Self::Directory(url) => url.resource_id(),
+            Self::Script(url) => url.resource_id(),
         }
     }
 }
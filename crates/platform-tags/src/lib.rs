@@ -1,5 +1,5 @@
 pub use platform::{Arch, Os, Platform, PlatformError};
-pub use tags::{IncompatibleTag, TagCompatibility, TagPriority, Tags, TagsError};
+pub use tags::{IncompatibleTag, Tag, TagCompatibility, TagPriority, Tags, TagsError};
 
 mod platform;
 mod tags;
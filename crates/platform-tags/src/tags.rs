@@ -276,6 +276,24 @@ impl TryFrom<usize> for TagPriority {
     }
 }
 
+/// A single compatibility tag, composed of a Python, ABI, and platform tag (e.g.,
+/// `cp312-cp312-win_amd64`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tag {
+    /// The Python tag (e.g., `cp312`).
+    pub python: String,
+    /// The ABI tag (e.g., `cp312`).
+    pub abi: String,
+    /// The platform tag (e.g., `win_amd64`).
+    pub platform: String,
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.python, self.abi, self.platform)
+    }
+}
+
 impl std::fmt::Display for Tags {
     /// Display tags from high to low priority
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -185,6 +185,34 @@ impl Metadata23 {
         })
     }
 
+    /// Extract the metadata from the inline metadata of a Python script, as specified in PEP 723.
+    ///
+    /// Scripts aren't distributions, so the name must be provided by the caller (e.g., from the
+    /// script's filename), and the version is always `0`. If the script lacks a `script` metadata
+    /// block, it's assumed to have no dependencies.
+    pub fn parse_script(name: PackageName, contents: &str) -> Result<Self, MetadataError> {
+        let script = match script_block(contents) {
+            Some(block) => toml::from_str::<ScriptMetadata>(&block)?,
+            None => ScriptMetadata::default(),
+        };
+
+        let requires_python = script.requires_python.map(VersionSpecifiers::from);
+        let requires_dist = script
+            .dependencies
+            .unwrap_or_default()
+            .into_iter()
+            .map(Requirement::from)
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            name,
+            version: Version::new([0]),
+            requires_dist,
+            requires_python,
+            provides_extras: vec![],
+        })
+    }
+
     /// Extract the metadata from a `pyproject.toml` file, as specified in PEP 621.
     pub fn parse_pyproject_toml(contents: &str) -> Result<Self, MetadataError> {
         let pyproject_toml: PyProjectToml = toml::from_str(contents)?;
@@ -276,6 +304,41 @@ pub(crate) struct Project {
     pub(crate) dynamic: Option<Vec<String>>,
 }
 
+/// The `script` metadata block of a Python script, as specified in PEP 723.
+///
+/// See <https://packaging.python.org/en/latest/specifications/inline-script-metadata>.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+struct ScriptMetadata {
+    /// The Python version requirements of the script
+    requires_python: Option<LenientVersionSpecifiers>,
+    /// Script dependencies
+    dependencies: Option<Vec<LenientRequirement>>,
+}
+
+/// Extract the TOML contents of the `script` metadata block from a Python script.
+///
+/// The block opens with a `# /// script` line and closes with the last `# ///` line before the
+/// next line that isn't a comment, with each line in between prefixed by `#` or `# `.
+fn script_block(contents: &str) -> Option<String> {
+    let mut lines = contents
+        .lines()
+        .skip_while(|line| line.trim_end() != "# /// script");
+    lines.next()?;
+
+    let block = lines
+        .take_while(|line| *line == "#" || line.starts_with("# "))
+        .collect::<Vec<_>>();
+    let end = block.iter().rposition(|line| line.trim_end() == "# ///")?;
+    Some(
+        block[..end]
+            .iter()
+            .map(|line| line.strip_prefix("# ").unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
 /// Python Package Metadata 1.0 and later as specified in
 /// <https://peps.python.org/pep-0241/>.
 ///
@@ -506,4 +569,32 @@ mod tests {
         );
         assert_eq!(meta.provides_extras, vec!["dotenv".parse().unwrap()]);
     }
+
+    #[test]
+    fn test_parse_script() {
+        let name = PackageName::from_str("example").unwrap();
+
+        let s = "import requests\n";
+        let meta = Metadata23::parse_script(name.clone(), s).unwrap();
+        assert_eq!(meta.name, name);
+        assert_eq!(meta.version, Version::new([0]));
+        assert!(meta.requires_python.is_none());
+        assert!(meta.requires_dist.is_empty());
+
+        let s = "# /// script\n# requires-python = \">=3.11\"\n# dependencies = [\n#   \"requests<3\",\n#   \"rich\",\n# ]\n# ///\n\nimport requests\n";
+        let meta = Metadata23::parse_script(name.clone(), s).unwrap();
+        assert_eq!(meta.requires_python, Some(">=3.11".parse().unwrap()));
+        assert_eq!(
+            meta.requires_dist,
+            vec!["requests<3".parse().unwrap(), "rich".parse().unwrap()]
+        );
+
+        let s = "# /// script\n# dependencies = [\n#   \"requests<3\",\n# ]\nimport requests\n";
+        let meta = Metadata23::parse_script(name.clone(), s).unwrap();
+        assert!(meta.requires_dist.is_empty());
+
+        let s = "# /// script\n# dependencies = \"requests\"\n# ///\n";
+        let meta = Metadata23::parse_script(name, s);
+        assert!(matches!(meta, Err(MetadataError::Toml(_))));
+    }
 }
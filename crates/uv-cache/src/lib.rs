@@ -155,6 +155,11 @@ impl Cache {
         &self.root
     }
 
+    /// Return the [`Refresh`] policy for the cache.
+    pub fn refresh(&self) -> &Refresh {
+        &self.refresh
+    }
+
     /// The folder for a specific cache bucket
     pub fn bucket(&self, cache_bucket: CacheBucket) -> PathBuf {
         self.root.join(cache_bucket.to_str())
@@ -549,6 +554,10 @@ pub enum CacheBucket {
     FlatIndex,
     /// Git repositories.
     Git,
+    /// Mercurial repositories.
+    ///
+    /// Cache structure: `hg-v0/checkouts/<digest(repository_url)>/<node>`
+    Mercurial,
     /// Information about an interpreter at a path.
     ///
     /// To avoid caching pyenv shims, bash scripts which may redirect to a new python version
@@ -610,6 +619,7 @@ impl CacheBucket {
             Self::BuiltWheels => "built-wheels-v3",
             Self::FlatIndex => "flat-index-v0",
             Self::Git => "git-v0",
+            Self::Mercurial => "hg-v0",
             Self::Interpreter => "interpreter-v1",
             Self::Simple => "simple-v7",
             Self::Wheels => "wheels-v1",
@@ -696,6 +706,17 @@ impl CacheBucket {
                         }
                     }
                 }
+
+                // Mercurial dependencies follow the same structure, with a directory for every
+                // repository, followed by a directory for every changeset.
+                let root = cache.bucket(self).join(WheelCacheKind::Mercurial);
+                for repository in directories(root) {
+                    for node in directories(repository) {
+                        if is_match(&node, name) {
+                            summary += rm_rf(node)?;
+                        }
+                    }
+                }
             }
             Self::Simple => {
                 // For `pypi` wheels, we expect a rkyv file per package, indexed by name.
@@ -718,6 +739,9 @@ impl CacheBucket {
             Self::Git => {
                 // Nothing to do.
             }
+            Self::Mercurial => {
+                // Nothing to do.
+            }
             Self::Interpreter => {
                 // Nothing to do.
             }
@@ -735,6 +759,7 @@ impl CacheBucket {
             CacheBucket::BuiltWheels,
             CacheBucket::FlatIndex,
             CacheBucket::Git,
+            CacheBucket::Mercurial,
             CacheBucket::Interpreter,
             CacheBucket::Simple,
             CacheBucket::Archive,
@@ -19,6 +19,10 @@ pub enum WheelCache<'a> {
     /// Note that this variant only exists for source distributions; wheels can't be delivered
     /// through Git.
     Git(&'a Url, &'a str),
+    /// A Mercurial dependency, which we key by URL and changeset ID.
+    ///
+    /// Like [`WheelCache::Git`], this variant only exists for source distributions.
+    Mercurial(&'a Url, &'a str),
 }
 
 impl<'a> WheelCache<'a> {
@@ -39,6 +43,10 @@ impl<'a> WheelCache<'a> {
                 .root()
                 .join(digest(&CanonicalUrl::new(url)))
                 .join(sha),
+            WheelCache::Mercurial(url, node) => WheelCacheKind::Mercurial
+                .root()
+                .join(digest(&CanonicalUrl::new(url)))
+                .join(node),
         }
     }
 
@@ -60,6 +68,8 @@ pub(crate) enum WheelCacheKind {
     Path,
     /// A cache of data from a Git repository.
     Git,
+    /// A cache of data from a Mercurial repository.
+    Mercurial,
 }
 
 impl WheelCacheKind {
@@ -70,6 +80,7 @@ impl WheelCacheKind {
             Self::Url => "url",
             Self::Path => "path",
             Self::Git => "git",
+            Self::Mercurial => "hg",
         }
     }
 
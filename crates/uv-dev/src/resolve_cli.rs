@@ -1,55 +1,153 @@
+use std::borrow::Cow;
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
-use anstream::println;
-use anyhow::{Context, Result};
+use anstream::{eprintln, print, println};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, ValueEnum};
 use fs_err::File;
+use indexmap::IndexMap;
 use itertools::Itertools;
-use petgraph::dot::{Config as DotConfig, Dot};
 
-use distribution_types::{FlatIndexLocation, IndexLocations, IndexUrl, Requirement, Resolution};
+use distribution_types::{
+    FlatIndexLocation, IndexLocations, IndexUrl, LocalEditable, LocalEditables, Requirement,
+    Requirements, SourceAnnotations,
+};
+use platform_tags::Tags;
 use uv_cache::{Cache, CacheArgs};
-use uv_client::{FlatIndexClient, RegistryClientBuilder};
-use uv_configuration::{Concurrency, ConfigSettings, NoBinary, NoBuild, SetupPyStrategy};
+use uv_client::{BaseClientBuilder, FlatIndexClient, RegistryClientBuilder};
+use uv_configuration::{
+    Concurrency, ConfigSettings, Constraints, NoBinary, NoBuild, Overrides, PreviewMode,
+    SetupPyStrategy,
+};
 use uv_dispatch::BuildDispatch;
 use uv_distribution::DistributionDatabase;
-use uv_installer::SitePackages;
-use uv_interpreter::PythonEnvironment;
+use uv_installer::{Downloader, SitePackages};
+use uv_interpreter::{PythonEnvironment, PythonVersion};
+use uv_requirements::{
+    ExtrasSpecification, NamedRequirementsResolver, RequirementsSource, RequirementsSpecification,
+};
 use uv_resolver::{
-    ExcludeNewer, FlatIndex, InMemoryIndex, Manifest, Options, PythonRequirement, Resolver,
+    AnnotationStyle, Diagnostic, DisplayResolutionGraph, DotOptions, ExcludeNewer,
+    ExcludeNewerPackage, Exclusions, FlatIndex, InMemoryIndex, Manifest, OptionsBuilder,
+    PythonRequirement, ResolutionMode, Resolver,
 };
 use uv_types::{BuildIsolation, HashStrategy, InFlight};
 
-#[derive(ValueEnum, Default, Clone)]
+#[derive(ValueEnum, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum ResolveCliFormat {
     #[default]
     Compact,
     Expanded,
+    /// Print the resolution as JSON, including the dependency edges between packages and the
+    /// messages for any diagnostics (as an empty array if there are none).
+    Json,
+}
+
+#[derive(ValueEnum, Default, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ResolveCliAnnotationStyle {
+    /// Render the annotations on a single, comma-separated line.
+    Line,
+    /// Render each annotation on its own line.
+    #[default]
+    Split,
+}
+
+impl From<ResolveCliAnnotationStyle> for AnnotationStyle {
+    fn from(style: ResolveCliAnnotationStyle) -> Self {
+        match style {
+            ResolveCliAnnotationStyle::Line => Self::Line,
+            ResolveCliAnnotationStyle::Split => Self::Split,
+        }
+    }
 }
 
 #[derive(Parser)]
 pub(crate) struct ResolveCliArgs {
     requirements: Vec<pep508_rs::Requirement>,
+    /// Include all packages listed in the given `requirements.txt` files, including any editables
+    /// (`-e`) and constraints (`-c`) referenced within them.
+    #[clap(long, short)]
+    requirement: Vec<PathBuf>,
+    /// Constrain versions using the given requirements files.
+    ///
+    /// Constraints files are `requirements.txt`-like files that only control the _version_ of a
+    /// requirement that's installed. However, including a package in a constraints file will _not_
+    /// trigger the installation of that package.
+    #[clap(long, short)]
+    constraint: Vec<PathBuf>,
+    /// Override versions using the given requirements files.
+    ///
+    /// Overrides files are `requirements.txt`-like files that force a specific version of a
+    /// requirement to be installed, regardless of the requirements declared by any constituent
+    /// package.
+    #[clap(long = "override")]
+    overrides: Vec<PathBuf>,
     /// Write debug output in DOT format for graphviz to this file
     #[clap(long)]
     graphviz: Option<PathBuf>,
+    /// Render editables as boxes and label conditional dependencies with their markers in the
+    /// `--graphviz` output.
+    #[clap(long, requires = "graphviz")]
+    graphviz_detailed: bool,
     /// Don't build source distributions. This means resolving will not run arbitrary code. The
     /// cached wheels of already built source distributions will be reused.
     #[clap(long)]
     no_build: bool,
-    #[clap(long, default_value = "compact")]
-    format: ResolveCliFormat,
+    /// The formats in which to print the resolution. Multiple formats may be provided (e.g.,
+    /// `--format compact,expanded`), in which case each is printed in turn, with the packages
+    /// listed in the same order.
+    #[clap(long, default_value = "compact", value_delimiter = ',')]
+    format: Vec<ResolveCliFormat>,
+    /// Exclude the comment annotations indicating the source of each package from the expanded
+    /// output and the output file.
+    #[clap(long)]
+    no_annotate: bool,
+    /// The style of the annotations indicating the source of each package.
+    #[clap(long, value_enum, default_value_t = ResolveCliAnnotationStyle::default())]
+    annotation_style: ResolveCliAnnotationStyle,
+    /// Write the resolution to this file in `requirements.txt` format, with annotations, in
+    /// addition to printing it.
+    #[clap(long, short)]
+    output_file: Option<PathBuf>,
+    /// Include distribution hashes in the output file.
+    #[clap(long)]
+    generate_hashes: bool,
     #[command(flatten)]
     cache_args: CacheArgs,
     #[arg(long)]
     exclude_newer: Option<ExcludeNewer>,
+    /// Exclude distributions of a specific package uploaded after the given date, overriding
+    /// `--exclude-newer` for that package (e.g., `flask=2023-06-01`).
+    #[arg(long)]
+    exclude_newer_package: Vec<ExcludeNewerPackage>,
+    /// The strategy to use when selecting between the different compatible versions for a given
+    /// package requirement.
+    #[clap(long, value_enum, default_value_t = ResolutionMode::default())]
+    strategy: ResolutionMode,
+    /// Resolve for the given Python version (e.g., `3.8`), rather than that of the active
+    /// interpreter.
+    #[clap(long)]
+    python_version: Option<PythonVersion>,
     #[clap(long, short, env = "UV_INDEX_URL")]
     index_url: Option<IndexUrl>,
     #[clap(long, env = "UV_EXTRA_INDEX_URL")]
     extra_index_url: Vec<IndexUrl>,
     #[clap(long)]
     find_links: Vec<FlatIndexLocation>,
+    /// Fail if the resolution contains more than this many packages.
+    #[clap(long)]
+    max_packages: Option<usize>,
+    /// Fail if the resolution includes any yanked packages, rather than warning.
+    #[clap(long)]
+    no_allow_yanked: bool,
+    /// List each diagnostic encountered during resolution, in addition to the summary.
+    #[clap(long, short)]
+    verbose: bool,
+    /// Print the marker tree under which the resolution is valid to stderr, to debug why a
+    /// resolution is considered platform-specific.
+    #[clap(long)]
+    show_marker_tree: bool,
 }
 
 pub(crate) async fn resolve_cli(args: ResolveCliArgs) -> Result<()> {
@@ -68,16 +166,38 @@ pub(crate) async fn resolve_cli(args: ResolveCliArgs) -> Result<()> {
     let client = RegistryClientBuilder::new(cache.clone())
         .index_urls(index_locations.index_urls())
         .build();
+    let hasher = if args.generate_hashes {
+        HashStrategy::Generate
+    } else {
+        HashStrategy::None
+    };
+
+    // Determine the tags and markers to use for resolution, applying the target Python version,
+    // if any.
+    let interpreter = venv.interpreter();
+    let (tags, markers) = if let Some(python_version) = args.python_version.as_ref() {
+        let tags = Tags::from_env(
+            interpreter.platform(),
+            (python_version.major(), python_version.minor()),
+            interpreter.implementation_name(),
+            interpreter.implementation_tuple(),
+            interpreter.gil_disabled(),
+        )?;
+        (
+            Cow::Owned(tags),
+            Cow::Owned(python_version.markers(interpreter.markers())),
+        )
+    } else {
+        (
+            Cow::Borrowed(interpreter.tags()?),
+            Cow::Borrowed(interpreter.markers()),
+        )
+    };
+
     let flat_index = {
         let client = FlatIndexClient::new(&client, &cache);
         let entries = client.fetch(index_locations.flat_index()).await?;
-        FlatIndex::from_entries(
-            entries,
-            venv.interpreter().tags()?,
-            &HashStrategy::None,
-            &no_build,
-            &NoBinary::None,
-        )
+        FlatIndex::from_entries(entries, &tags, &hasher, &no_build, &NoBinary::None)
     };
     let config_settings = ConfigSettings::default();
     let concurrency = Concurrency::default();
@@ -101,26 +221,151 @@ pub(crate) async fn resolve_cli(args: ResolveCliArgs) -> Result<()> {
 
     let site_packages = SitePackages::from_executable(&venv)?;
 
-    // Copied from `BuildDispatch`
-    let tags = venv.interpreter().tags()?;
-    let markers = venv.interpreter().markers();
-    let python_requirement =
-        PythonRequirement::from_marker_environment(venv.interpreter(), markers);
+    let python_requirement = PythonRequirement::from_marker_environment(interpreter, &markers);
+
+    // When resolving for a different Python version, use a separate index for the top-level
+    // resolution, since any source distributions are built against the installed interpreter.
+    let top_level_index = InMemoryIndex::default();
+    let top_level_index = if args.python_version.is_some() {
+        &top_level_index
+    } else {
+        &index
+    };
+
+    // Read any requirements, constraints, and overrides files. Requirements files may also
+    // contain editables and constraints.
+    let sources = args
+        .requirement
+        .iter()
+        .cloned()
+        .map(RequirementsSource::from_requirements_file)
+        .collect::<Vec<_>>();
+    let constraints = args
+        .constraint
+        .iter()
+        .cloned()
+        .map(RequirementsSource::from_constraints_txt)
+        .collect::<Vec<_>>();
+    let overrides = args
+        .overrides
+        .iter()
+        .cloned()
+        .map(RequirementsSource::from_overrides_txt)
+        .collect::<Vec<_>>();
+    let spec = RequirementsSpecification::from_sources(
+        &sources,
+        &constraints,
+        &overrides,
+        &ExtrasSpecification::None,
+        &BaseClientBuilder::new(),
+        PreviewMode::Disabled,
+    )
+    .await?;
+
+    // Merge the inline requirements with those from the requirements files, resolving the names
+    // of any unnamed requirements (e.g., `./path/to/project`).
+    let mut requirements = args
+        .requirements
+        .iter()
+        .cloned()
+        .map(Requirement::from_pep508)
+        .collect::<Result<Vec<_>, _>>()?;
+    requirements.extend(
+        NamedRequirementsResolver::new(
+            spec.requirements,
+            &hasher,
+            top_level_index,
+            DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
+        )
+        .with_cache(&cache)
+        .with_concurrency_limit(concurrency.downloads)
+        .resolve()
+        .await?,
+    );
+
+    // Resolve the names of any unnamed overrides.
+    let overrides = NamedRequirementsResolver::new(
+        spec.overrides,
+        &hasher,
+        top_level_index,
+        DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
+    )
+    .with_cache(&cache)
+    .with_concurrency_limit(concurrency.downloads)
+    .resolve()
+    .await?;
+
+    // Build any editables, to determine their requirements.
+    let editables = if spec.editables.is_empty() {
+        Vec::new()
+    } else {
+        let editables =
+            LocalEditables::from_editables(spec.editables.into_iter().map(|editable| {
+                LocalEditable {
+                    url: editable.url,
+                    path: editable.path,
+                    extras: editable.extras,
+                }
+            }));
+        let downloader = Downloader::new(
+            &cache,
+            &tags,
+            &hasher,
+            DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
+        );
+        let editable_wheel_dir = tempfile::tempdir_in(cache.root())?;
+        downloader
+            .build_editables(editables, editable_wheel_dir.path())
+            .await
+            .context("Failed to build editables")?
+            .into_iter()
+            .map(|built_editable| {
+                let requirements = Requirements {
+                    dependencies: built_editable
+                        .metadata
+                        .requires_dist
+                        .iter()
+                        .cloned()
+                        .map(Requirement::from_pep508)
+                        .collect::<Result<_, _>>()?,
+                    optional_dependencies: IndexMap::default(),
+                };
+                Ok::<_, anyhow::Error>((
+                    built_editable.editable,
+                    built_editable.metadata,
+                    requirements,
+                ))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let manifest = Manifest::new(
+        requirements.clone(),
+        Constraints::from_requirements(spec.constraints),
+        Overrides::from_requirements(overrides),
+        Vec::new(),
+        None,
+        editables,
+        Exclusions::default(),
+        Vec::new(),
+    );
+
+    // Retain a copy of the manifest to compute the marker tree after resolving.
+    let marker_tree_manifest = args.show_marker_tree.then(|| manifest.clone());
+
     let resolver = Resolver::new(
-        Manifest::simple(
-            args.requirements
-                .iter()
-                .cloned()
-                .map(Requirement::from_pep508)
-                .collect::<Result<_, _>>()?,
-        ),
-        Options::default(),
+        manifest,
+        OptionsBuilder::new()
+            .resolution_mode(args.strategy)
+            .exclude_newer(args.exclude_newer)
+            .exclude_newer_package(args.exclude_newer_package)
+            .build(),
         &python_requirement,
-        Some(venv.interpreter().markers()),
-        tags,
+        Some(&markers),
+        &tags,
         &flat_index,
-        &index,
-        &HashStrategy::None,
+        top_level_index,
+        &hasher,
         &build_dispatch,
         &site_packages,
         DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
@@ -128,35 +373,307 @@ pub(crate) async fn resolve_cli(args: ResolveCliArgs) -> Result<()> {
     let resolution_graph = resolver.resolve().await.with_context(|| {
         format!(
             "No solution found when resolving: {}",
-            args.requirements.iter().map(ToString::to_string).join(", "),
+            requirements.iter().map(ToString::to_string).join(", "),
         )
     })?;
 
+    // Summarize the resolution, including any diagnostics.
+    let diagnostics = resolution_graph.diagnostics();
+    eprintln!(
+        "Resolved {} package{} with {} warning{}",
+        resolution_graph.len(),
+        if resolution_graph.len() == 1 { "" } else { "s" },
+        diagnostics.len(),
+        if diagnostics.len() == 1 { "" } else { "s" },
+    );
+    if args.verbose {
+        for diagnostic in diagnostics {
+            eprintln!("warning: {}", diagnostic.message());
+        }
+    }
+
+    if let Some(manifest) = marker_tree_manifest {
+        let marker_tree = resolution_graph.marker_tree(&manifest, top_level_index, &markers)?;
+        eprintln!("Marker tree: {marker_tree}");
+    }
+
+    if args.no_allow_yanked {
+        let yanked = diagnostics
+            .iter()
+            .filter_map(|diagnostic| match diagnostic {
+                Diagnostic::YankedPackage { dist, .. } => Some(dist.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !yanked.is_empty() {
+            bail!(
+                "Resolution includes yanked packages: {}",
+                yanked.iter().join(", ")
+            );
+        }
+    }
+
+    if let Some(max_packages) = args.max_packages {
+        if resolution_graph.len() > max_packages {
+            bail!(
+                "Resolution contains {} packages, which exceeds the limit of {max_packages}",
+                resolution_graph.len()
+            );
+        }
+    }
+
     if let Some(graphviz) = args.graphviz {
         let mut writer = BufWriter::new(File::create(graphviz)?);
-        let graphviz = Dot::with_attr_getters(
-            resolution_graph.petgraph(),
-            &[DotConfig::NodeNoLabel, DotConfig::EdgeNoLabel],
-            &|_graph, edge_ref| format!("label={:?}", edge_ref.weight().to_string()),
-            &|_graph, (_node_index, dist)| {
-                format!("label={:?}", dist.to_string().replace("==", "\n"))
-            },
-        );
-        write!(&mut writer, "{graphviz:?}")?;
+        let options = if args.graphviz_detailed {
+            DotOptions {
+                editable_color: Some("blue".to_string()),
+                editable_shape: Some("box".to_string()),
+                edge_markers: true,
+                ..DotOptions::default()
+            }
+        } else {
+            DotOptions::default()
+        };
+        write!(
+            &mut writer,
+            "{}",
+            resolution_graph.to_dot_with_options(&options)
+        )?;
     }
 
-    let requirements = Resolution::from(resolution_graph).requirements();
+    if let Some(output_file) = args.output_file.as_ref() {
+        let output = format!(
+            "# This file was autogenerated by uv-dev via `resolve-cli`.\n{}",
+            DisplayResolutionGraph::new(
+                &resolution_graph,
+                &[],
+                args.generate_hashes,
+                false,
+                false,
+                !args.no_annotate,
+                false,
+                args.annotation_style.into(),
+                SourceAnnotations::default(),
+            )
+            .with_color(false)
+        );
+        // Write to a temporary file and rename it into place, such that an interrupted run doesn't
+        // truncate an existing output file.
+        uv_fs::write_atomic(output_file, output)
+            .await
+            .with_context(|| format!("Failed to write to `{}`", output_file.display()))?;
+    }
 
-    match args.format {
-        ResolveCliFormat::Compact => {
-            println!("{}", requirements.iter().map(ToString::to_string).join(" "));
-        }
-        ResolveCliFormat::Expanded => {
-            for package in requirements {
-                println!("{}", package);
+    // Print each requested format. Every format uses the default sort order, such that the
+    // packages are listed identically across formats.
+    for format in args.format.iter().copied().unique() {
+        match format {
+            ResolveCliFormat::Compact => {
+                // Render the bare requirements on a single line, omitting any annotations.
+                let display = DisplayResolutionGraph::new(
+                    &resolution_graph,
+                    &[],
+                    false,
+                    false,
+                    false,
+                    false,
+                    false,
+                    AnnotationStyle::default(),
+                    SourceAnnotations::default(),
+                );
+                println!("{}", display.to_string().lines().join(" "));
+            }
+            ResolveCliFormat::Expanded => {
+                let display = DisplayResolutionGraph::new(
+                    &resolution_graph,
+                    &[],
+                    false,
+                    false,
+                    false,
+                    !args.no_annotate,
+                    false,
+                    args.annotation_style.into(),
+                    SourceAnnotations::default(),
+                );
+                print!("{display}");
+            }
+            ResolveCliFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&resolution_graph.to_json())?
+                );
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use clap::Parser;
+
+    use uv_normalize::PackageName;
+    use uv_resolver::{ExcludeNewerMap, ResolutionMode};
+
+    use super::{ResolveCliAnnotationStyle, ResolveCliArgs, ResolveCliFormat};
+
+    #[test]
+    fn strategy() {
+        let args = ResolveCliArgs::try_parse_from(["resolve-cli", "flask"]).unwrap();
+        assert_eq!(args.strategy, ResolutionMode::Highest);
+
+        for (flag, mode) in [
+            ("highest", ResolutionMode::Highest),
+            ("lowest", ResolutionMode::Lowest),
+            ("lowest-direct", ResolutionMode::LowestDirect),
+        ] {
+            let args = ResolveCliArgs::try_parse_from(["resolve-cli", "flask", "--strategy", flag])
+                .unwrap();
+            assert_eq!(args.strategy, mode);
+        }
+    }
+
+    #[test]
+    fn constraints_and_overrides() {
+        let args = ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "black",
+            "--constraint",
+            "constraints.txt",
+            "-c",
+            "more-constraints.txt",
+            "--override",
+            "overrides.txt",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.constraint,
+            [
+                PathBuf::from("constraints.txt"),
+                PathBuf::from("more-constraints.txt")
+            ]
+        );
+        assert_eq!(args.overrides, [PathBuf::from("overrides.txt")]);
+    }
+
+    #[test]
+    fn annotations() {
+        let args = ResolveCliArgs::try_parse_from(["resolve-cli", "flask"]).unwrap();
+        assert!(!args.no_annotate);
+        assert_eq!(args.annotation_style, ResolveCliAnnotationStyle::Split);
+
+        let args = ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "flask",
+            "--no-annotate",
+            "--annotation-style",
+            "line",
+        ])
+        .unwrap();
+        assert!(args.no_annotate);
+        assert_eq!(args.annotation_style, ResolveCliAnnotationStyle::Line);
+    }
+
+    #[test]
+    fn exclude_newer_package() {
+        let args = ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "flask",
+            "--exclude-newer",
+            "2024-03-25",
+            "--exclude-newer-package",
+            "flask=2023-06-01",
+            "--exclude-newer-package",
+            "Werkzeug = 2023-01-01T00:00:00Z",
+        ])
+        .unwrap();
+        let exclude_newer = ExcludeNewerMap::new(args.exclude_newer, args.exclude_newer_package);
+        let cutoff = |name: &str| {
+            exclude_newer
+                .get(&PackageName::from_str(name).unwrap())
+                .map(ToString::to_string)
+        };
+        assert_eq!(cutoff("flask").as_deref(), Some("2023-06-02 00:00:00 UTC"));
+        assert_eq!(
+            cutoff("werkzeug").as_deref(),
+            Some("2023-01-01 00:00:00 UTC")
+        );
+        assert_eq!(cutoff("jinja2").as_deref(), Some("2024-03-26 00:00:00 UTC"));
+
+        assert!(ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "flask",
+            "--exclude-newer-package",
+            "flask"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn graphviz_detailed() {
+        let args = ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "flask",
+            "--graphviz",
+            "graph.dot",
+            "--graphviz-detailed",
+        ])
+        .unwrap();
+        assert!(args.graphviz_detailed);
+
+        assert!(
+            ResolveCliArgs::try_parse_from(["resolve-cli", "flask", "--graphviz-detailed"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn show_marker_tree() {
+        let args = ResolveCliArgs::try_parse_from(["resolve-cli", "flask"]).unwrap();
+        assert!(!args.show_marker_tree);
+
+        let args =
+            ResolveCliArgs::try_parse_from(["resolve-cli", "flask", "--show-marker-tree"]).unwrap();
+        assert!(args.show_marker_tree);
+    }
+
+    #[test]
+    fn formats() {
+        let args = ResolveCliArgs::try_parse_from(["resolve-cli", "flask"]).unwrap();
+        assert_eq!(args.format, [ResolveCliFormat::Compact]);
+
+        let args = ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "flask",
+            "--format",
+            "compact,expanded,json",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.format,
+            [
+                ResolveCliFormat::Compact,
+                ResolveCliFormat::Expanded,
+                ResolveCliFormat::Json
+            ]
+        );
+
+        let args = ResolveCliArgs::try_parse_from([
+            "resolve-cli",
+            "flask",
+            "--format",
+            "expanded",
+            "--format",
+            "compact",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.format,
+            [ResolveCliFormat::Expanded, ResolveCliFormat::Compact]
+        );
+    }
+}
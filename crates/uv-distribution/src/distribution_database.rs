@@ -1,6 +1,6 @@
 use std::future::Future;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -26,6 +26,7 @@ use uv_client::{
 use uv_configuration::{NoBinary, NoBuild};
 use uv_extract::hash::Hasher;
 use uv_fs::write_atomic;
+use uv_git::Fetch;
 use uv_types::BuildContext;
 
 use crate::archive::Archive;
@@ -421,6 +422,25 @@ impl<'a, Context: BuildContext> DistributionDatabase<'a, Context> {
         Ok(metadata)
     }
 
+    /// Fetch a Git repository, returning the checkout and the subdirectory (if any) within which
+    /// the source distribution lives.
+    ///
+    /// This enables callers to inspect the repository contents (e.g., to read static metadata)
+    /// without running a build.
+    pub async fn fetch_git(&self, url: &Url) -> Result<(Fetch, Option<PathBuf>), Error> {
+        self.builder.fetch_git(url).await
+    }
+
+    /// Clone a Mercurial repository, returning the path to the checkout and the subdirectory (if
+    /// any) within which the source distribution lives.
+    ///
+    /// Like [`DistributionDatabase::fetch_git`], this enables callers to inspect the repository
+    /// contents without running a build.
+    pub async fn fetch_hg(&self, url: &Url) -> Result<(PathBuf, Option<PathBuf>), Error> {
+        let (fetch, subdirectory) = self.builder.fetch_hg(url).await?;
+        Ok((fetch.path().to_path_buf(), subdirectory))
+    }
+
     /// Stream a wheel from a URL, unzipping it into the cache as it's downloaded.
     async fn stream_wheel(
         &self,
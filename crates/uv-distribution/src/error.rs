@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 use tokio::task::JoinError;
+use url::Url;
 use zip::result::ZipError;
 
 use distribution_filename::WheelFilenameError;
@@ -23,6 +24,8 @@ pub enum Error {
     JoinRelativeUrl(#[from] pypi_types::JoinRelativeError),
     #[error("Git operation failed")]
     Git(#[source] anyhow::Error),
+    #[error("Mercurial operation failed")]
+    Hg(#[source] anyhow::Error),
     #[error(transparent)]
     DirectUrl(#[from] Box<ParsedUrlError>),
     #[error(transparent)]
@@ -76,6 +79,8 @@ pub enum Error {
     DynamicPyprojectToml(#[source] pypi_types::MetadataError),
     #[error("Unsupported scheme in URL: {0}")]
     UnsupportedScheme(String),
+    #[error("Python scripts can't be built into distributions: {0}")]
+    UnsupportedScript(Url),
 
     /// A generic request middleware error happened while making a request.
     /// Refer to the error message for more details.
@@ -119,6 +124,9 @@ pub enum Error {
 
     #[error("Hash-checking is not supported for Git repositories: `{0}`")]
     HashesNotSupportedGit(String),
+
+    #[error("Hash-checking is not supported for Mercurial repositories: `{0}`")]
+    HashesNotSupportedHg(String),
 }
 
 impl From<reqwest::Error> for Error {
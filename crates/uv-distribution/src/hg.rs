@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use fs_err::tokio as fs;
+use tokio::process::Command;
+use tracing::debug;
+use url::Url;
+
+use cache_key::RepositoryUrl;
+use uv_cache::{Cache, CacheBucket};
+use uv_fs::LockedFile;
+
+use crate::error::Error;
+
+/// A Mercurial URL, split into the repository to clone, the revision to update to, and the
+/// subdirectory (if any) within which the source distribution lives.
+///
+/// For example, `hg+https://hg.example.com/repo@v1.0#subdirectory=pkg_dir` refers to the
+/// `https://hg.example.com/repo` repository, at the `v1.0` revision, in the `pkg_dir`
+/// subdirectory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParsedHgUrl {
+    pub(crate) repository: Url,
+    pub(crate) revision: Option<String>,
+    pub(crate) subdirectory: Option<PathBuf>,
+}
+
+impl TryFrom<&Url> for ParsedHgUrl {
+    type Error = Error;
+
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        let Some(inner) = url.as_str().strip_prefix("hg+") else {
+            return Err(Error::UnsupportedScheme(url.scheme().to_string()));
+        };
+        let mut repository = Url::parse(inner).map_err(|err| Error::Url(inner.to_string(), err))?;
+
+        // Extract the subdirectory from the fragment, e.g., `#subdirectory=pkg_dir`.
+        let subdirectory = repository.fragment().and_then(|fragment| {
+            fragment
+                .split('&')
+                .find_map(|fragment| fragment.strip_prefix("subdirectory="))
+                .map(PathBuf::from)
+        });
+        repository.set_fragment(None);
+
+        // Extract the revision from the path, e.g., `/repo@v1.0`.
+        let revision = if let Some((path, revision)) = repository.path().rsplit_once('@') {
+            let path = path.to_string();
+            let revision = revision.to_string();
+            repository.set_path(&path);
+            Some(revision)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            repository,
+            revision,
+            subdirectory,
+        })
+    }
+}
+
+/// A checkout of a Mercurial repository at a specific changeset.
+#[derive(Debug, Clone)]
+pub(crate) struct HgFetch {
+    /// The path to the checkout in the cache.
+    path: PathBuf,
+    /// The full changeset ID of the checkout.
+    node: String,
+}
+
+impl HgFetch {
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the abbreviated changeset ID of the checkout, for use in cache keys.
+    pub(crate) fn short_node(&self) -> &str {
+        &self.node[..self.node.len().min(16)]
+    }
+}
+
+/// Clone a Mercurial repository into the cache, returning the checkout and the subdirectory (if
+/// any) within which the source distribution lives.
+///
+/// Unlike Git, the repository is always cloned anew, since Mercurial revisions can't be resolved
+/// to a changeset without contacting the remote; however, checkouts are deduplicated by changeset.
+pub(crate) async fn fetch_hg_archive(
+    url: &Url,
+    cache: &Cache,
+) -> Result<(HgFetch, Option<PathBuf>), Error> {
+    debug!("Fetching source distribution from Mercurial: {url}");
+    let hg_dir = cache.bucket(CacheBucket::Mercurial);
+
+    let ParsedHgUrl {
+        repository,
+        revision,
+        subdirectory,
+    } = ParsedHgUrl::try_from(url)?;
+
+    // Avoid races between different processes, too.
+    let lock_dir = hg_dir.join("locks");
+    fs::create_dir_all(&lock_dir)
+        .await
+        .map_err(Error::CacheWrite)?;
+    let repository_url = RepositoryUrl::new(&repository);
+    let _lock = LockedFile::acquire(
+        lock_dir.join(cache_key::digest(&repository_url)),
+        &repository_url,
+    )
+    .map_err(Error::CacheWrite)?;
+
+    // Clone the repository into a temporary directory.
+    let temp_dir = tempfile::tempdir_in(&hg_dir).map_err(Error::CacheWrite)?;
+    let checkout = temp_dir.path().join("checkout");
+    let mut clone = Command::new("hg");
+    clone.arg("clone").arg("--quiet");
+    if let Some(revision) = revision.as_deref() {
+        clone.arg("--updaterev").arg(revision);
+    }
+    clone.arg(repository.as_str()).arg(&checkout);
+    run(clone).await.map_err(Error::Hg)?;
+
+    // Determine the changeset ID of the checkout.
+    let mut identify = Command::new("hg");
+    identify
+        .arg("log")
+        .arg("--rev")
+        .arg(".")
+        .arg("--template")
+        .arg("{node}")
+        .current_dir(&checkout);
+    let node = run(identify).await.map_err(Error::Hg)?.trim().to_string();
+
+    // Persist the checkout, unless a checkout of the same changeset already exists.
+    let path = hg_dir
+        .join("checkouts")
+        .join(cache_key::digest(&repository_url))
+        .join(&node);
+    if path.is_dir() {
+        debug!("Using existing Mercurial checkout at: {}", path.display());
+    } else {
+        fs::create_dir_all(path.parent().expect("checkout path has a parent"))
+            .await
+            .map_err(Error::CacheWrite)?;
+        fs::rename(&checkout, &path)
+            .await
+            .map_err(Error::CacheWrite)?;
+    }
+
+    Ok((HgFetch { path, node }, subdirectory))
+}
+
+/// Run an `hg` command, returning its standard output.
+async fn run(mut command: Command) -> anyhow::Result<String> {
+    let output = command
+        .output()
+        .await
+        .context("Failed to run `hg`; is Mercurial installed?")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`hg` exited with {status}:\n{stderr}",
+            status = output.status,
+            stderr = String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anyhow::Result;
+    use url::Url;
+
+    use super::ParsedHgUrl;
+
+    #[test]
+    fn parse_hg_url() -> Result<()> {
+        let url = Url::parse("hg+https://hg.example.com/repo")?;
+        assert_eq!(
+            ParsedHgUrl::try_from(&url)?,
+            ParsedHgUrl {
+                repository: Url::parse("https://hg.example.com/repo")?,
+                revision: None,
+                subdirectory: None,
+            }
+        );
+
+        let url = Url::parse("hg+http://hg.example.com/repo@v1.0#subdirectory=pkg_dir")?;
+        assert_eq!(
+            ParsedHgUrl::try_from(&url)?,
+            ParsedHgUrl {
+                repository: Url::parse("http://hg.example.com/repo")?,
+                revision: Some("v1.0".to_string()),
+                subdirectory: Some(PathBuf::from("pkg_dir")),
+            }
+        );
+
+        let url = Url::parse("https://hg.example.com/repo")?;
+        assert!(ParsedHgUrl::try_from(&url).is_err());
+
+        Ok(())
+    }
+}
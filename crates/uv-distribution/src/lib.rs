@@ -13,6 +13,7 @@ mod distribution_database;
 mod download;
 mod error;
 mod git;
+mod hg;
 mod index;
 mod locks;
 mod reporter;
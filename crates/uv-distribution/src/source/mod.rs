@@ -18,7 +18,7 @@ use distribution_filename::WheelFilename;
 use distribution_types::{
     BuildableSource, DirectorySourceDist, DirectorySourceUrl, Dist, FileLocation, GitSourceUrl,
     HashPolicy, Hashed, LocalEditable, ParsedArchiveUrl, PathSourceUrl, RemoteSource, SourceDist,
-    SourceUrl,
+    SourceUrl, Vcs, VcsSourceUrl,
 };
 use install_wheel_rs::metadata::read_archive_metadata;
 use platform_tags::Tags;
@@ -33,11 +33,13 @@ use uv_client::{
 use uv_configuration::{BuildKind, NoBuild};
 use uv_extract::hash::Hasher;
 use uv_fs::{write_atomic, LockedFile};
+use uv_git::Fetch;
 use uv_types::{BuildContext, SourceBuildTrait};
 
 use crate::distribution_database::ManagedClient;
 use crate::error::Error;
 use crate::git::{fetch_git_archive, resolve_precise};
+use crate::hg::{fetch_hg_archive, HgFetch};
 use crate::source::built_wheel_metadata::BuiltWheelMetadata;
 use crate::source::revision::Revision;
 use crate::{ArchiveMetadata, Reporter};
@@ -78,6 +80,27 @@ impl<'a, T: BuildContext> SourceDistributionBuilder<'a, T> {
         }
     }
 
+    /// Fetch a Git repository at a precise commit, returning the checkout and the subdirectory
+    /// (if any) within which the source distribution lives.
+    pub(super) async fn fetch_git(&self, url: &Url) -> Result<(Fetch, Option<PathBuf>), Error> {
+        // Resolve to a precise Git SHA.
+        let url = if let Some(url) =
+            resolve_precise(url, self.build_context.cache(), self.reporter.as_ref()).await?
+        {
+            Cow::Owned(url)
+        } else {
+            Cow::Borrowed(url)
+        };
+
+        fetch_git_archive(&url, self.build_context.cache(), self.reporter.as_ref()).await
+    }
+
+    /// Clone a Mercurial repository, returning the checkout and the subdirectory (if any) within
+    /// which the source distribution lives.
+    pub(super) async fn fetch_hg(&self, url: &Url) -> Result<(HgFetch, Option<PathBuf>), Error> {
+        fetch_hg_archive(url, self.build_context.cache()).await
+    }
+
     /// Download and build a [`SourceDist`].
     pub(super) async fn download_and_build(
         &self,
@@ -216,6 +239,11 @@ impl<'a, T: BuildContext> SourceDistributionBuilder<'a, T> {
                     .boxed_local()
                     .await?
             }
+            BuildableSource::Url(SourceUrl::Vcs(resource)) => {
+                self.vcs(source, resource, tags, hashes)
+                    .boxed_local()
+                    .await?
+            }
             BuildableSource::Url(SourceUrl::Directory(resource)) => {
                 self.source_tree(source, resource, tags, hashes)
                     .boxed_local()
@@ -230,6 +258,9 @@ impl<'a, T: BuildContext> SourceDistributionBuilder<'a, T> {
                     .boxed_local()
                     .await?
             }
+            BuildableSource::Url(SourceUrl::Script(resource)) => {
+                return Err(Error::UnsupportedScript(resource.url.clone()));
+            }
         };
 
         Ok(built_wheel_metadata)
@@ -363,6 +394,11 @@ impl<'a, T: BuildContext> SourceDistributionBuilder<'a, T> {
                     .boxed_local()
                     .await?
             }
+            BuildableSource::Url(SourceUrl::Vcs(resource)) => {
+                self.vcs_metadata(source, resource, hashes)
+                    .boxed_local()
+                    .await?
+            }
             BuildableSource::Url(SourceUrl::Directory(resource)) => {
                 self.source_tree_metadata(source, resource, hashes)
                     .boxed_local()
@@ -378,6 +414,10 @@ impl<'a, T: BuildContext> SourceDistributionBuilder<'a, T> {
                     .boxed_local()
                     .await?
             }
+
+            BuildableSource::Url(SourceUrl::Script(resource)) => {
+                return Err(Error::UnsupportedScript(resource.url.clone()));
+            }
         };
 
         Ok(metadata)
@@ -1176,6 +1216,147 @@ impl<'a, T: BuildContext> SourceDistributionBuilder<'a, T> {
         Ok(ArchiveMetadata::from(metadata))
     }
 
+    /// Build a source distribution from a non-Git version control system.
+    async fn vcs(
+        &self,
+        source: &BuildableSource<'_>,
+        resource: &VcsSourceUrl<'_>,
+        tags: &Tags,
+        hashes: HashPolicy<'_>,
+    ) -> Result<BuiltWheelMetadata, Error> {
+        // Before running the build, check that the hashes match.
+        if hashes.is_validate() {
+            return Err(Error::HashesNotSupportedHg(source.to_string()));
+        }
+
+        // Clone the repository.
+        let (fetch, subdirectory) = match resource.vcs {
+            Vcs::Mercurial => fetch_hg_archive(resource.url, self.build_context.cache()).await?,
+        };
+
+        let cache_shard = self.build_context.cache().shard(
+            CacheBucket::BuiltWheels,
+            WheelCache::Mercurial(resource.url, fetch.short_node()).root(),
+        );
+
+        let _lock = lock_shard(&cache_shard).await?;
+
+        // If the cache contains a compatible wheel, return it.
+        if let Some(built_wheel) = BuiltWheelMetadata::find_in_cache(tags, &cache_shard) {
+            return Ok(built_wheel);
+        }
+
+        let task = self
+            .reporter
+            .as_ref()
+            .map(|reporter| reporter.on_build_start(source));
+
+        let (disk_filename, filename, metadata) = self
+            .build_distribution(source, fetch.path(), subdirectory.as_deref(), &cache_shard)
+            .await?;
+
+        if let Some(task) = task {
+            if let Some(reporter) = self.reporter.as_ref() {
+                reporter.on_build_complete(source, task);
+            }
+        }
+
+        // Store the metadata.
+        let metadata_entry = cache_shard.entry(METADATA);
+        write_atomic(metadata_entry.path(), rmp_serde::to_vec(&metadata)?)
+            .await
+            .map_err(Error::CacheWrite)?;
+
+        Ok(BuiltWheelMetadata {
+            path: cache_shard.join(&disk_filename),
+            target: cache_shard.join(filename.stem()),
+            filename,
+            hashes: vec![],
+        })
+    }
+
+    /// Build the source distribution's metadata from a non-Git version control system.
+    ///
+    /// If the build backend supports `prepare_metadata_for_build_wheel`, this method will avoid
+    /// building the wheel.
+    async fn vcs_metadata(
+        &self,
+        source: &BuildableSource<'_>,
+        resource: &VcsSourceUrl<'_>,
+        hashes: HashPolicy<'_>,
+    ) -> Result<ArchiveMetadata, Error> {
+        // Before running the build, check that the hashes match.
+        if hashes.is_validate() {
+            return Err(Error::HashesNotSupportedHg(source.to_string()));
+        }
+
+        // Clone the repository.
+        let (fetch, subdirectory) = match resource.vcs {
+            Vcs::Mercurial => fetch_hg_archive(resource.url, self.build_context.cache()).await?,
+        };
+
+        let cache_shard = self.build_context.cache().shard(
+            CacheBucket::BuiltWheels,
+            WheelCache::Mercurial(resource.url, fetch.short_node()).root(),
+        );
+
+        let _lock = lock_shard(&cache_shard).await?;
+
+        // If the cache contains compatible metadata, return it.
+        let metadata_entry = cache_shard.entry(METADATA);
+        if self
+            .build_context
+            .cache()
+            .freshness(&metadata_entry, source.name())
+            .is_ok_and(Freshness::is_fresh)
+        {
+            if let Some(metadata) = read_cached_metadata(&metadata_entry).await? {
+                debug!("Using cached metadata for: {source}");
+                return Ok(ArchiveMetadata::from(metadata));
+            }
+        }
+
+        // If the backend supports `prepare_metadata_for_build_wheel`, use it.
+        if let Some(metadata) = self
+            .build_metadata(source, fetch.path(), subdirectory.as_deref())
+            .boxed_local()
+            .await?
+        {
+            // Store the metadata.
+            fs::create_dir_all(metadata_entry.dir())
+                .await
+                .map_err(Error::CacheWrite)?;
+            write_atomic(metadata_entry.path(), rmp_serde::to_vec(&metadata)?)
+                .await
+                .map_err(Error::CacheWrite)?;
+
+            return Ok(ArchiveMetadata::from(metadata));
+        }
+
+        // Otherwise, we need to build a wheel.
+        let task = self
+            .reporter
+            .as_ref()
+            .map(|reporter| reporter.on_build_start(source));
+
+        let (_disk_filename, _filename, metadata) = self
+            .build_distribution(source, fetch.path(), subdirectory.as_deref(), &cache_shard)
+            .await?;
+
+        if let Some(task) = task {
+            if let Some(reporter) = self.reporter.as_ref() {
+                reporter.on_build_complete(source, task);
+            }
+        }
+
+        // Store the metadata.
+        write_atomic(metadata_entry.path(), rmp_serde::to_vec(&metadata)?)
+            .await
+            .map_err(Error::CacheWrite)?;
+
+        Ok(ArchiveMetadata::from(metadata))
+    }
+
     /// Download and unzip a source distribution into the cache from an HTTP response.
     async fn download_archive(
         &self,
@@ -1,26 +1,63 @@
 use std::borrow::Cow;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::SystemTime;
 
 use anyhow::Result;
 use configparser::ini::Ini;
-use futures::{stream::FuturesOrdered, TryStreamExt};
+use futures::{StreamExt, TryStreamExt};
 use serde::Deserialize;
-use tracing::debug;
+use thiserror::Error;
+use tracing::{debug, warn};
+use url::Url;
 
-use distribution_filename::{SourceDistFilename, WheelFilename};
+use cache_key::digest;
+use distribution_filename::{
+    SourceDistExtension, SourceDistFilename, WheelFilename, WheelFilenameError,
+};
 use distribution_types::{
     BuildableSource, DirectSourceUrl, DirectorySourceUrl, GitSourceUrl, PathSourceUrl,
-    RemoteSource, Requirement, SourceUrl, UnresolvedRequirement,
-    UnresolvedRequirementSpecification, VersionId,
+    RemoteSource, Requirement, ScriptSourceUrl, SourceUrl, UnresolvedRequirement,
+    UnresolvedRequirementSpecification, Vcs, VcsSourceUrl, VersionId,
 };
-use pep508_rs::{Scheme, UnnamedRequirement, VersionOrUrl};
-use pypi_types::Metadata10;
-use uv_distribution::{DistributionDatabase, Reporter};
-use uv_normalize::PackageName;
+use pep508_rs::{Scheme, UnnamedRequirement, VerbatimUrl, VersionOrUrl};
+use pypi_types::{Metadata10, Metadata23, MetadataError};
+use uv_cache::{Cache, CacheBucket, CacheEntry, Refresh, WheelCache};
+use uv_configuration::Concurrency;
+use uv_distribution::{ArchiveMetadata, DistributionDatabase, Reporter};
+use uv_fs::Simplified;
+use uv_normalize::{InvalidNameError, PackageName};
 use uv_resolver::{InMemoryIndex, MetadataResponse};
 use uv_types::{BuildContext, HashStrategy};
 
+#[derive(Debug, Error)]
+pub enum NamedRequirementError {
+    #[error("Failed to determine the package name for: {0}")]
+    Requirement(VerbatimUrl, #[source] Box<NamedRequirementError>),
+    #[error("Unsupported scheme for unnamed requirement: {0}")]
+    UnsupportedScheme(VerbatimUrl),
+    #[error("Failed to parse wheel filename: {0}")]
+    FilenameParse(VerbatimUrl, #[source] WheelFilenameError),
+    #[error("Failed to fetch: {0}")]
+    Fetch(VerbatimUrl, #[source] uv_distribution::Error),
+    #[error("Failed to build metadata for: {0}")]
+    MetadataBuild(VerbatimUrl, #[source] uv_distribution::Error),
+    #[error("Invalid package name in `{}`", _0.user_display())]
+    InvalidPackageName(PathBuf, #[source] InvalidNameError),
+    #[error("Failed to parse inline script metadata in `{}`", _0.user_display())]
+    ScriptMetadata(PathBuf, #[source] MetadataError),
+    #[error("Found multiple `.dist-info` directories in `{}`, so the package name is ambiguous: {}", _0.user_display(), _1)]
+    AmbiguousDistInfo(PathBuf, String),
+    #[error(transparent)]
+    UnsupportedUrl(#[from] distribution_types::Error),
+    #[error(transparent)]
+    InvalidRequirement(#[from] Box<distribution_types::ParsedUrlError>),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
 /// Like [`RequirementsSpecification`], but with concrete names for all requirements.
 pub struct NamedRequirementsResolver<'a, Context: BuildContext> {
     /// The requirements for the project.
@@ -31,6 +68,12 @@ pub struct NamedRequirementsResolver<'a, Context: BuildContext> {
     index: &'a InMemoryIndex,
     /// The database for fetching and building distributions.
     database: DistributionDatabase<'a, Context>,
+    /// The refresh policy, used to bypass any metadata already present in the index.
+    refresh: Refresh,
+    /// The cache in which to persist the metadata of local archives across invocations, if any.
+    cache: Option<&'a Cache>,
+    /// The maximum number of requirements to resolve at once.
+    concurrency_limit: usize,
 }
 
 impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
@@ -46,6 +89,47 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
             hasher,
             index,
             database,
+            refresh: Refresh::None,
+            cache: None,
+            concurrency_limit: Concurrency::DEFAULT_DOWNLOADS,
+        }
+    }
+
+    /// Set the [`Refresh`] policy to use for this resolver.
+    ///
+    /// Requirements whose inferred name is marked for refresh bypass any metadata that's already
+    /// present in the in-memory index, forcing the metadata to be fetched or built again. Entries
+    /// in the on-disk cache are governed by the refresh policy of the underlying cache.
+    #[must_use]
+    pub fn with_refresh(self, refresh: Refresh) -> Self {
+        Self { refresh, ..self }
+    }
+
+    /// Set the [`Cache`] in which to persist the metadata of local archives.
+    ///
+    /// Metadata that's built for a local archive is stored on disk, keyed by the source URL and a
+    /// digest of the archive, such that subsequent invocations can infer the package name without
+    /// running the build backend again.
+    #[must_use]
+    pub fn with_cache(self, cache: &'a Cache) -> Self {
+        Self {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of unnamed requirements to resolve at once.
+    ///
+    /// Resolving a requirement may require fetching or building its metadata, so this bounds the
+    /// number of concurrent requests (e.g., to the same index server). Builds are further limited
+    /// by the build context. Defaults to [`Concurrency::DEFAULT_DOWNLOADS`].
+    ///
+    /// A limit of zero is treated as one, such that requirements are resolved sequentially.
+    #[must_use]
+    pub fn with_concurrency_limit(self, limit: usize) -> Self {
+        Self {
+            concurrency_limit: limit.max(1),
+            ..self
         }
     }
 
@@ -59,24 +143,42 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
     }
 
     /// Resolve any unnamed requirements in the specification.
-    pub async fn resolve(self) -> Result<Vec<Requirement>> {
+    ///
+    /// If the name of any requirement can't be determined, returns a
+    /// [`NamedRequirementError::Requirement`] wrapping the underlying error.
+    pub async fn resolve(self) -> Result<Vec<Requirement>, NamedRequirementError> {
         let Self {
             requirements,
             hasher,
             index,
             database,
+            refresh,
+            cache,
+            concurrency_limit,
         } = self;
-        requirements
-            .into_iter()
+        futures::stream::iter(requirements)
             .map(|entry| async {
                 match entry.requirement {
                     UnresolvedRequirement::Named(requirement) => Ok(requirement),
-                    UnresolvedRequirement::Unnamed(requirement) => Ok(Requirement::from_pep508(
-                        Self::resolve_requirement(requirement, hasher, index, &database).await?,
-                    )?),
+                    UnresolvedRequirement::Unnamed(requirement) => {
+                        let url = requirement.url.clone();
+                        let requirement = Self::resolve_requirement(
+                            requirement,
+                            hasher,
+                            index,
+                            &database,
+                            &refresh,
+                            cache,
+                        )
+                        .await
+                        .map_err(|err| NamedRequirementError::Requirement(url, Box::new(err)))?;
+                        Ok(Requirement::from_pep508(requirement)?)
+                    }
                 }
             })
-            .collect::<FuturesOrdered<_>>()
+            // Preserve the order of the requirements, which determines the order in which they're
+            // visited during resolution.
+            .buffered(concurrency_limit)
             .try_collect()
             .await
     }
@@ -87,7 +189,9 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
         hasher: &HashStrategy,
         index: &InMemoryIndex,
         database: &DistributionDatabase<'a, Context>,
-    ) -> Result<pep508_rs::Requirement> {
+        refresh: &Refresh,
+        cache: Option<&Cache>,
+    ) -> Result<pep508_rs::Requirement, NamedRequirementError> {
         // If the requirement is a wheel, extract the package name from the wheel filename.
         //
         // Ex) `anyio-4.3.0-py3-none-any.whl`
@@ -95,9 +199,29 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
             .extension()
             .is_some_and(|ext| ext.eq_ignore_ascii_case("whl"))
         {
-            let filename = WheelFilename::from_str(&requirement.url.filename()?)?;
+            let name = match WheelFilename::from_str(&requirement.url.filename()?) {
+                Ok(filename) => filename.name,
+                Err(err) => {
+                    // If the wheel is available locally, fall back to reading the name from the
+                    // `METADATA` within it, e.g., for wheels that were renamed.
+                    //
+                    // Ex) `file:///home/ferris/downloads/anyio.whl`
+                    let name =
+                        matches!(Scheme::parse(requirement.url.scheme()), Some(Scheme::File))
+                            .then(|| requirement.url.to_file_path().ok())
+                            .flatten()
+                            .and_then(|path| read_wheel_name(&path));
+                    let Some(name) = name else {
+                        return Err(NamedRequirementError::FilenameParse(
+                            requirement.url.clone(),
+                            err,
+                        ));
+                    };
+                    name
+                }
+            };
             return Ok(pep508_rs::Requirement {
-                name: filename.name,
+                name,
                 extras: requirement.extras,
                 version_or_url: Some(VersionOrUrl::Url(requirement.url)),
                 marker: requirement.marker,
@@ -131,20 +255,20 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
                     .to_file_path()
                     .expect("URL to be a file path");
 
+                // If the URL includes a `subdirectory` fragment, the project lives in a subfolder
+                // of the given directory.
+                //
+                // Ex) `file:///home/ferris/monorepo#subdirectory=packages/foo`
+                let path = match fragment_parameter(&requirement.url, "subdirectory") {
+                    Some(subdirectory) if path.is_dir() => path.join(subdirectory),
+                    _ => path,
+                };
+
                 // If the path points to a directory, attempt to read the name from static metadata.
                 if path.is_dir() {
-                    // Attempt to read a `PKG-INFO` from the directory.
-                    if let Some(metadata) = fs_err::read(path.join("PKG-INFO"))
-                        .ok()
-                        .and_then(|contents| Metadata10::parse_pkg_info(&contents).ok())
-                    {
-                        debug!(
-                            "Found PKG-INFO metadata for {path} ({name})",
-                            path = path.display(),
-                            name = metadata.name
-                        );
+                    if let Some(name) = read_static_name(&path)? {
                         return Ok(pep508_rs::Requirement {
-                            name: metadata.name,
+                            name,
                             extras: requirement.extras,
                             version_or_url: Some(VersionOrUrl::Url(requirement.url)),
                             marker: requirement.marker,
@@ -152,83 +276,38 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
                         });
                     }
 
-                    // Attempt to read a `pyproject.toml` file.
-                    let project_path = path.join("pyproject.toml");
-                    if let Some(pyproject) = fs_err::read_to_string(project_path)
-                        .ok()
-                        .and_then(|contents| toml::from_str::<PyProjectToml>(&contents).ok())
-                    {
-                        // Read PEP 621 metadata from the `pyproject.toml`.
-                        if let Some(project) = pyproject.project {
-                            debug!(
-                                "Found PEP 621 metadata for {path} in `pyproject.toml` ({name})",
-                                path = path.display(),
-                                name = project.name
-                            );
-                            return Ok(pep508_rs::Requirement {
-                                name: project.name,
-                                extras: requirement.extras,
-                                version_or_url: Some(VersionOrUrl::Url(requirement.url)),
-                                marker: requirement.marker,
-                                origin: requirement.origin,
-                            });
-                        }
-
-                        // Read Poetry-specific metadata from the `pyproject.toml`.
-                        if let Some(tool) = pyproject.tool {
-                            if let Some(poetry) = tool.poetry {
-                                if let Some(name) = poetry.name {
-                                    debug!(
-                                        "Found Poetry metadata for {path} in `pyproject.toml` ({name})",
-                                        path = path.display(),
-                                        name = name
-                                    );
-                                    return Ok(pep508_rs::Requirement {
-                                        name,
-                                        extras: requirement.extras,
-                                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
-                                        marker: requirement.marker,
-                                        origin: requirement.origin,
-                                    });
-                                }
-                            }
-                        }
-                    }
-
-                    // Attempt to read a `setup.cfg` from the directory.
-                    if let Some(setup_cfg) = fs_err::read_to_string(path.join("setup.cfg"))
-                        .ok()
-                        .and_then(|contents| {
-                            let mut ini = Ini::new_cs();
-                            ini.set_multiline(true);
-                            ini.read(contents).ok()
-                        })
-                    {
-                        if let Some(section) = setup_cfg.get("metadata") {
-                            if let Some(Some(name)) = section.get("name") {
-                                if let Ok(name) = PackageName::from_str(name) {
-                                    debug!(
-                                        "Found setuptools metadata for {path} in `setup.cfg` ({name})",
-                                        path = path.display(),
-                                        name = name
-                                    );
-                                    return Ok(pep508_rs::Requirement {
-                                        name,
-                                        extras: requirement.extras,
-                                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
-                                        marker: requirement.marker,
-                                        origin: requirement.origin,
-                                    });
-                                }
-                            }
-                        }
-                    }
-
                     SourceUrl::Directory(DirectorySourceUrl {
                         url: &requirement.url,
                         path: Cow::Owned(path),
                     })
+                } else if path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("py"))
+                {
+                    // If the path points to a Python script, read its dependencies from the inline
+                    // script metadata (PEP 723), naming it after the file.
+                    //
+                    // Ex) `file:///home/ferris/scripts/example.py`
+                    SourceUrl::Script(ScriptSourceUrl {
+                        url: &requirement.url,
+                        path: Cow::Owned(path),
+                    })
                 } else {
+                    // If the path points to an archive, attempt to read the name from the
+                    // `PKG-INFO` within it, e.g., for archives whose filenames don't follow the
+                    // source distribution naming conventions.
+                    //
+                    // Ex) `file:///home/ferris/downloads/project.zip`
+                    if let Some(name) = read_archive_name(&path, cache).await {
+                        return Ok(pep508_rs::Requirement {
+                            name,
+                            extras: requirement.extras,
+                            version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                            marker: requirement.marker,
+                            origin: requirement.origin,
+                        });
+                    }
+
                     SourceUrl::Path(PathSourceUrl {
                         url: &requirement.url,
                         path: Cow::Owned(path),
@@ -238,34 +317,165 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
             Some(Scheme::Http | Scheme::Https) => SourceUrl::Direct(DirectSourceUrl {
                 url: &requirement.url,
             }),
-            Some(Scheme::GitSsh | Scheme::GitHttps) => SourceUrl::Git(GitSourceUrl {
-                url: &requirement.url,
-            }),
+            Some(Scheme::GitSsh | Scheme::GitHttps) => {
+                // If the URL includes an `egg` fragment, use it as the package name, as in pip.
+                //
+                // Ex) `git+https://github.com/pallets/flask.git#egg=flask`
+                if let Some(name) = egg_fragment_name(&requirement.url) {
+                    return Ok(pep508_rs::Requirement {
+                        name,
+                        extras: requirement.extras,
+                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                        marker: requirement.marker,
+                        origin: requirement.origin,
+                    });
+                }
+
+                // If the metadata is already in the index, avoid checking out the repository.
+                if let Some(name) = indexed_name(index, &requirement.url, refresh) {
+                    return Ok(pep508_rs::Requirement {
+                        name,
+                        extras: requirement.extras,
+                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                        marker: requirement.marker,
+                        origin: requirement.origin,
+                    });
+                }
+
+                // Check out the repository, and attempt to read the name from static metadata in
+                // the (optional) subdirectory, e.g., for monorepos in which the package lives in a
+                // subfolder of the repository.
+                let (fetch, subdirectory) = database
+                    .fetch_git(&requirement.url)
+                    .await
+                    .map_err(|err| NamedRequirementError::Fetch(requirement.url.clone(), err))?;
+                let path = match subdirectory {
+                    Some(subdirectory) => fetch.path().join(subdirectory),
+                    None => fetch.path().to_path_buf(),
+                };
+                if let Some(name) = read_static_name(&path)? {
+                    return Ok(pep508_rs::Requirement {
+                        name,
+                        extras: requirement.extras,
+                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                        marker: requirement.marker,
+                        origin: requirement.origin,
+                    });
+                }
+
+                SourceUrl::Git(GitSourceUrl {
+                    url: &requirement.url,
+                })
+            }
+            Some(
+                Scheme::HgFile
+                | Scheme::HgHttp
+                | Scheme::HgHttps
+                | Scheme::HgSsh
+                | Scheme::HgStaticHttp,
+            ) => {
+                // If the URL includes an `egg` fragment, use it as the package name, as in pip.
+                //
+                // Ex) `hg+https://hg.example.com/repo#egg=flask`
+                if let Some(name) = egg_fragment_name(&requirement.url) {
+                    return Ok(pep508_rs::Requirement {
+                        name,
+                        extras: requirement.extras,
+                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                        marker: requirement.marker,
+                        origin: requirement.origin,
+                    });
+                }
+
+                // If the metadata is already in the index, avoid cloning the repository.
+                if let Some(name) = indexed_name(index, &requirement.url, refresh) {
+                    return Ok(pep508_rs::Requirement {
+                        name,
+                        extras: requirement.extras,
+                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                        marker: requirement.marker,
+                        origin: requirement.origin,
+                    });
+                }
+
+                // Clone the repository, and attempt to read the name from static metadata in the
+                // (optional) subdirectory, as for Git repositories.
+                let (path, subdirectory) = database
+                    .fetch_hg(&requirement.url)
+                    .await
+                    .map_err(|err| NamedRequirementError::Fetch(requirement.url.clone(), err))?;
+                let path = match subdirectory {
+                    Some(subdirectory) => path.join(subdirectory),
+                    None => path,
+                };
+                if let Some(name) = read_static_name(&path)? {
+                    return Ok(pep508_rs::Requirement {
+                        name,
+                        extras: requirement.extras,
+                        version_or_url: Some(VersionOrUrl::Url(requirement.url)),
+                        marker: requirement.marker,
+                        origin: requirement.origin,
+                    });
+                }
+
+                SourceUrl::Vcs(VcsSourceUrl {
+                    url: &requirement.url,
+                    vcs: Vcs::Mercurial,
+                })
+            }
             _ => {
-                return Err(anyhow::anyhow!(
-                    "Unsupported scheme for unnamed requirement: {}",
-                    requirement.url
-                ));
+                return Err(NamedRequirementError::UnsupportedScheme(requirement.url));
             }
         };
 
         // Fetch the metadata for the distribution.
         let name = {
             let id = VersionId::from_url(source.url());
-            if let Some(archive) = index.get_metadata(&id).as_deref().and_then(|response| {
-                if let MetadataResponse::Found(archive) = response {
-                    Some(archive)
-                } else {
-                    None
-                }
-            }) {
+            if let Some(name) = indexed_name(index, source.url(), refresh) {
                 // If the metadata is already in the index, return it.
-                archive.metadata.name.clone()
+                name
             } else {
-                // Run the PEP 517 build process to extract metadata from the source distribution.
                 let hashes = hasher.get_url(source.url());
-                let source = BuildableSource::Url(source);
-                let archive = database.build_wheel_metadata(&source, hashes).await?;
+
+                // When validating hashes, the archive itself must be fetched, so the on-disk cache
+                // (which only stores the metadata) is bypassed.
+                let entry = cache
+                    .filter(|_| hashes.is_none())
+                    .and_then(|cache| metadata_entry(cache, &source));
+
+                let archive = if let SourceUrl::Script(script) = &source {
+                    // Scripts can't be built, but declare their metadata inline.
+                    ArchiveMetadata::from(read_script_metadata(&script.path)?)
+                } else if let Some(metadata) = entry
+                    .as_ref()
+                    .and_then(|entry| read_cached_metadata(entry, refresh))
+                {
+                    debug!(
+                        "Found cached metadata for {source} ({name})",
+                        name = metadata.name
+                    );
+                    ArchiveMetadata::from(metadata)
+                } else {
+                    // Run the PEP 517 build process to extract metadata from the source
+                    // distribution.
+                    let source = BuildableSource::Url(source);
+                    let archive = database
+                        .build_wheel_metadata(&source, hashes)
+                        .await
+                        .map_err(|err| {
+                            NamedRequirementError::MetadataBuild(requirement.url.clone(), err)
+                        })?;
+
+                    // Persist the metadata for subsequent invocations. Failing to do so isn't
+                    // fatal, since the metadata can always be built again.
+                    if let Some(entry) = entry.as_ref() {
+                        if let Err(err) = write_cached_metadata(entry, &archive.metadata).await {
+                            debug!("Failed to cache metadata for {source}: {err}");
+                        }
+                    }
+
+                    archive
+                };
 
                 let name = archive.metadata.name.clone();
 
@@ -286,6 +496,480 @@ impl<'a, Context: BuildContext> NamedRequirementsResolver<'a, Context> {
     }
 }
 
+/// Return the name of the distribution at the given URL, if its metadata is already present in the
+/// in-memory index and isn't due to be refreshed.
+fn indexed_name(index: &InMemoryIndex, url: &Url, refresh: &Refresh) -> Option<PackageName> {
+    let response = index.get_metadata(&VersionId::from_url(url))?;
+    let MetadataResponse::Found(archive) = &*response else {
+        return None;
+    };
+    Some(archive.metadata.name.clone()).filter(|name| !is_refresh(refresh, name))
+}
+
+/// Return the on-disk cache entry for the metadata of the given source, keyed by its URL, size, and
+/// modification time, or `None` if the source isn't a local archive.
+///
+/// Source trees aren't cached here, since their metadata (e.g., dependencies or a dynamic
+/// version) can depend on arbitrary files within the tree; instead, they rely on the
+/// timestamp-aware cache in the distribution database.
+fn metadata_entry(cache: &Cache, source: &SourceUrl) -> Option<CacheEntry> {
+    let SourceUrl::Path(source) = source else {
+        return None;
+    };
+    let metadata = fs_err::metadata(&source.path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    let key = digest(&(metadata.len(), modified));
+    Some(cache.entry(
+        CacheBucket::BuiltWheels,
+        WheelCache::Path(source.url).root().join(key),
+        "metadata.msgpack",
+    ))
+}
+
+/// Read the cached metadata from the given entry, if it exists, is valid, and isn't due to be
+/// refreshed.
+fn read_cached_metadata(entry: &CacheEntry, refresh: &Refresh) -> Option<Metadata23> {
+    let contents = fs_err::read(entry.path()).ok()?;
+    rmp_serde::from_slice::<Metadata23>(&contents)
+        .ok()
+        .filter(|metadata| !is_refresh(refresh, &metadata.name))
+}
+
+/// Write the given metadata to the cache entry.
+async fn write_cached_metadata(entry: &CacheEntry, metadata: &Metadata23) -> Result<()> {
+    fs_err::tokio::create_dir_all(entry.dir()).await?;
+    uv_fs::write_atomic(entry.path(), rmp_serde::to_vec(metadata)?).await?;
+    Ok(())
+}
+
+/// Attempt to read the package name from static metadata in the given source tree (e.g., a
+/// `PKG-INFO`, `pyproject.toml`, `setup.cfg`, or `setup.py` file, or the metadata in an existing
+/// `.dist-info` or `.egg-info` directory), without running a build.
+fn read_static_name(path: &Path) -> Result<Option<PackageName>, NamedRequirementError> {
+    // Attempt to read a `PKG-INFO` from the directory.
+    if let Some(metadata) = fs_err::read(path.join("PKG-INFO"))
+        .ok()
+        .and_then(|contents| Metadata10::parse_pkg_info(&contents).ok())
+    {
+        debug!(
+            "Found PKG-INFO metadata for {path} ({name})",
+            path = path.display(),
+            name = metadata.name
+        );
+        return Ok(Some(metadata.name));
+    }
+
+    // Attempt to read a `pyproject.toml` file.
+    let project_path = path.join("pyproject.toml");
+    if let Some(pyproject) = fs_err::read_to_string(project_path)
+        .ok()
+        .and_then(|contents| toml::from_str::<PyProjectToml>(&contents).ok())
+    {
+        // If the name is declared as dynamic, it can only be determined by the build backend,
+        // unless Hatch provides a static hint.
+        let dynamic = pyproject.project.as_ref().is_some_and(|project| {
+            project
+                .dynamic
+                .as_ref()
+                .is_some_and(|dynamic| dynamic.iter().any(|field| field == "name"))
+        });
+
+        // Read PEP 621 metadata from the `pyproject.toml`.
+        if !dynamic {
+            if let Some(name) = pyproject.project.and_then(|project| project.name) {
+                debug!(
+                    "Found PEP 621 metadata for {path} in `pyproject.toml` ({name})",
+                    path = path.display(),
+                    name = name
+                );
+                return Ok(Some(name));
+            }
+        }
+
+        if let Some(tool) = pyproject.tool {
+            // Read Poetry-specific metadata from the `pyproject.toml`.
+            if !dynamic {
+                if let Some(name) = tool.poetry.and_then(|poetry| poetry.name) {
+                    debug!(
+                        "Found Poetry metadata for {path} in `pyproject.toml` ({name})",
+                        path = path.display(),
+                        name = name
+                    );
+                    return Ok(Some(name));
+                }
+            }
+
+            // Read Hatch-specific metadata from the `pyproject.toml`.
+            if let Some(name) = tool.hatch.and_then(ToolHatch::into_name) {
+                debug!(
+                    "Found Hatch metadata for {path} in `pyproject.toml` ({name})",
+                    path = path.display(),
+                    name = name
+                );
+                return Ok(Some(name));
+            }
+        }
+
+        if dynamic {
+            debug!(
+                "Found dynamic `name` for {path} in `pyproject.toml`; falling back to a build",
+                path = path.display()
+            );
+            return Ok(None);
+        }
+    }
+
+    // Attempt to read a `setup.cfg` from the directory.
+    if let Some(setup_cfg) = fs_err::read_to_string(path.join("setup.cfg"))
+        .ok()
+        .and_then(|contents| {
+            let mut ini = Ini::new_cs();
+            ini.set_multiline(true);
+            ini.read(contents).ok()
+        })
+    {
+        if let Some(section) = setup_cfg.get("metadata") {
+            if let Some(Some(name)) = section.get("name") {
+                // As with a dynamic `name` in `pyproject.toml`, defer to the build backend, which
+                // may compute the name (e.g., via interpolation) or report a clearer error.
+                let Ok(name) = PackageName::from_str(name) else {
+                    debug!(
+                        "Found invalid `name` for {path} in `setup.cfg`; falling back to a build",
+                        path = path.display()
+                    );
+                    return Ok(None);
+                };
+                debug!(
+                    "Found setuptools metadata for {path} in `setup.cfg` ({name})",
+                    path = path.display(),
+                    name = name
+                );
+                return Ok(Some(name));
+            }
+        }
+    }
+
+    // Attempt to read a literal name from the `setup()` call in a `setup.py`.
+    if let Some(name) =
+        read_setup_py(&path.join("setup.py")).and_then(|contents| parse_setup_py_name(&contents))
+    {
+        debug!(
+            "Found setuptools metadata for {path} in `setup.py` ({name})",
+            path = path.display(),
+            name = name
+        );
+        return Ok(Some(name));
+    }
+
+    // Attempt to read the metadata from a `.dist-info` or `.egg-info` directory that was left
+    // behind by a previous build or install.
+    let mut dist_infos = Vec::new();
+    let mut egg_infos = Vec::new();
+    for entry in fs_err::read_dir(path)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let entry = entry.path();
+        match entry.extension().and_then(|extension| extension.to_str()) {
+            Some("dist-info") => dist_infos.push(entry),
+            Some("egg-info") => egg_infos.push(entry),
+            _ => {}
+        }
+    }
+
+    dist_infos.sort();
+    match dist_infos.as_slice() {
+        [] => {}
+        [dist_info] => {
+            if let Some(metadata) = fs_err::read(dist_info.join("METADATA"))
+                .ok()
+                .and_then(|contents| Metadata23::parse_metadata(&contents).ok())
+            {
+                debug!(
+                    "Found `.dist-info` metadata for {path} ({name})",
+                    path = path.display(),
+                    name = metadata.name
+                );
+                return Ok(Some(metadata.name));
+            }
+        }
+        _ => {
+            return Err(NamedRequirementError::AmbiguousDistInfo(
+                path.to_path_buf(),
+                dist_infos
+                    .iter()
+                    .filter_map(|dist_info| dist_info.file_name())
+                    .map(|dist_info| format!("`{}`", dist_info.to_string_lossy()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
+    }
+
+    // Unlike `.dist-info` directories, stale `.egg-info` directories are often left behind in
+    // source trees (e.g., after renaming the package), so if there are several, prefer the one
+    // that was most recently written.
+    let egg_info = match egg_infos.as_slice() {
+        [] => None,
+        [egg_info] => Some(egg_info),
+        _ => {
+            let egg_info = egg_infos.iter().max_by_key(|egg_info| {
+                fs_err::metadata(egg_info.join("PKG-INFO"))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            });
+            if let Some(egg_info) = egg_info {
+                warn!(
+                    "Found multiple `.egg-info` directories in {path}; using the most recently modified: `{egg_info}`",
+                    path = path.display(),
+                    egg_info = egg_info.file_name().unwrap_or_default().to_string_lossy()
+                );
+            }
+            egg_info
+        }
+    };
+    if let Some(egg_info) = egg_info {
+        if let Some(metadata) = fs_err::read(egg_info.join("PKG-INFO"))
+            .ok()
+            .and_then(|contents| Metadata10::parse_pkg_info(&contents).ok())
+        {
+            debug!(
+                "Found `.egg-info` metadata for {path} ({name})",
+                path = path.display(),
+                name = metadata.name
+            );
+            return Ok(Some(metadata.name));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Read the metadata of the Python script at the given path from its inline script metadata, as
+/// specified in PEP 723, using the (normalized) filename as its name.
+///
+/// Ex) `example_script.py` is named `example-script`
+fn read_script_metadata(path: &Path) -> Result<Metadata23, NamedRequirementError> {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let name = PackageName::from_str(&stem)
+        .map_err(|err| NamedRequirementError::InvalidPackageName(path.to_path_buf(), err))?;
+    let contents = fs_err::read_to_string(path)?;
+    let metadata = Metadata23::parse_script(name, &contents)
+        .map_err(|err| NamedRequirementError::ScriptMetadata(path.to_path_buf(), err))?;
+    debug!(
+        "Found inline script metadata for {path} ({name})",
+        path = path.display(),
+        name = metadata.name
+    );
+    Ok(metadata)
+}
+
+/// Attempt to read the package name from the `PKG-INFO` file in the given source archive (e.g., a
+/// `.zip` or `.tar.gz` file), without running a build.
+///
+/// The archive is unpacked into a temporary directory. Returns `None` if the path isn't a
+/// recognized archive, or if the archive can't be unpacked or lacks a valid `PKG-INFO`, in which
+/// case the caller should fall back to a build.
+async fn read_archive_name(path: &Path, cache: Option<&Cache>) -> Option<PackageName> {
+    let filename = path.file_name()?.to_str()?;
+    SourceDistExtension::from_filename(&filename.to_ascii_lowercase())?;
+    if !path.is_file() {
+        return None;
+    }
+
+    let temp_dir = match cache {
+        Some(cache) => tempfile::tempdir_in(cache.root()),
+        None => tempfile::tempdir(),
+    }
+    .ok()?;
+    let reader = fs_err::tokio::File::open(path).await.ok()?;
+    if let Err(err) = uv_extract::stream::archive(reader, path, temp_dir.path()).await {
+        debug!(
+            "Failed to unpack {path} to read its metadata: {err}",
+            path = path.display()
+        );
+        return None;
+    }
+
+    let root = uv_extract::strip_component(temp_dir.path()).ok()?;
+    let metadata = fs_err::read(root.join("PKG-INFO"))
+        .ok()
+        .and_then(|contents| Metadata10::parse_pkg_info(&contents).ok())?;
+    debug!(
+        "Found PKG-INFO metadata for {path} ({name})",
+        path = path.display(),
+        name = metadata.name
+    );
+    Some(metadata.name)
+}
+
+/// Attempt to read the package name from the `METADATA` file in the given wheel, without
+/// relying on the wheel filename (e.g., for wheels that were renamed).
+///
+/// Returns `None` if the wheel can't be read, or lacks a valid `*.dist-info/METADATA` entry.
+fn read_wheel_name(path: &Path) -> Option<PackageName> {
+    let mut archive = zip::ZipArchive::new(fs_err::File::open(path).ok()?).ok()?;
+    let entry = archive
+        .file_names()
+        .find(|name| {
+            name.split_once('/').is_some_and(|(directory, file)| {
+                directory.ends_with(".dist-info") && file == "METADATA"
+            })
+        })?
+        .to_string();
+    let mut contents = Vec::new();
+    archive
+        .by_name(&entry)
+        .ok()?
+        .read_to_end(&mut contents)
+        .ok()?;
+    let metadata = Metadata10::parse_pkg_info(&contents).ok()?;
+    debug!(
+        "Found METADATA for {path} ({name})",
+        path = path.display(),
+        name = metadata.name
+    );
+    Some(metadata.name)
+}
+
+/// The maximum size of a `setup.py` file to scan for a literal name, in bytes.
+const MAX_SETUP_PY_SIZE: u64 = 1024 * 1024;
+
+/// Read the contents of a `setup.py` file, if it exists.
+///
+/// Returns `None` for files larger than [`MAX_SETUP_PY_SIZE`], which are left to the build
+/// backend rather than scanned in their entirety.
+fn read_setup_py(path: &Path) -> Option<String> {
+    let mut contents = String::new();
+    fs_err::File::open(path)
+        .ok()?
+        .take(MAX_SETUP_PY_SIZE + 1)
+        .read_to_string(&mut contents)
+        .ok()?;
+    if contents.len() as u64 > MAX_SETUP_PY_SIZE {
+        debug!(
+            "Skipping name detection for {path}, which exceeds {MAX_SETUP_PY_SIZE} bytes",
+            path = path.display()
+        );
+        return None;
+    }
+    Some(contents)
+}
+
+/// Extract the package name from a `setup.py` file, if it's passed to `setup()` as a string
+/// literal (e.g., `setup(name="foo")`).
+///
+/// This is a lightweight scan rather than a Python parser: if the name may be computed
+/// dynamically (e.g., `setup(name=NAME)` or `setup(**kwargs)`), or if the file contains multiple
+/// `setup()` calls that don't agree on a name (e.g., in separate branches of a conditional),
+/// returns `None`, such that the caller falls back to a build.
+fn parse_setup_py_name(contents: &str) -> Option<PackageName> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+    // Find the arguments to each `setup()` call, ignoring comments and definitions.
+    let mut calls = contents.match_indices("setup").filter_map(|(index, _)| {
+        let line = &contents[contents[..index].rfind('\n').map_or(0, |start| start + 1)..index];
+        if line.contains('#') || line.trim_end().ends_with("def") {
+            return None;
+        }
+        if contents[..index].ends_with(is_identifier) {
+            return None;
+        }
+        contents[index + "setup".len()..]
+            .trim_start()
+            .strip_prefix('(')
+    });
+
+    let name = parse_setup_call_name(calls.next()?)?;
+    for arguments in calls {
+        if parse_setup_call_name(arguments).as_ref() != Some(&name) {
+            debug!("Found multiple `setup()` calls in `setup.py` with different names");
+            return None;
+        }
+    }
+    Some(name)
+}
+
+/// Extract the literal `name` keyword argument from the arguments to a `setup()` call, i.e., the
+/// text following the opening parenthesis.
+fn parse_setup_call_name(arguments: &str) -> Option<PackageName> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+    // Find the `name=` keyword argument.
+    let mut rest = arguments;
+    let value = loop {
+        let index = rest.find("name")?;
+        let preceded = rest[..index].ends_with(is_identifier);
+        rest = &rest[index + "name".len()..];
+        if preceded {
+            continue;
+        }
+        match rest.trim_start().strip_prefix('=') {
+            Some(value) if !value.starts_with('=') => break value.trim_start(),
+            _ => continue,
+        }
+    };
+
+    // Read the string literal, bailing if it's part of a larger expression (e.g.,
+    // `name="foo" + suffix` or `name=f"{prefix}-foo"`).
+    let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value = &value[quote.len_utf8()..];
+    let (name, rest) = value.split_at(value.find(quote)?);
+    if !rest[quote.len_utf8()..]
+        .trim_start()
+        .starts_with([',', ')'])
+    {
+        return None;
+    }
+
+    PackageName::from_str(name).ok()
+}
+
+/// Return the value of the given parameter in the fragment of the given URL, if present.
+///
+/// The fragment may contain multiple `&`-separated parameters (e.g.,
+/// `#subdirectory=packages/foo&egg=foo`).
+fn fragment_parameter<'url>(url: &'url Url, key: &str) -> Option<&'url str> {
+    url.fragment()?.split('&').find_map(|parameter| {
+        parameter
+            .strip_prefix(key)
+            .and_then(|parameter| parameter.strip_prefix('='))
+    })
+}
+
+/// Extract the package name from the `egg` fragment of the given URL, if present and valid.
+fn egg_fragment_name(url: &Url) -> Option<PackageName> {
+    let egg = fragment_parameter(url, "egg")?;
+    match PackageName::from_str(egg) {
+        Ok(name) => {
+            debug!("Found `egg` fragment for {url} ({name})");
+            Some(name)
+        }
+        Err(err) => {
+            debug!("Ignoring invalid `egg` fragment for {url}: {err}");
+            None
+        }
+    }
+}
+
+/// Returns `true` if the metadata for the given package should be refreshed.
+fn is_refresh(refresh: &Refresh, name: &PackageName) -> bool {
+    match refresh {
+        Refresh::None => false,
+        Refresh::All(_) => true,
+        Refresh::Packages(packages, _) => packages.contains(name),
+    }
+}
+
 /// A pyproject.toml as specified in PEP 517.
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
@@ -297,13 +981,16 @@ struct PyProjectToml {
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 struct Project {
-    name: PackageName,
+    name: Option<PackageName>,
+    /// The fields that are computed by the build backend (e.g., `["version"]`).
+    dynamic: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 struct Tool {
     poetry: Option<ToolPoetry>,
+    hatch: Option<ToolHatch>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -311,3 +998,457 @@ struct Tool {
 struct ToolPoetry {
     name: Option<PackageName>,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct ToolHatch {
+    metadata: Option<ToolHatchMetadata>,
+    build: Option<ToolHatchBuild>,
+}
+
+impl ToolHatch {
+    /// Return the name hinted by the Hatch configuration, preferring `[tool.hatch.metadata]` over
+    /// the build targets (e.g., `[tool.hatch.build.targets.wheel]`).
+    ///
+    /// Returns `None` if the name may be computed by a hook, or if the build targets disagree.
+    fn into_name(self) -> Option<PackageName> {
+        if let Some(metadata) = self.metadata {
+            if metadata.hooks.is_some() {
+                return None;
+            }
+            if metadata.name.is_some() {
+                return metadata.name;
+            }
+        }
+
+        let build = self.build?;
+        if build.hooks.is_some() {
+            return None;
+        }
+        let mut name = None;
+        for target in build.targets.into_values() {
+            if target.hooks.is_some() {
+                return None;
+            }
+            match (&name, target.name) {
+                (_, None) => {}
+                (None, Some(target)) => name = Some(target),
+                (Some(existing), Some(target)) if *existing == target => {}
+                (Some(_), Some(_)) => return None,
+            }
+        }
+        name
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct ToolHatchMetadata {
+    name: Option<PackageName>,
+    /// The metadata hooks (e.g., `[tool.hatch.metadata.hooks.custom]`), which may compute the name
+    /// dynamically.
+    hooks: Option<toml::Table>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct ToolHatchBuild {
+    /// The build targets, keyed by target name (e.g., `[tool.hatch.build.targets.wheel]`).
+    #[serde(default)]
+    targets: BTreeMap<String, ToolHatchBuildTarget>,
+    /// The build hooks that apply to every target (e.g., `[tool.hatch.build.hooks.custom]`).
+    hooks: Option<toml::Table>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct ToolHatchBuildTarget {
+    name: Option<PackageName>,
+    /// The build hooks for the target (e.g., `[tool.hatch.build.targets.wheel.hooks.custom]`).
+    hooks: Option<toml::Table>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::io::Write;
+    use std::str::FromStr;
+    use std::time::SystemTime;
+
+    use anyhow::Result;
+    use url::Url;
+
+    use distribution_types::{DirectSourceUrl, DirectorySourceUrl, PathSourceUrl, SourceUrl};
+    use pypi_types::Metadata23;
+    use uv_cache::{Cache, CacheBucket, Refresh, Timestamp};
+    use uv_normalize::PackageName;
+    use zip::write::SimpleFileOptions;
+
+    use super::{
+        egg_fragment_name, fragment_parameter, metadata_entry, parse_setup_py_name,
+        read_archive_name, read_cached_metadata, read_script_metadata, read_setup_py,
+        read_static_name, read_wheel_name, write_cached_metadata, NamedRequirementError,
+        MAX_SETUP_PY_SIZE,
+    };
+
+    /// The cache entry for an archive should change when the archive is modified, while source
+    /// trees and remote sources should never be cached.
+    #[test]
+    fn metadata_entry_key() -> Result<()> {
+        let cache = Cache::temp()?;
+        let path = cache.root().join("foo-1.0.0.tar.gz");
+        fs_err::write(&path, "foo")?;
+
+        let url = Url::from_file_path(&path).unwrap();
+        let source = SourceUrl::Path(PathSourceUrl {
+            url: &url,
+            path: Cow::Borrowed(&path),
+        });
+        let before = metadata_entry(&cache, &source).unwrap();
+        assert_eq!(
+            metadata_entry(&cache, &source).unwrap().path(),
+            before.path()
+        );
+
+        fs_err::write(&path, "foobar")?;
+        let after = metadata_entry(&cache, &source).unwrap();
+        assert_ne!(after.path(), before.path());
+        assert_eq!(after.dir().parent(), before.dir().parent());
+
+        let path = cache.root().join("project");
+        fs_err::create_dir_all(&path)?;
+        fs_err::write(path.join("pyproject.toml"), "[project]\nname = \"foo\"\n")?;
+        let url = Url::from_directory_path(&path).unwrap();
+        let source = SourceUrl::Directory(DirectorySourceUrl {
+            url: &url,
+            path: Cow::Borrowed(&path),
+        });
+        assert!(metadata_entry(&cache, &source).is_none());
+
+        let url = Url::parse("https://example.org/foo-1.0.0.tar.gz")?;
+        let source = SourceUrl::Direct(DirectSourceUrl { url: &url });
+        assert!(metadata_entry(&cache, &source).is_none());
+
+        Ok(())
+    }
+
+    /// Cached metadata should be reused across invocations, unless the package is refreshed.
+    #[tokio::test]
+    async fn cached_metadata_refresh() -> Result<()> {
+        let cache = Cache::temp()?;
+        let entry = cache.entry(CacheBucket::BuiltWheels, "foo", "metadata.msgpack");
+        let metadata =
+            Metadata23::parse_metadata(b"Metadata-Version: 2.1\nName: foo\nVersion: 1.0.0\n")?;
+        write_cached_metadata(&entry, &metadata).await?;
+
+        let cached = read_cached_metadata(&entry, &Refresh::None).unwrap();
+        assert_eq!(cached.name, metadata.name);
+
+        assert!(read_cached_metadata(&entry, &Refresh::All(Timestamp::now())).is_none());
+        assert!(read_cached_metadata(
+            &entry,
+            &Refresh::Packages(vec![PackageName::from_str("foo")?], Timestamp::now())
+        )
+        .is_none());
+        assert!(read_cached_metadata(
+            &entry,
+            &Refresh::Packages(vec![PackageName::from_str("bar")?], Timestamp::now())
+        )
+        .is_some());
+
+        Ok(())
+    }
+
+    /// Hatch name hints should be used when the name is dynamic, unless a hook could compute the
+    /// name, or the build targets disagree.
+    #[test]
+    fn hatch_name() -> Result<()> {
+        let cache = Cache::temp()?;
+        let name = |contents: &str| -> Result<Option<String>> {
+            let path = cache.root().join("project");
+            fs_err::create_dir_all(&path)?;
+            fs_err::write(path.join("pyproject.toml"), contents)?;
+            Ok(read_static_name(&path)?.map(|name| name.to_string()))
+        };
+
+        assert_eq!(
+            name("[project]\ndynamic = [\"name\"]\n\n[tool.hatch.metadata]\nname = \"foo_bar\"\n")?,
+            Some("foo-bar".to_string())
+        );
+        assert_eq!(
+            name("[project]\ndynamic = [\"name\"]\n\n[tool.hatch.build.targets.wheel]\nname = \"foo\"\n\n[tool.hatch.build.targets.sdist]\nname = \"foo\"\n")?,
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            name("[project]\ndynamic = [\"name\"]\n\n[tool.hatch.build.targets.wheel]\nname = \"foo\"\n\n[tool.hatch.build.targets.sdist]\nname = \"bar\"\n")?,
+            None
+        );
+
+        // A static `[project]` name takes precedence over any hints.
+        assert_eq!(
+            name("[project]\nname = \"foo\"\n\n[tool.hatch.metadata]\nname = \"bar\"\n")?,
+            Some("foo".to_string())
+        );
+
+        // Hooks may compute the name, so we fall back to a build.
+        assert_eq!(
+            name("[project]\ndynamic = [\"name\"]\n\n[tool.hatch.metadata]\nname = \"foo\"\n\n[tool.hatch.metadata.hooks.custom]\n")?,
+            None
+        );
+        assert_eq!(
+            name("[project]\ndynamic = [\"name\"]\n\n[tool.hatch.build.targets.wheel]\nname = \"foo\"\n\n[tool.hatch.build.targets.wheel.hooks.custom]\n")?,
+            None
+        );
+        assert_eq!(
+            name("[project]\ndynamic = [\"name\"]\n\n[tool.hatch.build.targets.wheel]\nname = \"foo\"\n\n[tool.hatch.build.hooks.custom]\n")?,
+            None
+        );
+
+        Ok(())
+    }
+
+    /// Oversized `setup.py` files should be skipped, rather than read in their entirety.
+    #[test]
+    fn setup_py_size_limit() -> Result<()> {
+        let cache = Cache::temp()?;
+        let path = cache.root().join("setup.py");
+        let contents = "from setuptools import setup\nsetup(name=\"foo\")\n";
+
+        fs_err::write(&path, contents)?;
+        assert_eq!(read_setup_py(&path).as_deref(), Some(contents));
+
+        let padding = "#".repeat(usize::try_from(MAX_SETUP_PY_SIZE)?);
+        fs_err::write(&path, format!("{contents}{padding}\n"))?;
+        assert_eq!(read_setup_py(&path), None);
+
+        assert_eq!(read_setup_py(&cache.root().join("missing.py")), None);
+
+        Ok(())
+    }
+
+    /// Literal names should be read from `setup.py`, while computed names should be ignored.
+    #[test]
+    fn setup_py_name() {
+        let name = |contents: &str| parse_setup_py_name(contents).map(|name| name.to_string());
+
+        assert_eq!(
+            name("from setuptools import setup\n\nsetup(\n    name=\"foo_bar\",\n    version=\"1.0.0\",\n)\n"),
+            Some("foo-bar".to_string())
+        );
+        assert_eq!(
+            name("import setuptools\nsetuptools.setup(version='1.0', name = 'foo')\n"),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            name("from setuptools import setup\nsetup(name=NAME, version=\"1.0.0\")\n"),
+            None
+        );
+        assert_eq!(
+            name("from setuptools import setup\nsetup(name=\"foo\" + SUFFIX)\n"),
+            None
+        );
+        assert_eq!(
+            name("from setuptools import setup\nsetup(name=f\"{PREFIX}-foo\")\n"),
+            None
+        );
+        assert_eq!(
+            name("from setuptools import setup\nsetup(**kwargs)\n"),
+            None
+        );
+        assert_eq!(
+            name("from setuptools import setup\n# setup(name=\"bar\")\nsetup(name=\"foo\")\n"),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            name("from setuptools import setup\nif WINDOWS:\n    setup(name=\"foo-win\")\nelse:\n    setup(name=\"foo\")\n"),
+            None
+        );
+    }
+
+    /// Names should be read from existing `.dist-info` and `.egg-info` directories, but multiple
+    /// `.dist-info` directories are ambiguous, while the most recent of multiple `.egg-info`
+    /// directories is used.
+    #[test]
+    fn info_directory_name() -> Result<()> {
+        let cache = Cache::temp()?;
+
+        let path = cache.root().join("egg-info");
+        fs_err::create_dir_all(path.join("foo_bar.egg-info"))?;
+        fs_err::write(
+            path.join("foo_bar.egg-info").join("PKG-INFO"),
+            "Metadata-Version: 2.1\nName: foo_bar\nVersion: 1.0.0\n",
+        )?;
+        assert_eq!(
+            read_static_name(&path)?.map(|name| name.to_string()),
+            Some("foo-bar".to_string())
+        );
+
+        fs_err::create_dir_all(path.join("foo_baz.egg-info"))?;
+        fs_err::write(
+            path.join("foo_baz.egg-info").join("PKG-INFO"),
+            "Metadata-Version: 2.1\nName: foo_baz\nVersion: 1.0.0\n",
+        )?;
+        #[allow(clippy::disallowed_types)]
+        std::fs::File::options()
+            .write(true)
+            .open(path.join("foo_bar.egg-info").join("PKG-INFO"))?
+            .set_modified(SystemTime::UNIX_EPOCH)?;
+        assert_eq!(
+            read_static_name(&path)?.map(|name| name.to_string()),
+            Some("foo-baz".to_string())
+        );
+
+        let path = cache.root().join("dist-info");
+        fs_err::create_dir_all(path.join("foo-1.0.0.dist-info"))?;
+        fs_err::write(
+            path.join("foo-1.0.0.dist-info").join("METADATA"),
+            "Metadata-Version: 2.1\nName: foo\nVersion: 1.0.0\n",
+        )?;
+        assert_eq!(
+            read_static_name(&path)?.map(|name| name.to_string()),
+            Some("foo".to_string())
+        );
+
+        fs_err::create_dir_all(path.join("bar-1.0.0.dist-info"))?;
+        let err = read_static_name(&path).unwrap_err();
+        assert!(err.to_string().ends_with(
+            "so the package name is ambiguous: `bar-1.0.0.dist-info`, `foo-1.0.0.dist-info`"
+        ));
+
+        Ok(())
+    }
+
+    /// Scripts should be named after their filename, with dependencies read from their inline
+    /// metadata.
+    #[test]
+    fn script_metadata() -> Result<()> {
+        let cache = Cache::temp()?;
+
+        let path = cache.root().join("example_script.py");
+        fs_err::write(
+            &path,
+            "# /// script\n# dependencies = [\"rich\"]\n# ///\n\nimport rich\n",
+        )?;
+        let metadata = read_script_metadata(&path)?;
+        assert_eq!(metadata.name.as_ref(), "example-script");
+        assert_eq!(metadata.requires_dist, vec!["rich".parse()?]);
+
+        Ok(())
+    }
+
+    /// Invalid names in `setup.cfg` should defer to a build, rather than falling through to the
+    /// other static sources or failing outright.
+    #[test]
+    fn setup_cfg_invalid_name() -> Result<()> {
+        let cache = Cache::temp()?;
+
+        let path = cache.root().join("setup-cfg");
+        fs_err::create_dir_all(&path)?;
+        fs_err::write(path.join("setup.cfg"), "[metadata]\nname = foo bar\n")?;
+        fs_err::write(
+            path.join("setup.py"),
+            "from setuptools import setup\nsetup(name=\"foo\")\n",
+        )?;
+        assert_eq!(read_static_name(&path)?, None);
+
+        Ok(())
+    }
+
+    /// Names should be read from `egg` fragments, ignoring any other parameters and invalid names.
+    #[test]
+    fn egg_fragment() -> Result<()> {
+        let name = |url: &str| -> Result<Option<String>> {
+            Ok(egg_fragment_name(&Url::parse(url)?).map(|name| name.to_string()))
+        };
+
+        assert_eq!(
+            name("git+https://github.com/pallets/flask.git#egg=flask")?,
+            Some("flask".to_string())
+        );
+        assert_eq!(
+            name("git+https://github.com/org/repo.git@v1.0#subdirectory=packages/foo&egg=Foo_Bar")?,
+            Some("foo-bar".to_string())
+        );
+        assert_eq!(
+            name("git+https://github.com/org/repo.git#subdirectory=packages/foo")?,
+            None
+        );
+        assert_eq!(
+            name("git+https://github.com/org/repo.git#egg=foo bar")?,
+            None
+        );
+        assert_eq!(name("git+https://github.com/org/repo.git")?, None);
+
+        Ok(())
+    }
+
+    /// Fragment parameters should be matched by their full key.
+    #[test]
+    fn subdirectory_fragment() -> Result<()> {
+        let url = Url::parse("file:///home/ferris/monorepo#egg=foo&subdirectory=packages/foo")?;
+        assert_eq!(
+            fragment_parameter(&url, "subdirectory"),
+            Some("packages/foo")
+        );
+        assert_eq!(fragment_parameter(&url, "egg"), Some("foo"));
+
+        let url = Url::parse("file:///home/ferris/monorepo#subdirectory_extra=foo")?;
+        assert_eq!(fragment_parameter(&url, "subdirectory"), None);
+
+        Ok(())
+    }
+
+    /// Names should be read from the `PKG-INFO` in archives with non-standard filenames.
+    #[tokio::test]
+    async fn archive_name() -> Result<()> {
+        let cache = Cache::temp()?;
+
+        let path = cache.root().join("project.zip");
+        let mut archive = zip::ZipWriter::new(fs_err::File::create(&path)?);
+        archive.start_file("project/PKG-INFO", SimpleFileOptions::default())?;
+        archive.write_all(b"Metadata-Version: 2.1\nName: foo_bar\nVersion: 1.0.0\n")?;
+        archive.finish()?;
+        assert_eq!(
+            read_archive_name(&path, Some(&cache))
+                .await
+                .map(|name| name.to_string()),
+            Some("foo-bar".to_string())
+        );
+
+        // Other files should be ignored.
+        let path = cache.root().join("project.txt");
+        fs_err::write(&path, "")?;
+        assert_eq!(read_archive_name(&path, Some(&cache)).await, None);
+
+        Ok(())
+    }
+
+    /// Names should be read from the `METADATA` in wheels with non-standard filenames.
+    #[test]
+    fn wheel_name() -> Result<()> {
+        let cache = Cache::temp()?;
+
+        let path = cache.root().join("renamed.whl");
+        let mut archive = zip::ZipWriter::new(fs_err::File::create(&path)?);
+        archive.start_file("foo_bar/__init__.py", SimpleFileOptions::default())?;
+        archive.start_file(
+            "foo_bar-1.0.0.dist-info/METADATA",
+            SimpleFileOptions::default(),
+        )?;
+        archive.write_all(b"Metadata-Version: 2.1\nName: foo_bar\nVersion: 1.0.0\n")?;
+        archive.finish()?;
+        assert_eq!(
+            read_wheel_name(&path).map(|name| name.to_string()),
+            Some("foo-bar".to_string())
+        );
+
+        // Wheels without a `METADATA` entry should be ignored.
+        let path = cache.root().join("empty.whl");
+        let mut archive = zip::ZipWriter::new(fs_err::File::create(&path)?);
+        archive.start_file("foo_bar/__init__.py", SimpleFileOptions::default())?;
+        archive.finish()?;
+        assert_eq!(read_wheel_name(&path), None);
+
+        Ok(())
+    }
+}
@@ -27,7 +27,7 @@ pub(crate) struct CandidateSelector {
 impl CandidateSelector {
     /// Return a [`CandidateSelector`] for the given [`Manifest`].
     pub(crate) fn for_resolution(
-        options: Options,
+        options: &Options,
         manifest: &Manifest,
         markers: Option<&MarkerEnvironment>,
     ) -> Self {
@@ -204,6 +204,12 @@ impl CandidateSelector {
         }
     }
 
+    /// Returns `true` if pre-releases of the given package are only allowed as a fallback, i.e.,
+    /// when no stable version satisfies the requirements.
+    pub(crate) fn prerelease_if_necessary(&self, package_name: &PackageName) -> bool {
+        self.allow_prereleases(package_name) == AllowPreRelease::IfNecessary
+    }
+
     /// Select a [`Candidate`] without checking for version preference such as an existing
     /// lockfile.
     pub(crate) fn select_no_preference<'a>(
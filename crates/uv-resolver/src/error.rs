@@ -9,7 +9,9 @@ use pubgrub::range::Range;
 use pubgrub::report::{DefaultStringReporter, DerivationTree, External, Reporter};
 use rustc_hash::FxHashMap;
 
-use distribution_types::{BuiltDist, IndexLocations, InstalledDist, ParsedUrlError, SourceDist};
+use distribution_types::{
+    BuiltDist, IndexLocations, InstalledDist, ParsedUrlError, SourceDist, VersionId,
+};
 use once_map::OnceMap;
 use pep440_rs::Version;
 use pep508_rs::Requirement;
@@ -17,6 +19,7 @@ use uv_normalize::PackageName;
 
 use crate::candidate_selector::CandidateSelector;
 use crate::dependency_provider::UvDependencyProvider;
+use crate::lock::LockError;
 use crate::pubgrub::{PubGrubPackage, PubGrubPython, PubGrubReportFormatter};
 use crate::python_requirement::PythonRequirement;
 use crate::resolver::{
@@ -102,6 +105,15 @@ pub enum ResolveError {
     #[error("Failed to parse requirements")]
     DirectUrl(#[from] Box<ParsedUrlError>),
 
+    #[error(transparent)]
+    Lock(#[from] LockError),
+
+    #[error("Missing metadata for `{0}`")]
+    MissingMetadata(VersionId),
+
+    #[error("Missing pin for `{0}=={1}`")]
+    MissingPin(PackageName, Version),
+
     /// Something unexpected happened.
     #[error("{0}")]
     Failure(String),
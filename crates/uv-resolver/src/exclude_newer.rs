@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
 use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use rustc_hash::FxHashMap;
+
+use uv_normalize::PackageName;
 
 /// A timestamp that excludes files newer than it.
 #[derive(Debug, Copy, Clone, serde::Deserialize, serde::Serialize)]
@@ -52,6 +55,61 @@ impl std::fmt::Display for ExcludeNewer {
     }
 }
 
+/// An exclusion date for a single package, which takes precedence over the global exclusion date.
+#[derive(Debug, Clone)]
+pub struct ExcludeNewerPackage {
+    pub name: PackageName,
+    pub exclude_newer: ExcludeNewer,
+}
+
+impl FromStr for ExcludeNewerPackage {
+    type Err = String;
+
+    /// Parse an [`ExcludeNewerPackage`] from a string in the form `<name>=<date>` (e.g.,
+    /// `flask=2006-12-02`).
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (name, exclude_newer) = input
+            .split_once('=')
+            .ok_or_else(|| format!("`{input}` is not of the form `<name>=<date>`"))?;
+        let name = PackageName::from_str(name.trim()).map_err(|err| err.to_string())?;
+        let exclude_newer = ExcludeNewer::from_str(exclude_newer.trim())?;
+        Ok(Self {
+            name,
+            exclude_newer,
+        })
+    }
+}
+
+/// The exclusion dates to apply to each package: a global exclusion date, if any, along with any
+/// per-package overrides.
+#[derive(Debug, Default, Clone)]
+pub struct ExcludeNewerMap {
+    global: Option<ExcludeNewer>,
+    packages: FxHashMap<PackageName, ExcludeNewer>,
+}
+
+impl ExcludeNewerMap {
+    /// Create a new [`ExcludeNewerMap`] from a global exclusion date and per-package overrides.
+    pub fn new(
+        global: Option<ExcludeNewer>,
+        packages: impl IntoIterator<Item = ExcludeNewerPackage>,
+    ) -> Self {
+        Self {
+            global,
+            packages: packages
+                .into_iter()
+                .map(|package| (package.name, package.exclude_newer))
+                .collect(),
+        }
+    }
+
+    /// Return the exclusion date for the given package, preferring a per-package override over
+    /// the global exclusion date.
+    pub fn get(&self, name: &PackageName) -> Option<&ExcludeNewer> {
+        self.packages.get(name).or(self.global.as_ref())
+    }
+}
+
 #[cfg(feature = "schemars")]
 impl schemars::JsonSchema for ExcludeNewer {
     fn schema_name() -> String {
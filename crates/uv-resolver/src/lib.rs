@@ -1,6 +1,6 @@
 pub use dependency_mode::DependencyMode;
 pub use error::ResolveError;
-pub use exclude_newer::ExcludeNewer;
+pub use exclude_newer::{ExcludeNewer, ExcludeNewerMap, ExcludeNewerPackage};
 pub use exclusions::Exclusions;
 pub use flat_index::FlatIndex;
 pub use lock::{Lock, LockError};
@@ -9,13 +9,19 @@ pub use options::{Options, OptionsBuilder};
 pub use preferences::{Preference, PreferenceError};
 pub use prerelease_mode::PreReleaseMode;
 pub use python_requirement::PythonRequirement;
-pub use resolution::{AnnotationStyle, Diagnostic, DisplayResolutionGraph, ResolutionGraph};
+pub use resolution::{
+    AnnotationStyle, CyclicDependencyError, DependencyJson, DependencyPin, Diagnostic,
+    DisplayResolutionGraph, DotOptions, IndexRedaction, InputDrift, MergeConflict, PackageJson,
+    PackageNotFoundError, PipCompileOutputOptions, ResolutionDiff, ResolutionGraph, ResolutionJson,
+    SortOrder, ValidationError, WheelhouseEntry, WheelhouseError,
+};
 pub use resolution_mode::ResolutionMode;
 pub use resolver::{
     BuildId, DefaultResolverProvider, InMemoryIndex, MetadataResponse, PackageVersionsResult,
     Reporter as ResolverReporter, Resolver, ResolverProvider, VersionsResponse,
     WheelMetadataResult,
 };
+pub use sbom::{to_cyclonedx, CycloneDxBom, SpdxDocument};
 pub use version_map::VersionMap;
 pub use yanks::AllowedYanks;
 
@@ -41,5 +47,6 @@ mod redirect;
 mod resolution;
 mod resolution_mode;
 mod resolver;
+mod sbom;
 mod version_map;
 mod yanks;
@@ -0,0 +1,99 @@
+//! Migrations for lock files written with an older version of the lock file schema.
+//!
+//! Each migration upgrades the raw TOML of a lock file from one version to the next, such that a
+//! lock file written with any supported version can be read via [`Lock::migrate`].
+//!
+//! To make a breaking change to the schema, bump [`LOCK_VERSION`] and append a migration from the
+//! previous version to [`MIGRATIONS`] (e.g., `migrate_v1_to_v2`), along with a test that upgrades
+//! a lock file written with the previous version.
+//!
+//! [`Lock::migrate`]: super::Lock::migrate
+
+use super::LockError;
+
+/// The current version of the lock file schema.
+pub(crate) const LOCK_VERSION: u32 = 1;
+
+/// A lock file of some version, prior to deserialization.
+pub(crate) type RawLock = toml::Table;
+
+/// A migration that upgrades a [`RawLock`] from one version to the next.
+type Migration = fn(RawLock) -> Result<RawLock, LockError>;
+
+/// The migrations between each pair of consecutive versions, such that `MIGRATIONS[i]` upgrades a
+/// lock file from version `i + 1` to version `i + 2`.
+const MIGRATIONS: &[Migration] = &[];
+
+// Every version prior to the current version must have a migration to its successor.
+const _: () = assert!(MIGRATIONS.len() + 1 == LOCK_VERSION as usize);
+
+/// Upgrade a lock file to the current version, by applying each migration from the version given
+/// in its header.
+///
+/// Returns an error if the lock file doesn't specify a version, or if the version is newer than
+/// the current version.
+pub(crate) fn migrate(mut raw: RawLock) -> Result<RawLock, LockError> {
+    let found = match raw.get("version") {
+        Some(toml::Value::Integer(version)) => u32::try_from(*version).unwrap_or(u32::MAX),
+        Some(_) => {
+            return Err(LockError::toml_deserialize(
+                "uv.lock",
+                serde::de::Error::custom("expected `version` to be an integer"),
+            ))
+        }
+        None => {
+            return Err(LockError::toml_deserialize(
+                "uv.lock",
+                serde::de::Error::missing_field("version"),
+            ))
+        }
+    };
+    if found == 0 || found > LOCK_VERSION {
+        return Err(LockError::unsupported_version(found, LOCK_VERSION));
+    }
+
+    for (version, migration) in (found..).zip(&MIGRATIONS[found as usize - 1..]) {
+        raw = migration(raw)?;
+        raw.insert(
+            "version".to_string(),
+            toml::Value::Integer(i64::from(version + 1)),
+        );
+    }
+
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_version() {
+        let raw: RawLock = toml::from_str("version = 1\n").unwrap();
+        let migrated = migrate(raw.clone()).unwrap();
+        assert_eq!(migrated, raw);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let raw: RawLock = toml::from_str("version = 2\n").unwrap();
+        let err = migrate(raw).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unsupported lock file version 2 (expected a version between 1 and 1)"
+        );
+
+        let raw: RawLock = toml::from_str("version = 0\n").unwrap();
+        let err = migrate(raw).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unsupported lock file version 0 (expected a version between 1 and 1)"
+        );
+    }
+
+    #[test]
+    fn missing_version() {
+        let raw: RawLock = toml::from_str("[[distribution]]\nname = \"anyio\"\n").unwrap();
+        assert!(migrate(raw).is_err());
+    }
+}
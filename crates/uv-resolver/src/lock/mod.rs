@@ -3,6 +3,7 @@
 #![allow(dead_code, unreachable_code, unused_variables)]
 
 use std::collections::VecDeque;
+use std::str::FromStr;
 
 use distribution_filename::WheelFilename;
 use distribution_types::{
@@ -12,13 +13,17 @@ use distribution_types::{
     VersionOrUrlRef,
 };
 use pep440_rs::Version;
-use pep508_rs::{MarkerEnvironment, VerbatimUrl};
+use pep508_rs::{MarkerEnvironment, MarkerTree, VerbatimUrl};
 use platform_tags::{TagCompatibility, TagPriority, Tags};
 use pypi_types::HashDigest;
 use rustc_hash::FxHashMap;
 use url::Url;
 use uv_normalize::PackageName;
 
+use crate::lock::migrations::LOCK_VERSION;
+
+mod migrations;
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(into = "LockWire", try_from = "LockWire")]
 pub struct Lock {
@@ -36,17 +41,122 @@ pub struct Lock {
     /// that exists in this map. That is, there are no dependencies that don't
     /// have a corresponding locked distribution entry in the same lock file.
     by_id: FxHashMap<DistributionId, usize>,
+    /// A map from distribution ID to the parsed marker of that distribution.
+    ///
+    /// Markers are parsed once, when the lock is constructed, such that a
+    /// malformed marker in a lock file is reported as an error rather than
+    /// when the lock is used. Distributions without a marker have no entry.
+    markers: FxHashMap<DistributionId, MarkerTree>,
 }
 
 impl Lock {
     pub(crate) fn new(distributions: Vec<Distribution>) -> Result<Lock, LockError> {
         let wire = LockWire {
-            version: 1,
+            version: LOCK_VERSION,
             distributions,
         };
         Lock::try_from(wire)
     }
 
+    /// Deserializes a lock from the given TOML, upgrading it from the
+    /// version in its header to the current version if necessary.
+    ///
+    /// Unlike deserializing a [`Lock`] directly, which only accepts lock
+    /// files written with the current version, this accepts lock files
+    /// written with any prior version.
+    pub fn migrate(raw: &str) -> Result<Lock, LockError> {
+        let raw: migrations::RawLock =
+            toml::from_str(raw).map_err(|err| LockError::toml_deserialize("uv.lock", err))?;
+        let raw = migrations::migrate(raw)?;
+        toml::Value::Table(raw)
+            .try_into()
+            .map_err(|err| LockError::toml_deserialize("uv.lock", err))
+    }
+
+    /// Merges the locks for several target platforms into a single lock.
+    ///
+    /// Each lock is paired with a marker that identifies the platform for
+    /// which it was produced. Distributions that are locked for every
+    /// platform are recorded unconditionally, while those that are only
+    /// locked for a subset of the platforms record the markers for that
+    /// subset, such that [`Lock::to_resolution`] only selects them on a
+    /// matching platform.
+    ///
+    /// Returns an error if a package is locked to different distributions
+    /// on different platforms.
+    pub fn for_platforms(platforms: &[(MarkerTree, Lock)]) -> Result<Lock, LockError> {
+        let mut distributions: Vec<Distribution> = vec![];
+        let mut markers: Vec<Vec<String>> = vec![];
+        let mut unconditional: Vec<usize> = vec![];
+        let mut by_name: FxHashMap<PackageName, usize> = FxHashMap::default();
+        for (platform, lock) in platforms {
+            for dist in &lock.distributions {
+                let index = match by_name.get(&dist.id.name) {
+                    Some(&index) => {
+                        let merged = &mut distributions[index];
+                        if merged.id != dist.id {
+                            return Err(LockError::conflicting_distributions(
+                                merged.id.clone(),
+                                dist.id.clone(),
+                            ));
+                        }
+                        if merged.sourcedist.is_none() {
+                            merged.sourcedist = dist.sourcedist.clone();
+                        }
+                        for wheel in &dist.wheels {
+                            if !merged.wheels.iter().any(|merged| merged.url == wheel.url) {
+                                merged.wheels.push(wheel.clone());
+                            }
+                        }
+                        for dependency in &dist.dependencies {
+                            if !merged.dependencies.contains(dependency) {
+                                merged.dependencies.push(dependency.clone());
+                            }
+                        }
+                        index
+                    }
+                    None => {
+                        by_name.insert(dist.id.name.clone(), distributions.len());
+                        distributions.push(Distribution {
+                            marker: None,
+                            ..dist.clone()
+                        });
+                        markers.push(vec![]);
+                        unconditional.push(0);
+                        distributions.len() - 1
+                    }
+                };
+
+                // Record the conditions under which the distribution is required on this
+                // platform.
+                match dist.marker {
+                    Some(ref marker) => markers[index].push(format!("({platform}) and ({marker})")),
+                    None => {
+                        markers[index].push(platform.to_string());
+                        unconditional[index] += 1;
+                    }
+                }
+            }
+        }
+
+        for ((dist, markers), unconditional) in
+            distributions.iter_mut().zip(markers).zip(unconditional)
+        {
+            if unconditional < platforms.len() {
+                dist.marker = Some(match markers.as_slice() {
+                    [marker] => marker.clone(),
+                    markers => markers
+                        .iter()
+                        .map(|marker| format!("({marker})"))
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                });
+            }
+        }
+
+        Lock::new(distributions)
+    }
+
     pub fn to_resolution(
         &self,
         marker_env: &MarkerEnvironment,
@@ -69,6 +179,12 @@ impl Lock {
         while let Some(dist) = queue.pop_front() {
             for dep in &dist.dependencies {
                 let dep_dist = self.find_by_id(&dep.id);
+                // Skip any distributions that are only locked for other platforms.
+                if let Some(marker) = self.markers.get(&dep_dist.id) {
+                    if !marker.evaluate(marker_env, &[]) {
+                        continue;
+                    }
+                }
                 queue.push_back(dep_dist);
             }
             let name = PackageName::new(dist.id.name.to_string()).unwrap();
@@ -78,6 +194,54 @@ impl Lock {
         Resolution::new(map)
     }
 
+    /// Serializes this lock to the canonical `uv.lock` TOML format.
+    ///
+    /// Distributions (and the dependencies of each distribution) are
+    /// written in a stable, sorted order, regardless of the order in which
+    /// they were resolved.
+    pub fn to_toml(&self) -> Result<String, LockError> {
+        toml::to_string_pretty(self).map_err(LockError::toml_serialize)
+    }
+
+    /// Serializes this lock to the TOML format defined by PEP 751 (i.e., a
+    /// `pylock.toml` file).
+    ///
+    /// Distributions are written as `[[packages]]` entries under a
+    /// `[tool.uv.lock]` header. Converting the result back with
+    /// [`Lock::from_pylock_toml`] yields an identical lock.
+    pub fn to_pylock_toml(&self) -> Result<String, LockError> {
+        let wire = PylockWire {
+            tool: PylockTool {
+                uv: PylockToolUv {
+                    lock: PylockHeader {
+                        version: self.version,
+                    },
+                },
+            },
+            packages: self
+                .distributions
+                .iter()
+                .cloned()
+                .map(PylockPackage::from)
+                .collect(),
+        };
+        toml::to_string_pretty(&wire).map_err(LockError::toml_serialize)
+    }
+
+    /// Deserializes a lock from the TOML format defined by PEP 751 (i.e., a
+    /// `pylock.toml` file), as written by [`Lock::to_pylock_toml`].
+    ///
+    /// The resulting lock is subject to the same validation as one read from
+    /// the `uv.lock` format.
+    pub fn from_pylock_toml(s: &str) -> Result<Lock, LockError> {
+        let wire: PylockWire =
+            toml::from_str(s).map_err(|err| LockError::toml_deserialize("pylock.toml", err))?;
+        Lock::try_from(LockWire {
+            version: wire.tool.uv.lock.version,
+            distributions: wire.packages.into_iter().map(Distribution::from).collect(),
+        })
+    }
+
     /// Returns the distribution with the given name. If there are multiple
     /// matching distributions, then an error is returned. If there are no
     /// matching distributions, then `Ok(None)` is returned.
@@ -124,6 +288,11 @@ impl TryFrom<LockWire> for Lock {
     type Error = LockError;
 
     fn try_from(mut wire: LockWire) -> Result<Lock, LockError> {
+        // Older lock files must be upgraded via `Lock::migrate` first.
+        if wire.version != LOCK_VERSION {
+            return Err(LockError::unsupported_version(wire.version, LOCK_VERSION));
+        }
+
         // Put all dependencies for each distribution in a canonical order and
         // check for duplicates.
         for dist in &mut wire.distributions {
@@ -179,14 +348,232 @@ impl TryFrom<LockWire> for Lock {
                 }
             }
         }
+        // Parse the marker of every distribution, so that a malformed marker
+        // is reported upfront.
+        let mut markers = FxHashMap::default();
+        for dist in &wire.distributions {
+            if let Some(ref marker) = dist.marker {
+                let parsed = MarkerTree::from_str(marker).map_err(|err| {
+                    LockError::invalid_marker(dist.id.clone(), marker.clone(), &err)
+                })?;
+                markers.insert(dist.id.clone(), parsed);
+            }
+        }
         Ok(Lock {
             version: wire.version,
             distributions: wire.distributions,
             by_id,
+            markers,
         })
     }
 }
 
+/// The wire format for a `pylock.toml` file, per PEP 751.
+///
+/// The lock metadata lives under `[tool.uv.lock]`, while each locked
+/// distribution is written as a `[[packages]]` entry.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockWire {
+    tool: PylockTool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<PylockPackage>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockTool {
+    uv: PylockToolUv,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockToolUv {
+    lock: PylockHeader,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockHeader {
+    version: u32,
+}
+
+/// A single `[[packages]]` entry in a `pylock.toml` file.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockPackage {
+    #[serde(flatten)]
+    id: PylockId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    marker: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<PylockId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sdist: Option<PylockSourceDist>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    wheels: Vec<PylockWheel>,
+}
+
+impl From<Distribution> for PylockPackage {
+    fn from(dist: Distribution) -> PylockPackage {
+        PylockPackage {
+            id: PylockId(dist.id),
+            marker: dist.marker,
+            dependencies: dist
+                .dependencies
+                .into_iter()
+                .map(|dep| PylockId(dep.id))
+                .collect(),
+            sdist: dist.sourcedist.map(PylockSourceDist),
+            wheels: dist.wheels.into_iter().map(PylockWheel).collect(),
+        }
+    }
+}
+
+impl From<PylockPackage> for Distribution {
+    fn from(package: PylockPackage) -> Distribution {
+        Distribution {
+            id: package.id.0,
+            marker: package.marker,
+            sourcedist: package.sdist.map(|sdist| sdist.0),
+            wheels: package.wheels.into_iter().map(|wheel| wheel.0).collect(),
+            dependencies: package
+                .dependencies
+                .into_iter()
+                .map(|dep| Dependency { id: dep.0 })
+                .collect(),
+        }
+    }
+}
+
+/// A distribution ID as represented in a `pylock.toml` file.
+///
+/// Rather than a single `source` string, distributions from a registry
+/// record the registry URL as `registry`, while all other distributions
+/// record their source as `url`.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(into = "PylockIdWire", try_from = "PylockIdWire")]
+struct PylockId(DistributionId);
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockIdWire {
+    name: PackageName,
+    version: Version,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    registry: Option<Url>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<Source>,
+}
+
+impl From<PylockId> for PylockIdWire {
+    fn from(id: PylockId) -> PylockIdWire {
+        let DistributionId {
+            name,
+            version,
+            source,
+        } = id.0;
+        let (registry, url) = match source.kind {
+            SourceKind::Registry => (Some(source.url), None),
+            _ => (None, Some(source)),
+        };
+        PylockIdWire {
+            name,
+            version,
+            registry,
+            url,
+        }
+    }
+}
+
+impl TryFrom<PylockIdWire> for PylockId {
+    type Error = String;
+
+    fn try_from(wire: PylockIdWire) -> Result<PylockId, String> {
+        let source = match (wire.registry, wire.url) {
+            (Some(url), None) => Source {
+                kind: SourceKind::Registry,
+                url,
+            },
+            (None, Some(source)) if source.kind != SourceKind::Registry => source,
+            _ => {
+                return Err(format!(
+                    "expected exactly one of `registry` or a non-registry `url` for `{}`",
+                    wire.name
+                ))
+            }
+        };
+        Ok(PylockId(DistributionId {
+            name: wire.name,
+            version: wire.version,
+            source,
+        }))
+    }
+}
+
+/// A wheel or source distribution as represented in a `pylock.toml` file.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct PylockArtifact {
+    url: Url,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash>,
+}
+
+impl PylockArtifact {
+    /// Returns the single hash for this artifact, if any.
+    ///
+    /// Locked artifacts only ever record a single hash, so finding more than
+    /// one is an error.
+    fn into_hash(self) -> Result<(Url, Option<Hash>), String> {
+        let PylockArtifact { url, mut hashes } = self;
+        if hashes.len() > 1 {
+            return Err(format!(
+                "expected at most one hash for `{url}`, but found {}",
+                hashes.len()
+            ));
+        }
+        Ok((url, hashes.pop()))
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(into = "PylockArtifact", try_from = "PylockArtifact")]
+struct PylockSourceDist(SourceDist);
+
+impl From<PylockSourceDist> for PylockArtifact {
+    fn from(sdist: PylockSourceDist) -> PylockArtifact {
+        PylockArtifact {
+            url: sdist.0.url,
+            hashes: sdist.0.hash.into_iter().collect(),
+        }
+    }
+}
+
+impl TryFrom<PylockArtifact> for PylockSourceDist {
+    type Error = String;
+
+    fn try_from(artifact: PylockArtifact) -> Result<PylockSourceDist, String> {
+        let (url, hash) = artifact.into_hash()?;
+        Ok(PylockSourceDist(SourceDist { url, hash }))
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(into = "PylockArtifact", try_from = "PylockArtifact")]
+struct PylockWheel(Wheel);
+
+impl From<PylockWheel> for PylockArtifact {
+    fn from(wheel: PylockWheel) -> PylockArtifact {
+        PylockArtifact {
+            url: wheel.0.url,
+            hashes: wheel.0.hash.into_iter().collect(),
+        }
+    }
+}
+
+impl TryFrom<PylockArtifact> for PylockWheel {
+    type Error = String;
+
+    fn try_from(artifact: PylockArtifact) -> Result<PylockWheel, String> {
+        let (url, hash) = artifact.into_hash()?;
+        Wheel::try_from(WheelWire { url, hash }).map(PylockWheel)
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub(crate) struct Distribution {
     #[serde(flatten)]
@@ -918,6 +1305,52 @@ impl LockError {
         }
     }
 
+    fn unsupported_version(found: u32, max_supported: u32) -> LockError {
+        let kind = LockErrorKind::UnsupportedVersion {
+            found,
+            max_supported,
+        };
+        LockError {
+            kind: Box::new(kind),
+        }
+    }
+
+    fn toml_serialize(err: toml::ser::Error) -> LockError {
+        let kind = LockErrorKind::TomlSerialize { err };
+        LockError {
+            kind: Box::new(kind),
+        }
+    }
+
+    fn toml_deserialize(filename: &'static str, err: toml::de::Error) -> LockError {
+        let kind = LockErrorKind::TomlDeserialize { filename, err };
+        LockError {
+            kind: Box::new(kind),
+        }
+    }
+
+    fn conflicting_distributions(id: DistributionId, conflict_id: DistributionId) -> LockError {
+        let kind = LockErrorKind::ConflictingDistributions { id, conflict_id };
+        LockError {
+            kind: Box::new(kind),
+        }
+    }
+
+    fn invalid_marker(
+        id: DistributionId,
+        marker: String,
+        err: &pep508_rs::Pep508Error,
+    ) -> LockError {
+        let kind = LockErrorKind::InvalidMarker {
+            id,
+            marker,
+            message: err.message.to_string(),
+        };
+        LockError {
+            kind: Box::new(kind),
+        }
+    }
+
     fn hash(id: DistributionId, artifact_type: &'static str, expected: bool) -> LockError {
         let kind = LockErrorKind::Hash {
             id,
@@ -938,6 +1371,11 @@ impl std::error::Error for LockError {
             LockErrorKind::InvalidFileUrl { ref err } => Some(err),
             LockErrorKind::UnrecognizedDependency { ref err } => Some(err),
             LockErrorKind::Hash { .. } => None,
+            LockErrorKind::InvalidMarker { .. } => None,
+            LockErrorKind::ConflictingDistributions { .. } => None,
+            LockErrorKind::UnsupportedVersion { .. } => None,
+            LockErrorKind::TomlSerialize { ref err } => Some(err),
+            LockErrorKind::TomlDeserialize { ref err } => Some(err),
         }
     }
 }
@@ -987,6 +1425,43 @@ impl std::fmt::Display for LockError {
                     source = id.source.kind.name(),
                 )
             }
+            LockErrorKind::InvalidMarker {
+                ref id,
+                ref marker,
+                ref message,
+            } => {
+                write!(
+                    f,
+                    "failed to parse marker `{marker}` for distribution `{id}`: {message}"
+                )
+            }
+            LockErrorKind::ConflictingDistributions {
+                ref id,
+                ref conflict_id,
+            } => {
+                write!(
+                    f,
+                    "found conflicting distributions `{id}` and `{conflict_id}` \
+                     for `{name}` across target platforms",
+                    name = id.name,
+                )
+            }
+            LockErrorKind::UnsupportedVersion {
+                found,
+                max_supported,
+            } => {
+                write!(
+                    f,
+                    "unsupported lock file version {found} \
+                     (expected a version between 1 and {max_supported})"
+                )
+            }
+            LockErrorKind::TomlSerialize { .. } => {
+                write!(f, "failed to serialize lock to TOML")
+            }
+            LockErrorKind::TomlDeserialize { filename, .. } => {
+                write!(f, "failed to parse lock from `{filename}`")
+            }
         }
     }
 }
@@ -1033,6 +1508,49 @@ enum LockErrorKind {
         /// When true, a hash is expected to be present.
         expected: bool,
     },
+    /// An error that occurs when the marker of a distribution is not a valid
+    /// PEP 508 marker expression.
+    InvalidMarker {
+        /// The ID of the distribution with the invalid marker.
+        id: DistributionId,
+        /// The marker, as written in the lock file.
+        marker: String,
+        /// The reason the marker could not be parsed.
+        message: String,
+    },
+    /// An error that occurs when merging the locks for multiple target
+    /// platforms, and a package was locked to different distributions (e.g.,
+    /// different versions) on different platforms.
+    ConflictingDistributions {
+        /// The ID of the distribution that was locked first.
+        id: DistributionId,
+        /// The ID of the conflicting distribution from a later platform.
+        conflict_id: DistributionId,
+    },
+    /// An error that occurs when a lock file was written with a version of
+    /// the schema that isn't supported (e.g., by a newer version of uv).
+    UnsupportedVersion {
+        /// The version of the lock file.
+        found: u32,
+        /// The latest version that's supported.
+        max_supported: u32,
+    },
+    /// An error that occurs when a lock could not be serialized to TOML
+    /// (in either the `uv.lock` or `pylock.toml` format).
+    TomlSerialize {
+        /// The underlying error that occurred.
+        err: toml::ser::Error,
+    },
+    /// An error that occurs when a lock file could not be parsed (in either
+    /// the `uv.lock` or `pylock.toml` format).
+    TomlDeserialize {
+        /// The name of the file format that was being parsed (e.g.,
+        /// `uv.lock`).
+        filename: &'static str,
+        /// The underlying error that occurred. This includes the location of
+        /// the errant TOML in its error message.
+        err: toml::de::Error,
+    },
 }
 
 /// An error that occurs when there's an unrecognized dependency.
@@ -1182,4 +1700,238 @@ hash = "sha256:048e05d0f6caeed70d731f3db756d35dcc1f35747c8c403364a8332c630441b8"
         let result: Result<Lock, _> = toml::from_str(data);
         insta::assert_debug_snapshot!(result);
     }
+
+    #[test]
+    fn invalid_marker() {
+        let data = r#"
+version = 1
+
+[[distribution]]
+name = "idna"
+version = "3.6"
+source = "path+file:///foo/bar"
+marker = "python_version >= "
+
+[[distribution.wheel]]
+url = "file:///foo/bar/idna-3.6-py3-none-any.whl"
+hash = "sha256:c05567e9c24a6b9faaa835c4821bad0590fbb9d5779e7caa6e1cc4978e7eb24f"
+"#;
+        let err = toml::from_str::<Lock>(data).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("failed to parse marker `python_version >= ` for distribution `idna 3.6"));
+    }
+
+    #[test]
+    fn pylock_toml_round_trip() {
+        let data = r#"
+version = 1
+
+[[distribution]]
+name = "anyio"
+version = "4.3.0"
+source = "registry+https://pypi.org/simple"
+
+[[distribution.wheel]]
+url = "https://files.pythonhosted.org/packages/14/fd/2f20c40b45e4fb4324834aea24bd4afdf1143390242c0b33774da0e2e34f/anyio-4.3.0-py3-none-any.whl"
+hash = "sha256:048e05d0f6caeed70d731f3db756d35dcc1f35747c8c403364a8332c630441b8"
+
+[[distribution.dependencies]]
+name = "idna"
+version = "3.6"
+source = "path+file:///foo/bar"
+
+[[distribution]]
+name = "idna"
+version = "3.6"
+source = "path+file:///foo/bar"
+marker = "python_version >= '3.8'"
+
+[[distribution.wheel]]
+url = "file:///foo/bar/idna-3.6-py3-none-any.whl"
+hash = "sha256:c05567e9c24a6b9faaa835c4821bad0590fbb9d5779e7caa6e1cc4978e7eb24f"
+"#;
+        let lock: Lock = toml::from_str(data).unwrap();
+        let pylock = lock.to_pylock_toml().unwrap();
+        let round_trip = Lock::from_pylock_toml(&pylock).unwrap();
+        assert_eq!(
+            toml::to_string_pretty(&round_trip).unwrap(),
+            toml::to_string_pretty(&lock).unwrap()
+        );
+    }
+
+    #[test]
+    fn pylock_toml_sources() {
+        let data = r#"
+[tool.uv.lock]
+version = 1
+
+[[packages]]
+name = "anyio"
+version = "4.3.0"
+registry = "https://pypi.org/simple"
+dependencies = [{ name = "idna", version = "3.6", url = "path+file:///foo/bar" }]
+
+[[packages.wheels]]
+url = "https://files.pythonhosted.org/packages/14/fd/2f20c40b45e4fb4324834aea24bd4afdf1143390242c0b33774da0e2e34f/anyio-4.3.0-py3-none-any.whl"
+hashes = ["sha256:048e05d0f6caeed70d731f3db756d35dcc1f35747c8c403364a8332c630441b8"]
+
+[[packages]]
+name = "idna"
+version = "3.6"
+url = "path+file:///foo/bar"
+
+[[packages.wheels]]
+url = "file:///foo/bar/idna-3.6-py3-none-any.whl"
+hashes = ["sha256:c05567e9c24a6b9faaa835c4821bad0590fbb9d5779e7caa6e1cc4978e7eb24f"]
+"#;
+        let lock = Lock::from_pylock_toml(data).unwrap();
+        let ids = lock
+            .distributions
+            .iter()
+            .map(|dist| dist.id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                "anyio 4.3.0 registry+https://pypi.org/simple",
+                "idna 3.6 path+file:///foo/bar"
+            ]
+        );
+        assert_eq!(
+            lock.distributions[0].dependencies[0].id.name.as_ref(),
+            "idna"
+        );
+
+        // A package must specify exactly one of `registry` or `url`.
+        let data = r#"
+[tool.uv.lock]
+version = 1
+
+[[packages]]
+name = "anyio"
+version = "4.3.0"
+"#;
+        let err = Lock::from_pylock_toml(data).unwrap_err();
+        assert_eq!(err.to_string(), "failed to parse lock from `pylock.toml`");
+    }
+
+    #[test]
+    fn for_platforms() {
+        let lock = |version: &str, dependencies: &[&str]| -> Lock {
+            let mut data = r#"
+version = 1
+
+[[distribution]]
+name = "root"
+version = "0.1.0"
+source = "path+file:///foo/bar"
+
+[[distribution.wheel]]
+url = "file:///foo/bar/root-0.1.0-py3-none-any.whl"
+hash = "sha256:1234"
+"#
+            .to_string();
+            for dependency in dependencies {
+                data.push_str(&format!(
+                    r#"
+[[distribution.dependencies]]
+name = "{dependency}"
+version = "{version}"
+source = "path+file:///foo/bar"
+"#
+                ));
+            }
+            for dependency in dependencies {
+                data.push_str(&format!(
+                    r#"
+[[distribution]]
+name = "{dependency}"
+version = "{version}"
+source = "path+file:///foo/bar"
+
+[[distribution.wheel]]
+url = "file:///foo/bar/{dependency}-{version}-py3-none-any.whl"
+hash = "sha256:1234"
+"#
+                ));
+            }
+            toml::from_str(&data).unwrap()
+        };
+        let linux = MarkerTree::from_str("sys_platform == 'linux'").unwrap();
+        let windows = MarkerTree::from_str("sys_platform == 'win32'").unwrap();
+
+        let merged = Lock::for_platforms(&[
+            (linux.clone(), lock("1.0.0", &["anyio", "uvloop"])),
+            (windows.clone(), lock("1.0.0", &["anyio", "colorama"])),
+        ])
+        .unwrap();
+        let markers = merged
+            .distributions
+            .iter()
+            .map(|dist| (dist.id.name.to_string(), dist.marker.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            markers,
+            vec![
+                ("anyio".to_string(), None),
+                (
+                    "colorama".to_string(),
+                    Some("sys_platform == 'win32'".to_string())
+                ),
+                ("root".to_string(), None),
+                (
+                    "uvloop".to_string(),
+                    Some("sys_platform == 'linux'".to_string())
+                ),
+            ]
+        );
+        let root = merged
+            .find_by_name(&PackageName::from_str("root").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(root.dependencies.len(), 3);
+
+        // A package can't be locked to different versions on different platforms.
+        let err = Lock::for_platforms(&[
+            (linux, lock("1.0.0", &["anyio"])),
+            (windows, lock("2.0.0", &["anyio"])),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "found conflicting distributions `anyio 1.0.0 path+file:///foo/bar` and \
+             `anyio 2.0.0 path+file:///foo/bar` for `anyio` across target platforms"
+        );
+    }
+
+    #[test]
+    fn migrate_current_version() {
+        let data = r#"
+version = 1
+
+[[distribution]]
+name = "anyio"
+version = "4.3.0"
+source = "registry+https://pypi.org/simple"
+
+[[distribution.wheel]]
+url = "https://files.pythonhosted.org/packages/14/fd/2f20c40b45e4fb4324834aea24bd4afdf1143390242c0b33774da0e2e34f/anyio-4.3.0-py3-none-any.whl"
+hash = "sha256:048e05d0f6caeed70d731f3db756d35dcc1f35747c8c403364a8332c630441b8"
+"#;
+        let lock = Lock::migrate(data).unwrap();
+        assert_eq!(
+            toml::to_string_pretty(&lock).unwrap(),
+            toml::to_string_pretty(&toml::from_str::<Lock>(data).unwrap()).unwrap()
+        );
+
+        let err = Lock::migrate(&data.replace("version = 1", "version = 2")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unsupported lock file version 2 (expected a version between 1 and 1)"
+        );
+
+        let err = Lock::migrate(&data.replace("version = 1", "version = \"1\"")).unwrap_err();
+        assert_eq!(err.to_string(), "failed to parse lock from `uv.lock`");
+    }
 }
@@ -1,14 +1,15 @@
 use uv_configuration::IndexStrategy;
 
-use crate::{DependencyMode, ExcludeNewer, PreReleaseMode, ResolutionMode};
+use crate::{DependencyMode, ExcludeNewer, ExcludeNewerPackage, PreReleaseMode, ResolutionMode};
 
 /// Options for resolving a manifest.
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Options {
     pub resolution_mode: ResolutionMode,
     pub prerelease_mode: PreReleaseMode,
     pub dependency_mode: DependencyMode,
     pub exclude_newer: Option<ExcludeNewer>,
+    pub exclude_newer_package: Vec<ExcludeNewerPackage>,
     pub index_strategy: IndexStrategy,
 }
 
@@ -19,6 +20,7 @@ pub struct OptionsBuilder {
     prerelease_mode: PreReleaseMode,
     dependency_mode: DependencyMode,
     exclude_newer: Option<ExcludeNewer>,
+    exclude_newer_package: Vec<ExcludeNewerPackage>,
     index_strategy: IndexStrategy,
 }
 
@@ -56,6 +58,16 @@ impl OptionsBuilder {
         self
     }
 
+    /// Sets the per-package exclusion dates, which take precedence over the global exclusion date.
+    #[must_use]
+    pub fn exclude_newer_package(
+        mut self,
+        exclude_newer_package: Vec<ExcludeNewerPackage>,
+    ) -> Self {
+        self.exclude_newer_package = exclude_newer_package;
+        self
+    }
+
     /// Sets the index strategy.
     #[must_use]
     pub fn index_strategy(mut self, index_strategy: IndexStrategy) -> Self {
@@ -70,6 +82,7 @@ impl OptionsBuilder {
             prerelease_mode: self.prerelease_mode,
             dependency_mode: self.dependency_mode,
             exclude_newer: self.exclude_newer,
+            exclude_newer_package: self.exclude_newer_package,
             index_strategy: self.index_strategy,
         }
     }
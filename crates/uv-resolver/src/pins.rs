@@ -30,3 +30,15 @@ impl FilePins {
         self.0.get(name)?.get(version)
     }
 }
+
+impl FromIterator<(PackageName, pep440_rs::Version, ResolvedDist)> for FilePins {
+    fn from_iter<T: IntoIterator<Item = (PackageName, pep440_rs::Version, ResolvedDist)>>(
+        iter: T,
+    ) -> Self {
+        let mut pins = Self::default();
+        for (name, version, dist) in iter {
+            pins.0.entry(name).or_default().insert(version, dist);
+        }
+        pins
+    }
+}
@@ -1,28 +1,43 @@
 use std::borrow::Cow;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::hash::BuildHasherDefault;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
 use itertools::Itertools;
-use owo_colors::OwoColorize;
+use owo_colors::{AnsiColors, OwoColorize};
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
 use pubgrub::range::Range;
 use pubgrub::solver::{Kind, State};
 use pubgrub::type_aliases::SelectedDependencies;
 use rustc_hash::{FxHashMap, FxHashSet};
+use tracing::warn;
+use url::Url;
 
+use distribution_filename::WheelFilename;
 use distribution_types::{
-    Dist, DistributionMetadata, IndexUrl, LocalEditable, Name, ParsedUrlError, Requirement,
-    ResolvedDist, SourceAnnotations, Verbatim, VersionId, VersionOrUrlRef,
+    BuiltDist, Dist, DistributionMetadata, FileLocation, IncompatibleWheel, IndexUrl,
+    LocalEditable, Name, PrioritizedDist, RemoteSource, Requirement, RequirementSource,
+    ResolvedDist, ResolvedDistRef, SourceAnnotations, SourceDist, ToUrlError, Verbatim, VersionId,
+    VersionOrUrlRef,
 };
 use once_map::OnceMap;
-use pep440_rs::Version;
-use pep508_rs::MarkerEnvironment;
-use pypi_types::HashDigest;
-use uv_distribution::to_precise;
+use pep440_rs::{Operator, Version, VersionSpecifier, VersionSpecifiers};
+use pep508_rs::{MarkerEnvironment, MarkerTree, VerbatimUrl};
+use platform_tags::{IncompatibleTag, Tag, Tags};
+use pypi_types::{HashAlgorithm, HashDigest, Metadata23, Yanked};
+use uv_cache::{Cache, CacheBucket, WheelCache};
+use uv_distribution::{to_precise, HttpArchivePointer};
+use uv_extract::hash::Hasher;
 use uv_normalize::{ExtraName, PackageName};
+use uv_types::HashStrategy;
 
 use crate::dependency_provider::UvDependencyProvider;
 use crate::editables::Editables;
@@ -30,8 +45,11 @@ use crate::lock::{self, Lock, LockError};
 use crate::pins::FilePins;
 use crate::preferences::Preferences;
 use crate::pubgrub::{PubGrubDistribution, PubGrubPackage};
+use crate::python_requirement::PythonRequirement;
 use crate::redirect::apply_redirect;
-use crate::resolver::{InMemoryIndex, MetadataResponse, VersionsResponse};
+use crate::resolver::{
+    InMemoryIndex, MetadataResponse, Reporter as ResolverReporter, VersionsResponse,
+};
 use crate::{Manifest, ResolveError};
 
 /// Indicate the style of annotation comments, used to indicate the dependencies that requested each
@@ -46,6 +64,110 @@ pub enum AnnotationStyle {
     /// Render each annotation on its own line.
     #[default]
     Split,
+    /// Render the resolution as a nested tree, in which each top-level requirement is followed by
+    /// its transitive dependencies (as in `pipdeptree`).
+    ///
+    /// The output isn't a valid requirements file, so this style isn't exposed to `pip compile`.
+    #[serde(skip)]
+    #[cfg_attr(feature = "clap", value(skip))]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    Tree,
+    /// Render each package as a JSON object on its own line (JSON Lines), for consumption by
+    /// other tools.
+    ///
+    /// The output isn't a valid requirements file, so this style isn't exposed to `pip compile`.
+    #[serde(skip)]
+    #[cfg_attr(feature = "clap", value(skip))]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    Json,
+    /// Render each annotation as the full chain of dependents from a top-level requirement
+    /// (e.g., `# via requests -> urllib3`), with one line per shortest path. Intended for
+    /// debugging resolutions, rather than for everyday use.
+    Verbose,
+}
+
+/// Indicate the order in which packages should be emitted in the text output.
+#[derive(Debug, Default, Copy, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum SortOrder {
+    /// Sort packages alphabetically by name, with editable packages first.
+    #[default]
+    Alphabetical,
+    /// Sort packages in dependency order, such that every package appears after its
+    /// dependencies. Packages at the same level are sorted alphabetically.
+    Topological,
+}
+
+/// Indicate how index URLs should be rendered in `# from` annotation comments.
+#[derive(Debug, Default, Copy, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum IndexRedaction {
+    /// Omit any credentials from the URL (e.g., `https://index.example.com/simple`).
+    #[default]
+    Redacted,
+    /// Render the URL as it was provided by the user, preserving any environment variable
+    /// references (e.g., `https://${TOKEN}@index.example.com/simple`).
+    Verbatim,
+    /// Render the URL with all environment variables expanded, including any credentials.
+    Expanded,
+}
+
+/// The version specifier to use when exporting a resolution as a list of dependencies.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DependencyPin {
+    /// Pin each package to its resolved version (e.g., `flask==3.0.0`).
+    #[default]
+    Exact,
+    /// Require at least the resolved version of each package (e.g., `flask>=3.0.0`).
+    LowerBound,
+}
+
+/// Options for rendering a resolution in DOT format, via [`ResolutionGraph::to_dot_with_options`].
+#[derive(Debug, Clone)]
+pub struct DotOptions {
+    /// The color with which to render editable packages (e.g., `blue`), if any.
+    pub editable_color: Option<String>,
+    /// Whether to label each edge with the version range of the dependency. Omitting the labels
+    /// can make large graphs considerably easier to read.
+    pub edge_labels: bool,
+    /// The shape with which to render editable packages (e.g., `box`), if any.
+    pub editable_shape: Option<String>,
+    /// Whether to append the marker on the requirement that introduced each dependency (e.g.,
+    /// `sys_platform == 'win32'`) to its edge label, to distinguish conditional dependencies.
+    pub edge_markers: bool,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self {
+            editable_color: None,
+            edge_labels: true,
+            editable_shape: None,
+            edge_markers: false,
+        }
+    }
+}
+
+/// Options for rendering a resolution in the format of `pip-compile`, via
+/// [`ResolutionGraph::to_pip_compile_output`].
+#[derive(Debug, Default, Clone)]
+pub struct PipCompileOutputOptions {
+    /// The command line that generated the file (e.g., `pip-compile requirements.in`), to include
+    /// in the header comment. If `None`, the header is omitted.
+    pub command: Option<String>,
+    /// The time at which the file was generated, to include in the header comment. Defaults to
+    /// the current time.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Whether to annotate each package with the index from which it was fetched (e.g.,
+    /// `# from https://pypi.org/simple`).
+    pub emit_index_url: bool,
+    /// Whether to render each package name as published (e.g., `Flask`), rather than normalized
+    /// (e.g., `flask`).
+    pub verbatim_names: bool,
 }
 
 /// A complete resolution graph in which every node represents a pinned package and every edge
@@ -60,6 +182,16 @@ pub struct ResolutionGraph {
     extras: FxHashMap<PackageName, Vec<ExtraName>>,
     /// The set of editable requirements in this resolution.
     editables: Editables,
+    /// The packages that were requested directly (e.g., via a requirements file), as opposed to
+    /// those that were pulled in transitively.
+    requested: FxHashSet<PackageName>,
+    /// The marker under which each conditionally-required package was selected (e.g., for
+    /// `colorama ; sys_platform == 'win32'`). Packages that were required unconditionally are
+    /// omitted.
+    markers: FxHashMap<PackageName, MarkerTree>,
+    /// The marker on the requirement (or requirements) that introduced each dependency edge, as
+    /// opposed to the aggregated marker of the dependency itself. Unconditional edges are omitted.
+    edge_markers: FxHashMap<EdgeIndex, MarkerTree>,
     /// Any diagnostics that were encountered while building the graph.
     diagnostics: Vec<Diagnostic>,
 }
@@ -70,11 +202,17 @@ impl ResolutionGraph {
     pub(crate) fn from_state(
         selection: &SelectedDependencies<UvDependencyProvider>,
         pins: &FilePins,
+        resolver_diagnostics: &FxHashMap<PackageName, FxHashMap<Version, Diagnostic>>,
         packages: &OnceMap<PackageName, Rc<VersionsResponse>>,
         distributions: &OnceMap<VersionId, Rc<MetadataResponse>>,
         state: &State<UvDependencyProvider>,
         preferences: &Preferences,
+        hasher: &HashStrategy,
+        python_requirement: &PythonRequirement,
+        tags: Option<&Tags>,
+        requirements: &[Requirement],
         editables: Editables,
+        reporter: Option<&Arc<dyn ResolverReporter>>,
     ) -> Result<Self, ResolveError> {
         // TODO(charlie): petgraph is a really heavy and unnecessary dependency here. We should
         // write our own graph, given that our requirements are so simple.
@@ -82,6 +220,9 @@ impl ResolutionGraph {
         let mut hashes =
             FxHashMap::with_capacity_and_hasher(selection.len(), BuildHasherDefault::default());
         let mut extras = FxHashMap::default();
+        let mut requested = FxHashSet::default();
+        let mut guards = FxHashMap::<NodeIndex, MarkerDisjunction>::default();
+        let mut edge_guards = FxHashMap::<EdgeIndex, MarkerDisjunction>::default();
         let mut diagnostics = Vec::new();
 
         // Add every package to the graph.
@@ -96,7 +237,9 @@ impl ResolutionGraph {
                         Dist::from_editable(package_name.clone(), editable.clone())?.into()
                     } else {
                         pins.get(package_name, version)
-                            .expect("Every package should be pinned")
+                            .ok_or_else(|| {
+                                ResolveError::MissingPin(package_name.clone(), version.clone())
+                            })?
                             .clone()
                     };
 
@@ -106,6 +249,23 @@ impl ResolutionGraph {
                         .match_hashes(package_name, version)
                         .filter(|digests| !digests.is_empty())
                     {
+                        // Flag any preferred hashes that contradict those reported by the index.
+                        if let Some(versions_response) = packages.get(package_name) {
+                            if let VersionsResponse::Found(ref version_maps) = *versions_response {
+                                if let Some(fetched) = version_maps
+                                    .iter()
+                                    .find_map(|version_map| version_map.hashes(version))
+                                {
+                                    for (expected, actual) in hash_mismatches(digests, &fetched) {
+                                        diagnostics.push(Diagnostic::InvalidHash {
+                                            dist: pinned_package.clone(),
+                                            expected,
+                                            actual,
+                                        });
+                                    }
+                                }
+                            }
+                        }
                         hashes.insert(package_name.clone(), digests.to_vec());
                     } else if let Some(versions_response) = packages.get(package_name) {
                         if let VersionsResponse::Found(ref version_maps) = *versions_response {
@@ -119,6 +279,70 @@ impl ResolutionGraph {
                         }
                     }
 
+                    // Warn if the pinned distribution has been yanked. Yanked versions are only
+                    // selected when requested explicitly (e.g., `attrs==21.1.0`).
+                    if let ResolvedDist::Installable(dist) = &pinned_package {
+                        match dist.file().and_then(|file| file.yanked.as_ref()) {
+                            None | Some(Yanked::Bool(false)) => {}
+                            Some(Yanked::Bool(true)) => {
+                                diagnostics.push(Diagnostic::YankedPackage {
+                                    dist: pinned_package.clone(),
+                                    reason: None,
+                                });
+                            }
+                            Some(Yanked::Reason(reason)) => {
+                                diagnostics.push(Diagnostic::YankedPackage {
+                                    dist: pinned_package.clone(),
+                                    reason: Some(reason.clone()),
+                                });
+                            }
+                        }
+                    }
+
+                    // Report any diagnostics encountered while choosing this version.
+                    if let Some(diagnostic) = resolver_diagnostics
+                        .get(package_name)
+                        .and_then(|versions| versions.get(version))
+                    {
+                        diagnostics.push(diagnostic.clone());
+                    }
+
+                    // Warn if the pinned version only ships a wheel for other platforms, such that
+                    // it will be built from source on the target platform.
+                    if let (
+                        Some(tags),
+                        ResolvedDist::Installable(Dist::Source(SourceDist::Registry(_))),
+                    ) = (tags, &pinned_package)
+                    {
+                        if let Some(versions_response) = packages.get(package_name) {
+                            if let VersionsResponse::Found(ref version_maps) = *versions_response {
+                                if let Some((
+                                    Dist::Built(BuiltDist::Registry(wheel)),
+                                    IncompatibleWheel::Tag(incompatibility),
+                                )) = version_maps
+                                    .iter()
+                                    .find_map(|version_map| version_map.get(version))
+                                    .and_then(PrioritizedDist::incompatible_wheel)
+                                {
+                                    if let Some(dist_tags) =
+                                        incompatible_wheel_tags(&wheel.filename, tags)
+                                    {
+                                        diagnostics.push(Diagnostic::PlatformIncompatible {
+                                            dist: pinned_package.clone(),
+                                            dist_tags,
+                                            incompatibility: incompatibility.clone(),
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Notify the reporter that the package was pinned.
+                    if let Some(reporter) = reporter {
+                        reporter.on_pinned(package_name, &pinned_package.version_or_url());
+                    }
+
                     // Add the distribution to the graph.
                     let index = petgraph.add_node(pinned_package);
                     inverse.insert(package_name, index);
@@ -140,6 +364,21 @@ impl ResolutionGraph {
                         .match_hashes(package_name, version)
                         .filter(|digests| !digests.is_empty())
                     {
+                        // Flag any preferred hashes that contradict those of the fetched archive.
+                        if let Some(metadata_response) =
+                            distributions.get(&pinned_package.version_id())
+                        {
+                            if let MetadataResponse::Found(ref archive) = *metadata_response {
+                                for (expected, actual) in hash_mismatches(digests, &archive.hashes)
+                                {
+                                    diagnostics.push(Diagnostic::InvalidHash {
+                                        dist: pinned_package.clone().into(),
+                                        expected,
+                                        actual,
+                                    });
+                                }
+                            }
+                        }
                         hashes.insert(package_name.clone(), digests.to_vec());
                     } else if let Some(metadata_response) =
                         distributions.get(&pinned_package.version_id())
@@ -151,6 +390,11 @@ impl ResolutionGraph {
                         }
                     }
 
+                    // Notify the reporter that the package was pinned.
+                    if let Some(reporter) = reporter {
+                        reporter.on_pinned(package_name, &pinned_package.version_or_url());
+                    }
+
                     // Add the distribution to the graph.
                     let index = petgraph.add_node(pinned_package.into());
                     inverse.insert(package_name, index);
@@ -175,18 +419,12 @@ impl ResolutionGraph {
                             });
                         }
                     } else {
-                        let response = distributions.get(&dist.version_id()).unwrap_or_else(|| {
-                            panic!(
-                                "Every package should have metadata: {:?}",
-                                dist.version_id()
-                            )
-                        });
+                        let response = distributions
+                            .get(&dist.version_id())
+                            .ok_or_else(|| ResolveError::MissingMetadata(dist.version_id()))?;
 
                         let MetadataResponse::Found(archive) = &*response else {
-                            panic!(
-                                "Every package should have metadata: {:?}",
-                                dist.version_id()
-                            )
+                            return Err(ResolveError::MissingMetadata(dist.version_id()));
                         };
 
                         if archive.metadata.provides_extras.contains(extra) {
@@ -197,9 +435,9 @@ impl ResolutionGraph {
                         } else {
                             let pinned_package = pins
                                 .get(package_name, version)
-                                .unwrap_or_else(|| {
-                                    panic!("Every package should be pinned: {package_name:?}")
-                                })
+                                .ok_or_else(|| {
+                                    ResolveError::MissingPin(package_name.clone(), version.clone())
+                                })?
                                 .clone();
 
                             diagnostics.push(Diagnostic::MissingExtra {
@@ -229,18 +467,12 @@ impl ResolutionGraph {
                             });
                         }
                     } else {
-                        let response = distributions.get(&dist.version_id()).unwrap_or_else(|| {
-                            panic!(
-                                "Every package should have metadata: {:?}",
-                                dist.version_id()
-                            )
-                        });
+                        let response = distributions
+                            .get(&dist.version_id())
+                            .ok_or_else(|| ResolveError::MissingMetadata(dist.version_id()))?;
 
                         let MetadataResponse::Found(archive) = &*response else {
-                            panic!(
-                                "Every package should have metadata: {:?}",
-                                dist.version_id()
-                            )
+                            return Err(ResolveError::MissingMetadata(dist.version_id()));
                         };
 
                         if archive.metadata.provides_extras.contains(extra) {
@@ -266,6 +498,47 @@ impl ResolutionGraph {
             };
         }
 
+        // In `--require-hashes` mode, flag any packages for which no hash was recorded, such that
+        // they can be reported in a single pass rather than failing on the first.
+        if matches!(hasher, HashStrategy::Validate(_)) {
+            for index in petgraph.node_indices() {
+                let dist = &petgraph[index];
+                if editables.get(dist.name()).is_some() {
+                    continue;
+                }
+                if hashes.get(dist.name()).map_or(true, Vec::is_empty) {
+                    diagnostics.push(Diagnostic::MissingHash { dist: dist.clone() });
+                }
+            }
+        }
+
+        // Flag any packages that aren't compatible with the installed Python version, which may
+        // differ from the target version (e.g., when resolving for another Python version).
+        for (package, version) in selection {
+            let PubGrubPackage::Package(package_name, None, url) = package else {
+                continue;
+            };
+            let Some(requires_python) = Self::with_metadata(
+                package_name,
+                url.as_ref(),
+                version,
+                &editables,
+                distributions,
+                |metadata| metadata.requires_python.clone(),
+            )
+            .flatten() else {
+                continue;
+            };
+            let installed_python = python_requirement.installed();
+            if !requires_python.contains(installed_python) {
+                diagnostics.push(Diagnostic::IncompatiblePythonVersion {
+                    dist: petgraph[inverse[package_name]].clone(),
+                    requires_python,
+                    installed_python: installed_python.version.clone(),
+                });
+            }
+        }
+
         // Add every edge to the graph.
         for (package, version) in selection {
             for id in &state.incompatibilities[package] {
@@ -284,7 +557,36 @@ impl ResolutionGraph {
                         continue;
                     }
 
-                    let PubGrubPackage::Package(self_package, _, _) = self_package else {
+                    // Track the packages that are required by the root directly.
+                    if let (
+                        PubGrubPackage::Root(_),
+                        PubGrubPackage::Package(dependency_package, _, _)
+                        | PubGrubPackage::Extra(dependency_package, _, _),
+                    ) = (self_package, dependency_package)
+                    {
+                        requested.insert(dependency_package.clone());
+
+                        // Record the markers on the direct requirement. Editables are always
+                        // required unconditionally.
+                        if let Some(&dependency_index) = inverse.get(dependency_package) {
+                            let guard = guards.entry(dependency_index).or_default();
+                            let mut found = false;
+                            for requirement in requirements
+                                .iter()
+                                .filter(|requirement| requirement.name == *dependency_package)
+                            {
+                                found = true;
+                                guard.insert(requirement.marker.clone().into_iter().collect());
+                            }
+                            if !found {
+                                guard.insert(Vec::new());
+                            }
+                        }
+                        continue;
+                    }
+
+                    let PubGrubPackage::Package(self_package, self_extra, self_url) = self_package
+                    else {
                         continue;
                     };
                     let PubGrubPackage::Package(dependency_package, _, _) = dependency_package
@@ -298,25 +600,230 @@ impl ResolutionGraph {
                     }
 
                     if self_version.contains(version) {
-                        let self_index = &inverse[self_package];
-                        let dependency_index = &inverse[dependency_package];
-                        petgraph.update_edge(
-                            *self_index,
-                            *dependency_index,
-                            dependency_range.clone(),
-                        );
+                        let self_index = inverse[self_package];
+                        let dependency_index = inverse[dependency_package];
+                        // A package may depend on the same package through multiple paths (e.g.,
+                        // via its base requirements and via an extra), so intersect the ranges
+                        // rather than retaining the last one seen.
+                        let edge =
+                            if let Some(edge) = petgraph.find_edge(self_index, dependency_index) {
+                                let range = petgraph[edge].intersection(dependency_range);
+                                petgraph[edge] = range;
+                                edge
+                            } else {
+                                petgraph.add_edge(
+                                    self_index,
+                                    dependency_index,
+                                    dependency_range.clone(),
+                                )
+                            };
+
+                        // Record the markers on the requirement that introduced the edge, with
+                        // any enabled extras simplified away.
+                        let extras = self_extra.as_slice();
+                        let guard = guards.entry(dependency_index).or_default();
+                        let edge_guard = edge_guards.entry(edge).or_default();
+                        let mut found = false;
+                        if let Some(requires_dist) = Self::with_metadata(
+                            self_package,
+                            self_url.as_ref(),
+                            version,
+                            &editables,
+                            distributions,
+                            |metadata| metadata.requires_dist.clone(),
+                        ) {
+                            for requirement in requires_dist
+                                .iter()
+                                .filter(|requirement| requirement.name == *dependency_package)
+                            {
+                                let marker = requirement.marker.as_ref();
+                                if marker.is_some_and(|marker| {
+                                    !marker.evaluate_optional_environment(None, extras)
+                                }) {
+                                    continue;
+                                }
+                                found = true;
+                                let conjunction = marker
+                                    .cloned()
+                                    .and_then(|marker| marker.simplify_extras(extras))
+                                    .into_iter()
+                                    .collect::<Vec<_>>();
+                                guard.insert(conjunction.clone());
+                                edge_guard.insert(conjunction);
+                            }
+                        }
+                        if !found {
+                            guard.insert(Vec::new());
+                            edge_guard.insert(Vec::new());
+                        }
                     }
                 }
             }
         }
 
-        Ok(Self {
+        // Flag any enabled extras that didn't add any packages to the resolution. A dependency is
+        // considered to have been added by an extra if it isn't required by the base package, by
+        // the user, or by any other package in the graph.
+        for (package, version) in selection {
+            let PubGrubPackage::Package(package_name, None, url) = package else {
+                continue;
+            };
+            let Some(package_extras) = extras.get(package_name) else {
+                continue;
+            };
+            let Some(requires_dist) = Self::with_metadata(
+                package_name,
+                url.as_ref(),
+                version,
+                &editables,
+                distributions,
+                |metadata| metadata.requires_dist.clone(),
+            ) else {
+                continue;
+            };
+            let index = inverse[package_name];
+            let base = requires_dist
+                .iter()
+                .filter(|requirement| {
+                    requirement.marker.as_ref().map_or(true, |marker| {
+                        marker.evaluate_optional_environment(None, &[])
+                    })
+                })
+                .map(|requirement| &requirement.name)
+                .collect::<FxHashSet<_>>();
+            for extra in package_extras {
+                let added = requires_dist
+                    .iter()
+                    .filter(|requirement| {
+                        requirement.marker.as_ref().is_some_and(|marker| {
+                            marker.evaluate_optional_environment(None, std::slice::from_ref(extra))
+                                && !marker.evaluate_optional_environment(None, &[])
+                        })
+                    })
+                    .filter_map(|requirement| inverse.get(&requirement.name).copied())
+                    .any(|dependency| {
+                        let name = petgraph[dependency].name();
+                        dependency != index
+                            && !base.contains(name)
+                            && !requested.contains(name)
+                            && petgraph
+                                .neighbors_directed(dependency, Direction::Incoming)
+                                .all(|dependent| dependent == index)
+                    });
+                if !added {
+                    diagnostics.push(Diagnostic::UnusedExtra {
+                        dist: petgraph[index].clone(),
+                        extra: extra.clone(),
+                    });
+                }
+            }
+        }
+
+        // Flag any direct requirements that lack a lower bound (e.g., `requests` or `requests<3`),
+        // since their resolution can change as new versions are published. Packages that are only
+        // required transitively are not flagged.
+        // A package may be required multiple times (e.g., with different markers), in which case
+        // it's only flagged if none of its requirements are bounded.
+        let mut bounded = BTreeMap::<&PackageName, bool>::new();
+        for requirement in requirements
+            .iter()
+            .filter(|requirement| requested.contains(&requirement.name))
+        {
+            let has_bound = match &requirement.source {
+                RequirementSource::Registry { specifier, .. } => has_lower_bound(specifier),
+                _ => true,
+            };
+            *bounded.entry(&requirement.name).or_default() |= has_bound;
+        }
+        for (name, bounded) in bounded {
+            if !bounded {
+                diagnostics.push(Diagnostic::MissingLowerBound { name: name.clone() });
+            }
+        }
+
+        let markers = guards
+            .into_iter()
+            .filter_map(|(index, guard)| {
+                let marker = guard.into_marker()?;
+                Some((petgraph[index].name().clone(), marker))
+            })
+            .collect();
+        let edge_markers = edge_guards
+            .into_iter()
+            .filter_map(|(edge, guard)| Some((edge, guard.into_marker()?)))
+            .collect();
+
+        let mut graph = Self {
             petgraph,
             hashes,
             extras,
             editables,
+            requested,
+            markers,
+            edge_markers,
             diagnostics,
-        })
+        };
+
+        // Flag any packages that were added to the graph more than once (e.g., for an editable
+        // that conflicts with a registry package).
+        let duplicates = graph
+            .duplicate_names()
+            .into_iter()
+            .map(|(name, dists)| Diagnostic::DuplicatePackage {
+                name,
+                dists: dists.into_iter().cloned().collect(),
+            })
+            .collect::<Vec<_>>();
+        graph.diagnostics.extend(duplicates);
+
+        // PubGrub should prevent genuine cycles, but malformed or locally-patched metadata can
+        // still introduce them. Rather than failing, attach a diagnostic for each cycle.
+        let cycles = graph
+            .cycles()
+            .into_iter()
+            .map(|cycle| Diagnostic::CyclicDependency { cycle })
+            .collect::<Vec<_>>();
+        graph.diagnostics.extend(cycles);
+
+        // Duplicate packages are reported as diagnostics, so they're excluded here.
+        #[cfg(debug_assertions)]
+        if let Err(errors) = graph.validate() {
+            let errors = errors
+                .iter()
+                .filter(|error| !matches!(error, ValidationError::DuplicateNode(_)))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            assert!(
+                errors.is_empty(),
+                "Resolution graph is inconsistent: {}",
+                errors.join("; ")
+            );
+        }
+
+        Ok(graph)
+    }
+
+    /// Apply the given function to the metadata of the given package, if it's available.
+    fn with_metadata<T>(
+        package_name: &PackageName,
+        url: Option<&VerbatimUrl>,
+        version: &Version,
+        editables: &Editables,
+        distributions: &OnceMap<VersionId, Rc<MetadataResponse>>,
+        f: impl FnOnce(&Metadata23) -> T,
+    ) -> Option<T> {
+        if let Some((_, metadata, _)) = editables.get(package_name) {
+            return Some(f(metadata));
+        }
+        let dist = match url {
+            Some(url) => PubGrubDistribution::from_url(package_name, url),
+            None => PubGrubDistribution::from_registry(package_name, version),
+        };
+        let response = distributions.get(&dist.version_id())?;
+        let MetadataResponse::Found(archive) = &*response else {
+            return None;
+        };
+        Some(f(&archive.metadata))
     }
 
     /// Return the number of packages in the graph.
@@ -336,519 +843,6548 @@ impl ResolutionGraph {
             .any(|index| self.petgraph[index].name() == name)
     }
 
-    /// Iterate over the [`ResolvedDist`] entities in this resolution.
-    pub fn into_distributions(self) -> impl Iterator<Item = ResolvedDist> {
+    /// Return the top-level packages in this resolution: those that were requested directly (e.g.,
+    /// via a requirements file), along with any editables (e.g., via `-e .`).
+    ///
+    /// Note that this is not the same as the nodes without incoming edges. A requested package
+    /// remains a root even if another package depends on it, and a package that was only pulled
+    /// in transitively is never a root, even if nothing depends on it in the resolved graph
+    /// (e.g., if its dependent was removed via [`ResolutionGraph::for_environment`]). For the
+    /// latter, see [`ResolutionGraph::leaf_packages`].
+    pub fn roots(&self) -> impl Iterator<Item = &ResolvedDist> {
         self.petgraph
-            .into_nodes_edges()
-            .0
-            .into_iter()
-            .map(|node| node.weight)
+            .node_indices()
+            .filter(|index| self.is_root_index(*index))
+            .map(|index| &self.petgraph[index])
     }
 
-    /// Return the [`Diagnostic`]s that were encountered while building the graph.
-    pub fn diagnostics(&self) -> &[Diagnostic] {
-        &self.diagnostics
+    /// Returns `true` if the given package is a top-level package in this resolution, as in
+    /// [`ResolutionGraph::roots`].
+    pub fn is_root(&self, name: &PackageName) -> bool {
+        self.petgraph
+            .node_indices()
+            .find(|index| self.petgraph[*index].name() == name)
+            .is_some_and(|index| self.is_root_index(index))
     }
 
-    /// Return the underlying graph.
-    pub fn petgraph(
-        &self,
-    ) -> &petgraph::graph::Graph<ResolvedDist, Range<Version>, petgraph::Directed> {
-        &self.petgraph
+    /// Returns `true` if the given node is a top-level package.
+    fn is_root_index(&self, index: NodeIndex) -> bool {
+        let dist = &self.petgraph[index];
+        self.requested.contains(dist.name()) || self.editables.get(dist.name()).is_some()
     }
 
-    /// Return the marker tree specific to this resolution.
-    ///
-    /// This accepts a manifest, in-memory-index and marker environment. All
-    /// of which should be the same values given to the resolver that produced
-    /// this graph.
+    /// Return the leaf packages in this resolution: those that were pulled in transitively, but
+    /// that no other package in the resolution depends on.
     ///
-    /// The marker tree returned corresponds to an expression that, when true,
-    /// this resolution is guaranteed to be correct. Note though that it's
-    /// possible for resolution to be correct even if the returned marker
-    /// expression is false.
+    /// Unlike [`ResolutionGraph::roots`], this never includes packages that were requested
+    /// directly or editables, even if nothing depends on them. Dependencies of an extra count as
+    /// dependencies of the base package, so a package that's only required via an enabled extra
+    /// is not a leaf.
+    pub fn leaf_packages(&self) -> impl Iterator<Item = &ResolvedDist> {
+        self.petgraph
+            .node_indices()
+            .filter(|index| {
+                !self.is_root_index(*index)
+                    && self
+                        .petgraph
+                        .neighbors_directed(*index, Direction::Incoming)
+                        .next()
+                        .is_none()
+            })
+            .map(|index| &self.petgraph[index])
+    }
+
+    /// Return every package that's reachable from the given package via its dependencies, in
+    /// breadth-first order, or `None` if the package isn't in the graph.
     ///
-    /// For example, if the root package has a dependency `foo; sys_platform ==
-    /// "macos"` and resolution was performed on Linux, then the marker tree
-    /// returned will contain a `sys_platform == "linux"` expression. This
-    /// means that whenever the marker expression evaluates to true (i.e., the
-    /// current platform is Linux), then the resolution here is correct. But
-    /// it is possible that the resolution is also correct on other platforms
-    /// that aren't macOS, such as Windows. (It is unclear at time of writing
-    /// whether this is fundamentally impossible to compute, or just impossible
-    /// to compute in some cases.)
-    pub fn marker_tree(
-        &self,
-        manifest: &Manifest,
-        index: &InMemoryIndex,
-        marker_env: &MarkerEnvironment,
-    ) -> Result<pep508_rs::MarkerTree, Box<ParsedUrlError>> {
-        use pep508_rs::{
-            MarkerExpression, MarkerOperator, MarkerTree, MarkerValue, MarkerValueString,
-            MarkerValueVersion,
-        };
+    /// Each node in the graph represents a package along with its enabled extras, so the closure
+    /// includes the dependencies of any extras that were requested in this resolution (e.g., via
+    /// `foo[bar]`), but never those of extras that weren't.
+    pub fn transitive_dependencies<'a>(
+        &'a self,
+        name: &PackageName,
+    ) -> Option<impl Iterator<Item = &'a ResolvedDist>> {
+        self.transitive_dependencies_up_to_depth(name, usize::MAX)
+    }
 
-        /// A subset of the possible marker values.
-        ///
-        /// We only track the marker parameters that are referenced in a marker
-        /// expression. We'll use references to the parameter later to generate
-        /// values based on the current marker environment.
-        #[derive(Debug, Eq, Hash, PartialEq)]
-        enum MarkerParam {
-            Version(MarkerValueVersion),
-            String(MarkerValueString),
-        }
+    /// Return every package that's reachable from the given package via at most `depth`
+    /// dependency edges, in breadth-first order, or `None` if the package isn't in the graph.
+    ///
+    /// A depth of `1` returns the direct dependencies of the package.
+    pub fn transitive_dependencies_up_to_depth<'a>(
+        &'a self,
+        name: &PackageName,
+        depth: usize,
+    ) -> Option<impl Iterator<Item = &'a ResolvedDist>> {
+        let start = self
+            .petgraph
+            .node_indices()
+            .find(|index| self.petgraph[*index].name() == name)?;
 
-        /// Add all marker parameters from the given tree to the given set.
-        fn add_marker_params_from_tree(marker_tree: &MarkerTree, set: &mut FxHashSet<MarkerParam>) {
-            match *marker_tree {
-                MarkerTree::Expression(ref expr) => {
-                    add_marker_value(&expr.l_value, set);
-                    add_marker_value(&expr.r_value, set);
-                }
-                MarkerTree::And(ref exprs) | MarkerTree::Or(ref exprs) => {
-                    for expr in exprs {
-                        add_marker_params_from_tree(expr, set);
-                    }
-                }
+        let mut seen = FxHashSet::default();
+        seen.insert(start);
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((index, distance)) = queue.pop_front() {
+            if distance >= depth {
+                continue;
             }
-        }
-
-        /// Add the marker value, if it's a marker parameter, to the set
-        /// given.
-        fn add_marker_value(value: &MarkerValue, set: &mut FxHashSet<MarkerParam>) {
-            match *value {
-                MarkerValue::MarkerEnvVersion(ref value_version) => {
-                    set.insert(MarkerParam::Version(value_version.clone()));
-                }
-                MarkerValue::MarkerEnvString(ref value_string) => {
-                    set.insert(MarkerParam::String(value_string.clone()));
+            for dependency in self.petgraph.neighbors_directed(index, Direction::Outgoing) {
+                if seen.insert(dependency) {
+                    order.push(dependency);
+                    queue.push_back((dependency, distance + 1));
                 }
-                // We specifically don't care about these for the
-                // purposes of generating a marker string for a lock
-                // file. Quoted strings are marker values given by the
-                // user. We don't track those here, since we're only
-                // interested in which markers are used.
-                MarkerValue::Extra | MarkerValue::QuotedString(_) => {}
             }
         }
 
-        let mut seen_marker_values = FxHashSet::default();
-        for i in self.petgraph.node_indices() {
-            let dist = &self.petgraph[i];
-            let version_id = match dist.version_or_url() {
-                VersionOrUrlRef::Version(version) => {
-                    VersionId::from_registry(dist.name().clone(), version.clone())
-                }
-                VersionOrUrlRef::Url(verbatim_url) => VersionId::from_url(verbatim_url.raw()),
-            };
-            let res = index
-                .distributions
-                .get(&version_id)
-                .expect("every package in resolution graph has metadata");
-            let MetadataResponse::Found(archive, ..) = &*res else {
-                panic!(
-                    "Every package should have metadata: {:?}",
-                    dist.version_id()
-                )
-            };
-            let requirements: Vec<_> = archive
-                .metadata
-                .requires_dist
-                .iter()
-                .cloned()
-                .map(Requirement::from_pep508)
-                .collect::<Result<_, _>>()?;
-            for req in manifest.apply(requirements.iter()) {
-                let Some(ref marker_tree) = req.marker else {
-                    continue;
-                };
-                add_marker_params_from_tree(marker_tree, &mut seen_marker_values);
-            }
-        }
+        Some(order.into_iter().map(|index| &self.petgraph[index]))
+    }
 
-        // Ensure that we consider markers from direct dependencies.
-        let direct_reqs = manifest.requirements.iter().chain(
-            manifest
-                .editables
-                .iter()
-                .flat_map(|(_, _, uv_requirements)| &uv_requirements.dependencies),
-        );
-        for direct_req in manifest.apply(direct_reqs) {
-            let Some(ref marker_tree) = direct_req.marker else {
-                continue;
-            };
-            add_marker_params_from_tree(marker_tree, &mut seen_marker_values);
-        }
+    /// Iterate over the packages that depend directly on the given package, sorted by name.
+    ///
+    /// This is the inverse of [`ResolutionGraph::dependencies`], and can be used to determine which
+    /// packages would be affected by removing or changing the given package. Yields nothing if the
+    /// package isn't in the graph, or if it's only required directly (e.g., by a requirements file).
+    pub fn dependents<'a>(&'a self, name: &PackageName) -> impl Iterator<Item = &'a ResolvedDist> {
+        self.neighbors(name, Direction::Incoming).into_iter()
+    }
 
-        // Generate the final marker expression as a conjunction of
-        // strict equality terms.
-        let mut conjuncts = vec![];
-        for marker_param in seen_marker_values {
-            let expr = match marker_param {
-                MarkerParam::Version(value_version) => {
-                    let from_env = marker_env.get_version(&value_version);
-                    MarkerExpression {
-                        l_value: MarkerValue::MarkerEnvVersion(value_version),
-                        operator: MarkerOperator::Equal,
-                        r_value: MarkerValue::QuotedString(from_env.to_string()),
+    /// Iterate over the packages that require the given package directly, along with the version
+    /// range under which each of them requires it.
+    ///
+    /// This is the information used to render `# via` annotations. Yields nothing if the package
+    /// isn't in the graph.
+    pub fn packages_requiring<'a>(
+        &'a self,
+        name: &PackageName,
+    ) -> impl Iterator<Item = (&'a ResolvedDist, &'a Range<Version>)> {
+        self.petgraph
+            .node_indices()
+            .find(|index| self.petgraph[*index].name() == name)
+            .into_iter()
+            .flat_map(|index| self.requiring(index))
+    }
+
+    /// Iterate over the packages that require the package at the given node directly, along with
+    /// the version range on each edge.
+    fn requiring(
+        &self,
+        index: NodeIndex,
+    ) -> impl Iterator<Item = (&ResolvedDist, &Range<Version>)> {
+        self.petgraph
+            .edges_directed(index, Direction::Incoming)
+            .map(|edge| (&self.petgraph[edge.source()], edge.weight()))
+    }
+
+    /// Return the direct dependencies of the given package, sorted by name.
+    ///
+    /// Returns an empty list if the package isn't in the graph, or if it has no dependencies.
+    pub fn dependencies(&self, name: &PackageName) -> Vec<&ResolvedDist> {
+        self.neighbors(name, Direction::Outgoing)
+    }
+
+    /// Return the dependency cycles in this resolution.
+    ///
+    /// Each cycle is a strongly-connected component with more than one package, sorted by package
+    /// name; the cycles themselves are sorted as well. Edges between a package and its own extras
+    /// are omitted when the graph is constructed, and so are never reported as cycles.
+    pub fn cycles(&self) -> Vec<Vec<PackageName>> {
+        let mut cycles = petgraph::algo::tarjan_scc(&self.petgraph)
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| {
+                let mut cycle = component
+                    .into_iter()
+                    .map(|index| self.petgraph[index].name().clone())
+                    .collect::<Vec<_>>();
+                cycle.sort_unstable();
+                cycle
+            })
+            .collect::<Vec<_>>();
+        cycles.sort_unstable();
+        cycles
+    }
+
+    /// Return the neighbors of the given package in the given direction, sorted by name.
+    fn neighbors(&self, name: &PackageName, direction: Direction) -> Vec<&ResolvedDist> {
+        let Some(index) = self
+            .petgraph
+            .node_indices()
+            .find(|index| self.petgraph[*index].name() == name)
+        else {
+            return Vec::new();
+        };
+        // Edges between a package and its own extras are omitted when the graph is constructed,
+        // but guard against self-edges regardless.
+        let mut neighbors = self
+            .petgraph
+            .neighbors_directed(index, direction)
+            .filter(|neighbor| *neighbor != index)
+            .map(|neighbor| &self.petgraph[neighbor])
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable_by_key(|dist| dist.name());
+        neighbors.dedup_by_key(|dist| dist.name());
+        neighbors
+    }
+
+    /// Iterate over the [`ResolvedDist`] entities in this resolution.
+    pub fn into_distributions(self) -> impl Iterator<Item = ResolvedDist> {
+        self.petgraph
+            .into_nodes_edges()
+            .0
+            .into_iter()
+            .map(|node| node.weight)
+    }
+
+    /// Consume the resolution, returning its [`ResolvedDist`] entities in topological order, such
+    /// that every package appears after its dependencies (e.g., for installation).
+    ///
+    /// Packages that aren't ordered relative to one another are sorted alphabetically by name, so
+    /// the order is deterministic. Unlike [`ResolutionGraph::install_order`], dependency cycles
+    /// aren't an error: each cycle is broken at its alphabetically-first package. Any cycles are
+    /// reported as a [`Diagnostic::CyclicDependency`] when the graph is constructed, and so should
+    /// be read from [`ResolutionGraph::diagnostics`] before the resolution is consumed.
+    pub fn into_distributions_topo(self) -> Vec<ResolvedDist> {
+        let order = self.topological_order();
+        let mut dists = self
+            .petgraph
+            .into_nodes_edges()
+            .0
+            .into_iter()
+            .map(|node| Some(node.weight))
+            .collect::<Vec<_>>();
+        order
+            .into_iter()
+            .filter_map(|index| dists[index.index()].take())
+            .collect()
+    }
+
+    /// Return every enabled extra that didn't add any packages to the resolution, i.e., for which
+    /// every dependency of the extra was already required by other means (or which had no
+    /// dependencies at all), as reported by [`Diagnostic::UnusedExtra`].
+    pub fn unused_extras(&self) -> Vec<(PackageName, ExtraName)> {
+        self.diagnostics
+            .iter()
+            .filter_map(|diagnostic| match diagnostic {
+                Diagnostic::UnusedExtra { dist, extra } => {
+                    Some((dist.name().clone(), extra.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Return every package name that appears in the graph more than once, along with each of its
+    /// distributions, sorted by name.
+    ///
+    /// The resolver never selects multiple versions of the same package, but duplicates can arise
+    /// from conflicting sources, e.g., an editable that conflicts with a registry package.
+    pub fn duplicate_names(&self) -> Vec<(PackageName, Vec<&ResolvedDist>)> {
+        let mut dists = BTreeMap::<&PackageName, Vec<&ResolvedDist>>::new();
+        for dist in self.petgraph.node_weights() {
+            dists.entry(dist.name()).or_default().push(dist);
+        }
+        dists
+            .into_iter()
+            .filter(|(_, dists)| dists.len() > 1)
+            .map(|(name, dists)| (name.clone(), dists))
+            .collect()
+    }
+
+    /// Check the internal consistency of the graph, returning every violated invariant.
+    ///
+    /// Every package must appear in the graph exactly once, every edge must connect two packages
+    /// in the graph, and every hash, extra, and marker must belong to a package in the graph. A
+    /// violation indicates a bug in the construction of the graph, rather than in the resolution
+    /// itself.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        errors.extend(
+            self.duplicate_names()
+                .into_iter()
+                .map(|(name, _)| ValidationError::DuplicateNode(name)),
+        );
+
+        let names = self
+            .petgraph
+            .node_weights()
+            .map(Name::name)
+            .collect::<FxHashSet<_>>();
+
+        // Every edge endpoint must be the node that's looked up by its package name; otherwise,
+        // the edge is invisible to any traversal that starts from a package name.
+        let inverse = self
+            .petgraph
+            .node_indices()
+            .map(|index| (self.petgraph[index].name(), index))
+            .collect::<FxHashMap<_, _>>();
+        let reachable =
+            |index: NodeIndex| inverse.get(self.petgraph[index].name()).copied() == Some(index);
+
+        for edge in self.petgraph.edge_references() {
+            if !reachable(edge.source()) || !reachable(edge.target()) {
+                errors.push(ValidationError::MissingEdgeTarget {
+                    dependent: edge.source().index(),
+                    dependency: edge.target().index(),
+                });
+            }
+        }
+
+        errors.extend(
+            self.hashes
+                .keys()
+                .filter(|name| !names.contains(name))
+                .sorted()
+                .map(|name| ValidationError::OrphanedHashEntry(name.clone())),
+        );
+        errors.extend(
+            self.extras
+                .keys()
+                .filter(|name| !names.contains(name))
+                .sorted()
+                .map(|name| ValidationError::OrphanedExtraEntry(name.clone())),
+        );
+        errors.extend(
+            self.markers
+                .keys()
+                .filter(|name| !names.contains(name))
+                .sorted()
+                .map(|name| ValidationError::OrphanedMarkerEntry(name.clone())),
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Return the [`Diagnostic`]s that were encountered while building the graph.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Return a [`Diagnostic::PinnedByDependent`] for every dependency that was constrained to a
+    /// single version by one of its dependents (e.g., `requests` requiring `idna==3.7`).
+    ///
+    /// Such pins are the most constrained edges in the graph, and thus the most likely to cause
+    /// conflicts. Requirements provided directly by the user aren't represented as edges, and so
+    /// are not included. The diagnostics are sorted by package name, then by dependent.
+    pub fn pinned_by_dependents(&self) -> Vec<Diagnostic> {
+        let mut pins = self
+            .petgraph
+            .edge_references()
+            .filter_map(|edge| {
+                let version = edge.weight().as_singleton()?;
+                Some((
+                    self.petgraph[edge.target()].name(),
+                    self.petgraph[edge.source()].name(),
+                    version,
+                ))
+            })
+            .collect::<Vec<_>>();
+        pins.sort_unstable();
+        pins.into_iter()
+            .map(
+                |(package, pinned_by, version)| Diagnostic::PinnedByDependent {
+                    package: package.clone(),
+                    pinned_by: pinned_by.clone(),
+                    version: version.clone(),
+                },
+            )
+            .collect()
+    }
+
+    /// Return the packages in this resolution that have no compatible wheel, and thus require a
+    /// build at install time, sorted by name.
+    ///
+    /// For registry distributions, this inspects the artifacts available in the index for the
+    /// resolved version. URL, Git, path, and directory source distributions always require a
+    /// build.
+    pub fn sdist_only_packages(&self, index: &InMemoryIndex) -> Vec<&PackageName> {
+        let mut packages = self
+            .petgraph
+            .node_indices()
+            .filter_map(|node| {
+                let dist = &self.petgraph[node];
+                let requires_build = match dist {
+                    ResolvedDist::Installed(_) => false,
+                    ResolvedDist::Installable(Dist::Built(BuiltDist::Registry(_)))
+                    | ResolvedDist::Installable(Dist::Source(SourceDist::Registry(_))) => {
+                        let VersionOrUrlRef::Version(version) = dist.version_or_url() else {
+                            return None;
+                        };
+                        let response = index.packages.get(dist.name())?;
+                        let VersionsResponse::Found(ref version_maps) = *response else {
+                            return None;
+                        };
+                        version_maps
+                            .iter()
+                            .find_map(|version_map| version_map.get(version))
+                            .and_then(PrioritizedDist::get)
+                            .is_some_and(|compatible| {
+                                matches!(
+                                    compatible.for_installation(),
+                                    ResolvedDistRef::Installable(Dist::Source(_))
+                                )
+                            })
+                    }
+                    ResolvedDist::Installable(Dist::Built(_)) => false,
+                    ResolvedDist::Installable(Dist::Source(_)) => true,
+                };
+                requires_build.then_some(dist.name())
+            })
+            .collect::<Vec<_>>();
+        packages.sort_unstable();
+        packages
+    }
+
+    /// Return the effective `Requires-Python` lower bound implied by this resolution (e.g.,
+    /// `>=3.9`), i.e., the tightest lower bound declared by any package, as recorded in the given
+    /// in-memory index.
+    ///
+    /// Packages that declare no `Requires-Python` (or no lower bound), or whose metadata isn't
+    /// available in the index, are treated as unconstrained. Returns `None` if no package declares
+    /// a lower bound, or a [`Diagnostic::ConflictingRequiresPython`] if the lower bound of one
+    /// package excludes every version allowed by the upper bound of another (e.g., `>=3.12` and
+    /// `<3.11`).
+    pub fn requires_python(
+        &self,
+        index: &InMemoryIndex,
+    ) -> Result<Option<VersionSpecifiers>, Diagnostic> {
+        // The tightest lower and upper bounds, as `(version, inclusive, dist, requires_python)`.
+        let mut floor: Option<(Version, bool, &ResolvedDist, VersionSpecifiers)> = None;
+        let mut ceiling: Option<(Version, bool, &ResolvedDist, VersionSpecifiers)> = None;
+        for dist in self.petgraph.node_weights() {
+            let Some(requires_python) = self
+                .with_index_metadata(dist, index, |metadata| metadata.requires_python.clone())
+                .flatten()
+            else {
+                continue;
+            };
+            for specifier in requires_python.iter() {
+                let version = specifier.version();
+                let (lower, upper) = match specifier.operator() {
+                    Operator::Equal | Operator::ExactEqual => (Some(true), Some(true)),
+                    Operator::EqualStar | Operator::TildeEqual | Operator::GreaterThanEqual => {
+                        (Some(true), None)
+                    }
+                    Operator::GreaterThan => (Some(false), None),
+                    Operator::LessThan => (None, Some(false)),
+                    Operator::LessThanEqual => (None, Some(true)),
+                    Operator::NotEqual | Operator::NotEqualStar => (None, None),
+                };
+                if let Some(inclusive) = lower {
+                    if floor
+                        .as_ref()
+                        .map_or(true, |(existing, existing_inclusive, ..)| {
+                            version > existing
+                                || (version == existing && *existing_inclusive && !inclusive)
+                        })
+                    {
+                        floor = Some((version.clone(), inclusive, dist, requires_python.clone()));
                     }
                 }
-                MarkerParam::String(value_string) => {
-                    let from_env = marker_env.get_string(&value_string);
-                    MarkerExpression {
-                        l_value: MarkerValue::MarkerEnvString(value_string),
-                        operator: MarkerOperator::Equal,
-                        r_value: MarkerValue::QuotedString(from_env.to_string()),
+                if let Some(inclusive) = upper {
+                    if ceiling
+                        .as_ref()
+                        .map_or(true, |(existing, existing_inclusive, ..)| {
+                            version < existing
+                                || (version == existing && *existing_inclusive && !inclusive)
+                        })
+                    {
+                        ceiling = Some((version.clone(), inclusive, dist, requires_python.clone()));
                     }
                 }
+            }
+        }
+
+        if let (
+            Some((lower, lower_inclusive, dist, requires_python)),
+            Some((upper, upper_inclusive, conflicting_dist, conflicting_requires_python)),
+        ) = (floor.as_ref(), ceiling)
+        {
+            if *lower > upper || (*lower == upper && !(*lower_inclusive && upper_inclusive)) {
+                return Err(Diagnostic::ConflictingRequiresPython {
+                    dist: (*dist).clone(),
+                    requires_python: requires_python.clone(),
+                    conflicting_dist: conflicting_dist.clone(),
+                    conflicting_requires_python,
+                });
+            }
+        }
+
+        Ok(floor.map(|(version, inclusive, ..)| {
+            let operator = if inclusive {
+                Operator::GreaterThanEqual
+            } else {
+                Operator::GreaterThan
             };
-            conjuncts.push(MarkerTree::Expression(expr));
+            VersionSpecifiers::from(
+                VersionSpecifier::from_version(operator, version.without_local())
+                    .expect("lower bounds are compatible with release versions"),
+            )
+        }))
+    }
+
+    /// Return the hashes for every distribution in this resolution, ordered by package name.
+    pub fn hash_map(&self) -> BTreeMap<&PackageName, &[HashDigest]> {
+        self.hashes
+            .iter()
+            .map(|(name, hashes)| (name, hashes.as_slice()))
+            .collect()
+    }
+
+    /// Verify the recorded hashes of every distribution in this resolution against the archives
+    /// in the cache, without re-resolving or re-installing.
+    ///
+    /// Local archives are re-hashed from disk. Remote wheels are only retained in the cache in
+    /// unzipped form, so they're instead compared against the digests that were computed when the
+    /// wheel was downloaded. Distributions that haven't been cached, and those that can't be
+    /// hashed (like directories and Git checkouts), are skipped.
+    ///
+    /// Returns a [`Diagnostic::InvalidHash`] for each mismatch. If `hasher` requires hashes, also
+    /// returns a [`Diagnostic::MissingHash`] for each distribution without any recorded hashes.
+    pub async fn check_hashes(
+        &self,
+        cache: &Cache,
+        hasher: &HashStrategy,
+    ) -> Result<Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        let mut tasks = Vec::new();
+        for dist in self.petgraph.node_weights() {
+            if dist.is_editable() {
+                continue;
+            }
+
+            let Some(expected) = self
+                .hashes
+                .get(dist.name())
+                .filter(|expected| !expected.is_empty())
+            else {
+                if matches!(hasher, HashStrategy::Validate(_)) {
+                    diagnostics.push(Diagnostic::MissingHash { dist: dist.clone() });
+                }
+                continue;
+            };
+
+            let Some(source) = ArchiveDigests::from_dist(dist, cache) else {
+                continue;
+            };
+
+            // Computing the digests requires reading each archive in full, so do so off the
+            // async runtime.
+            let dist = dist.clone();
+            let expected = expected.clone();
+            tasks.push(tokio::task::spawn_blocking(
+                move || -> Result<Option<Diagnostic>> {
+                    let Some(actual) = source.read(&expected)? else {
+                        return Ok(None);
+                    };
+                    if actual.iter().any(|digest| expected.contains(digest)) {
+                        return Ok(None);
+                    }
+                    Ok(actual.into_iter().find_map(|actual| {
+                        let expected = expected
+                            .iter()
+                            .find(|expected| expected.algorithm() == actual.algorithm())?;
+                        Some(Diagnostic::InvalidHash {
+                            dist: dist.clone(),
+                            expected: expected.clone(),
+                            actual,
+                        })
+                    }))
+                },
+            ));
+        }
+
+        for diagnostic in futures::future::try_join_all(tasks).await? {
+            diagnostics.extend(diagnostic?);
+        }
+        Ok(diagnostics)
+    }
+
+    /// Partition the distributions in this resolution by the index from which they were resolved.
+    ///
+    /// Distributions that weren't resolved from a registry (e.g., URL, path, and editable
+    /// distributions) are grouped under `None`. Each group is sorted by package name.
+    pub fn group_by_index(&self) -> FxHashMap<Option<&IndexUrl>, Vec<&ResolvedDist>> {
+        let mut groups = FxHashMap::<_, Vec<_>>::default();
+        for index in self.petgraph.node_indices() {
+            let dist = &self.petgraph[index];
+            groups.entry(dist.index()).or_default().push(dist);
+        }
+        for dists in groups.values_mut() {
+            dists.sort_unstable_by_key(|dist| dist.name());
+        }
+        groups
+    }
+
+    /// Return the packages in this resolution that trace back to the given source file (e.g.,
+    /// `requirements.in`), sorted by name.
+    ///
+    /// A package is included if its source annotations reference the file directly, or if it's
+    /// a (transitive) dependency of such a package.
+    pub fn packages_from_source(
+        &self,
+        source: &str,
+        sources: &SourceAnnotations,
+    ) -> Vec<&PackageName> {
+        let source = Path::new(source);
+
+        // Seed the traversal with the packages that are annotated with the source file.
+        let mut seen = FxHashSet::default();
+        let mut queue = self
+            .petgraph
+            .node_indices()
+            .filter(|index| {
+                let name = self.petgraph[*index].name();
+                let annotations = if let Some((editable, _, _)) = self.editables.get(name) {
+                    sources.get_editable(&editable.url)
+                } else {
+                    sources.get(name)
+                };
+                annotations.is_some_and(|annotations| {
+                    annotations
+                        .iter()
+                        .any(|annotation| annotation.origin().path() == source)
+                })
+            })
+            .collect::<VecDeque<_>>();
+        seen.extend(queue.iter().copied());
+
+        // Include any packages that are reachable from the seeds.
+        while let Some(index) = queue.pop_front() {
+            for dependency in self.petgraph.neighbors_directed(index, Direction::Outgoing) {
+                if seen.insert(dependency) {
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        let mut packages = seen
+            .into_iter()
+            .map(|index| self.petgraph[index].name())
+            .collect::<Vec<_>>();
+        packages.sort_unstable();
+        packages
+    }
+
+    /// Return the packages in the order in which they should be installed, such that every package
+    /// appears after its dependencies.
+    ///
+    /// The order is stable across runs: packages that aren't ordered relative to one another are
+    /// sorted alphabetically by name. Returns an error if the graph contains a dependency cycle,
+    /// since no such order exists.
+    pub fn install_order(&self) -> Result<Vec<&ResolvedDist>, CyclicDependencyError> {
+        if let Err(cycle) = petgraph::algo::toposort(&self.petgraph, None) {
+            return Err(CyclicDependencyError {
+                package: self.petgraph[cycle.node_id()].name().clone(),
+            });
+        }
+        Ok(self
+            .topological_order()
+            .into_iter()
+            .map(|index| &self.petgraph[index])
+            .collect())
+    }
+
+    /// Return the longest dependency chain in the resolution (by number of edges), from a package
+    /// that nothing depends on to a package without any dependencies.
+    ///
+    /// A long critical path indicates deeply nested dependencies, which must be fetched
+    /// sequentially during resolution. Ties are broken alphabetically, such that the path is
+    /// deterministic.
+    ///
+    /// Returns an empty `Vec` for an empty graph, or for a graph that contains a cycle (in which
+    /// case the longest path is unbounded); cycles are reported as
+    /// [`Diagnostic::CyclicDependency`] when the graph is constructed.
+    pub fn critical_path(&self) -> Vec<&ResolvedDist> {
+        let Ok(order) = petgraph::algo::toposort(&self.petgraph, None) else {
+            return Vec::new();
+        };
+
+        // Visit each package after its dependencies, tracking the length of the longest chain
+        // starting at each package along with the next package in that chain.
+        let mut longest: FxHashMap<NodeIndex, (usize, Option<NodeIndex>)> = FxHashMap::default();
+        for index in order.into_iter().rev() {
+            let next = self
+                .petgraph
+                .neighbors_directed(index, Direction::Outgoing)
+                .max_by(|a, b| {
+                    longest[a]
+                        .0
+                        .cmp(&longest[b].0)
+                        .then_with(|| self.petgraph[*b].name().cmp(self.petgraph[*a].name()))
+                });
+            let length = next.map_or(0, |next| longest[&next].0 + 1);
+            longest.insert(index, (length, next));
+        }
+
+        let start = longest
+            .iter()
+            .max_by(|(a, (a_length, _)), (b, (b_length, _))| {
+                a_length
+                    .cmp(b_length)
+                    .then_with(|| self.petgraph[**b].name().cmp(self.petgraph[**a].name()))
+            });
+        let mut path = Vec::new();
+        let mut current = start.map(|(index, _)| *index);
+        while let Some(index) = current {
+            path.push(&self.petgraph[index]);
+            current = longest[&index].1;
+        }
+        path
+    }
+
+    /// Return the nodes of the graph in topological order, such that every package appears after
+    /// its dependencies.
+    ///
+    /// Packages at the same level are ordered alphabetically by name. Cycles are legal in Python,
+    /// so if the remaining packages form a cycle, it's broken at the alphabetically-first package.
+    fn topological_order(&self) -> Vec<NodeIndex> {
+        // Track the number of unvisited dependencies for each package.
+        let mut remaining = self
+            .petgraph
+            .node_indices()
+            .map(|node| {
+                let count = self
+                    .petgraph
+                    .neighbors_directed(node, Direction::Outgoing)
+                    .count();
+                (node, count)
+            })
+            .collect::<FxHashMap<_, _>>();
+
+        let mut order = Vec::with_capacity(self.petgraph.node_count());
+        let mut ready = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(node, _)| *node)
+            .collect::<Vec<_>>();
+        while !remaining.is_empty() {
+            if ready.is_empty() {
+                // The remaining packages form (or depend on) a cycle.
+                let node = remaining
+                    .keys()
+                    .copied()
+                    .min_by_key(|node| (self.petgraph[*node].name(), *node))
+                    .expect("remaining packages should be non-empty");
+                ready.push(node);
+            }
+
+            // Visit the current level in alphabetical order.
+            ready.sort_unstable_by_key(|node| (self.petgraph[*node].name(), *node));
+            let mut next = Vec::new();
+            for node in ready.drain(..) {
+                if remaining.remove(&node).is_none() {
+                    continue;
+                }
+                order.push(node);
+                for dependent in self.petgraph.neighbors_directed(node, Direction::Incoming) {
+                    if let Some(count) = remaining.get_mut(&dependent) {
+                        *count = count.saturating_sub(1);
+                        if *count == 0 {
+                            next.push(dependent);
+                        }
+                    }
+                }
+            }
+            ready = next;
+        }
+        order
+    }
+
+    /// Return the underlying graph.
+    pub fn petgraph(
+        &self,
+    ) -> &petgraph::graph::Graph<ResolvedDist, Range<Version>, petgraph::Directed> {
+        &self.petgraph
+    }
+
+    /// Return the marker tree specific to this resolution.
+    ///
+    /// This accepts a manifest, in-memory-index and marker environment. All
+    /// of which should be the same values given to the resolver that produced
+    /// this graph.
+    ///
+    /// The marker tree returned corresponds to an expression that, when true,
+    /// this resolution is guaranteed to be correct. Note though that it's
+    /// possible for resolution to be correct even if the returned marker
+    /// expression is false.
+    ///
+    /// Markers that hold in the given environment are preserved as-is,
+    /// including any disjunctions. For example, if the root package has a
+    /// dependency `foo; sys_platform == "linux" or sys_platform == "win32"`
+    /// and resolution was performed on Linux, then the marker tree returned
+    /// will contain that same expression, since the resolution is equally
+    /// correct on Windows.
+    ///
+    /// Markers that don't hold are instead reduced to strict equality terms.
+    /// For example, if the root package has a dependency `foo; sys_platform ==
+    /// "macos"` and resolution was performed on Linux, then the marker tree
+    /// returned will contain a `sys_platform == "linux"` expression. This
+    /// means that whenever the marker expression evaluates to true (i.e., the
+    /// current platform is Linux), then the resolution here is correct. But
+    /// it is possible that the resolution is also correct on other platforms
+    /// that aren't macOS, such as Windows. (It is unclear at time of writing
+    /// whether this is fundamentally impossible to compute, or just impossible
+    /// to compute in some cases.)
+    pub fn marker_tree(
+        &self,
+        manifest: &Manifest,
+        index: &InMemoryIndex,
+        marker_env: &MarkerEnvironment,
+    ) -> Result<MarkerTree, ResolveError> {
+        use pep508_rs::{
+            MarkerExpression, MarkerOperator, MarkerValue, MarkerValueString, MarkerValueVersion,
+        };
+
+        /// A subset of the possible marker values.
+        ///
+        /// We only track the marker parameters that are referenced in a marker
+        /// expression. We'll use references to the parameter later to generate
+        /// values based on the current marker environment.
+        #[derive(Debug, Eq, Hash, PartialEq)]
+        enum MarkerParam {
+            Version(MarkerValueVersion),
+            String(MarkerValueString),
         }
-        Ok(MarkerTree::And(conjuncts))
+
+        /// Add all marker parameters from the given tree to the given set.
+        fn add_marker_params_from_tree(marker_tree: &MarkerTree, set: &mut FxHashSet<MarkerParam>) {
+            match *marker_tree {
+                MarkerTree::Expression(ref expr) => {
+                    add_marker_value(&expr.l_value, set);
+                    add_marker_value(&expr.r_value, set);
+                }
+                MarkerTree::And(ref exprs) | MarkerTree::Or(ref exprs) => {
+                    for expr in exprs {
+                        add_marker_params_from_tree(expr, set);
+                    }
+                }
+            }
+        }
+
+        /// Add the marker value, if it's a marker parameter, to the set
+        /// given.
+        fn add_marker_value(value: &MarkerValue, set: &mut FxHashSet<MarkerParam>) {
+            match *value {
+                MarkerValue::MarkerEnvVersion(ref value_version) => {
+                    set.insert(MarkerParam::Version(value_version.clone()));
+                }
+                MarkerValue::MarkerEnvString(ref value_string) => {
+                    set.insert(MarkerParam::String(value_string.clone()));
+                }
+                // We specifically don't care about these for the
+                // purposes of generating a marker string for a lock
+                // file. Quoted strings are marker values given by the
+                // user. We don't track those here, since we're only
+                // interested in which markers are used.
+                MarkerValue::Extra | MarkerValue::QuotedString(_) => {}
+            }
+        }
+
+        /// Returns `true` if the given tree references the `extra` marker.
+        fn contains_extra(marker_tree: &MarkerTree) -> bool {
+            match *marker_tree {
+                MarkerTree::Expression(ref expr) => {
+                    expr.l_value == MarkerValue::Extra || expr.r_value == MarkerValue::Extra
+                }
+                MarkerTree::And(ref exprs) | MarkerTree::Or(ref exprs) => {
+                    exprs.iter().any(contains_extra)
+                }
+            }
+        }
+
+        /// Add the given marker to the resulting tree.
+        ///
+        /// If the marker holds in the current environment, it's preserved
+        /// as-is, since the resolution remains correct wherever it holds. A
+        /// marker that doesn't hold can't be negated in general, so its
+        /// parameters are instead pinned to the current environment.
+        fn add_marker(
+            marker_tree: &MarkerTree,
+            marker_env: &MarkerEnvironment,
+            extras: &[ExtraName],
+            preserved: &mut Vec<MarkerTree>,
+            set: &mut FxHashSet<MarkerParam>,
+        ) {
+            if marker_tree.evaluate(marker_env, extras) {
+                match marker_tree.clone().simplify_extras(extras) {
+                    // The marker only depends on the enabled extras.
+                    None => return,
+                    Some(simplified) if !contains_extra(&simplified) => {
+                        if !preserved.contains(&simplified) {
+                            preserved.push(simplified);
+                        }
+                        return;
+                    }
+                    Some(_) => {}
+                }
+            }
+            add_marker_params_from_tree(marker_tree, set);
+        }
+
+        let mut seen_marker_values = FxHashSet::default();
+        let mut preserved = vec![];
+        for i in self.petgraph.node_indices() {
+            let extras = self
+                .extras
+                .get(self.petgraph[i].name())
+                .map_or(&[][..], Vec::as_slice);
+            let requirements = self.requirements_for(i, index)?;
+            for req in manifest.apply(requirements.iter()) {
+                let Some(ref marker_tree) = req.marker else {
+                    continue;
+                };
+                add_marker(
+                    marker_tree,
+                    marker_env,
+                    extras,
+                    &mut preserved,
+                    &mut seen_marker_values,
+                );
+            }
+        }
+
+        // Ensure that we consider markers from direct dependencies.
+        let direct_reqs = manifest.requirements.iter().chain(
+            manifest
+                .editables
+                .iter()
+                .flat_map(|(_, _, uv_requirements)| &uv_requirements.dependencies),
+        );
+        for direct_req in manifest.apply(direct_reqs) {
+            let Some(ref marker_tree) = direct_req.marker else {
+                continue;
+            };
+            add_marker(
+                marker_tree,
+                marker_env,
+                &[],
+                &mut preserved,
+                &mut seen_marker_values,
+            );
+        }
+
+        // Generate the final marker expression as a conjunction of the
+        // preserved markers and strict equality terms.
+        let mut conjuncts = vec![];
+        for marker_param in seen_marker_values {
+            let expr = match marker_param {
+                MarkerParam::Version(value_version) => {
+                    let from_env = marker_env.get_version(&value_version);
+                    MarkerExpression {
+                        l_value: MarkerValue::MarkerEnvVersion(value_version),
+                        operator: MarkerOperator::Equal,
+                        r_value: MarkerValue::QuotedString(from_env.to_string()),
+                    }
+                }
+                MarkerParam::String(value_string) => {
+                    let from_env = marker_env.get_string(&value_string);
+                    MarkerExpression {
+                        l_value: MarkerValue::MarkerEnvString(value_string),
+                        operator: MarkerOperator::Equal,
+                        r_value: MarkerValue::QuotedString(from_env.to_string()),
+                    }
+                }
+            };
+            conjuncts.push(MarkerTree::Expression(expr));
+        }
+        conjuncts.extend(preserved);
+        Ok(MarkerTree::And(simplify_conjuncts(conjuncts)))
+    }
+
+    /// Return the marker under which each package in the graph is required, or `None` if the
+    /// package is required unconditionally.
+    ///
+    /// A package's marker is the disjunction, over every path from a direct requirement to the
+    /// package, of the conjunction of the markers along that path. Like [`Self::marker_tree`],
+    /// this accepts the manifest and in-memory index that were given to the resolver.
+    pub fn conditional_packages(
+        &self,
+        manifest: &Manifest,
+        index: &InMemoryIndex,
+    ) -> Result<FxHashMap<PackageName, Option<MarkerTree>>, ResolveError> {
+        let inverse = self
+            .petgraph
+            .node_indices()
+            .map(|index| (self.petgraph[index].name(), index))
+            .collect::<FxHashMap<_, _>>();
+
+        // Collect the marker on every dependency edge, with any enabled extras simplified away.
+        let mut edges = FxHashMap::<NodeIndex, Vec<(NodeIndex, Option<MarkerTree>)>>::default();
+        for parent in self.petgraph.node_indices() {
+            let name = self.petgraph[parent].name();
+            let extras = self.extras.get(name).map_or(&[][..], Vec::as_slice);
+            let requirements = self.requirements_for(parent, index)?;
+            for requirement in manifest.overrides.apply(requirements.iter()) {
+                // Skip any requirements that are only enabled by extras that weren't requested.
+                if !requirement.evaluate_markers(None, extras) {
+                    continue;
+                }
+                let Some(&child) = inverse.get(&requirement.name) else {
+                    continue;
+                };
+                if child == parent {
+                    continue;
+                }
+                let marker = requirement
+                    .marker
+                    .clone()
+                    .and_then(|marker| marker.simplify_extras(extras));
+                edges.entry(parent).or_default().push((child, marker));
+            }
+        }
+
+        // Seed the traversal with the direct requirements and the editables.
+        let mut markers = FxHashMap::<NodeIndex, MarkerDisjunction>::default();
+        let mut queue = VecDeque::new();
+        for requirement in manifest.overrides.apply(manifest.requirements.iter()) {
+            let Some(&node) = inverse.get(&requirement.name) else {
+                continue;
+            };
+            let conjunction = requirement.marker.clone().into_iter().collect();
+            if markers.entry(node).or_default().insert(conjunction) {
+                queue.push_back(node);
+            }
+        }
+        for (_, metadata, _) in self.editables.iter() {
+            let Some(&node) = inverse.get(&metadata.name) else {
+                continue;
+            };
+            if markers.entry(node).or_default().insert(Vec::new()) {
+                queue.push_back(node);
+            }
+        }
+
+        // Propagate the markers along the dependency edges until we reach a fixed point.
+        while let Some(parent) = queue.pop_front() {
+            let Some(children) = edges.get(&parent) else {
+                continue;
+            };
+            let conjunctions = markers[&parent].0.clone();
+            for (child, marker) in children {
+                let mut changed = false;
+                for conjunction in &conjunctions {
+                    let mut conjunction = conjunction.clone();
+                    conjunction.extend(marker.clone());
+                    changed |= markers.entry(*child).or_default().insert(conjunction);
+                }
+                if changed {
+                    queue.push_back(*child);
+                }
+            }
+        }
+
+        Ok(self
+            .petgraph
+            .node_indices()
+            .map(|node| {
+                let marker = markers
+                    .remove(&node)
+                    .and_then(MarkerDisjunction::into_marker);
+                (self.petgraph[node].name().clone(), marker)
+            })
+            .collect())
+    }
+
+    /// Return the subset of this resolution that's relevant to the given marker environment.
+    ///
+    /// Starting from the direct requirements and any editables, only those dependency edges whose
+    /// markers evaluate to `true` in the given environment are followed. Packages that aren't
+    /// reachable via such edges are omitted from the returned graph.
+    ///
+    /// This accepts the same manifest and in-memory index that were given to the resolver that
+    /// produced this graph.
+    pub fn for_environment(
+        &self,
+        manifest: &Manifest,
+        index: &InMemoryIndex,
+        marker_env: &MarkerEnvironment,
+    ) -> Result<ResolutionGraph, ResolveError> {
+        let inverse = self
+            .petgraph
+            .node_indices()
+            .map(|index| (self.petgraph[index].name(), index))
+            .collect::<FxHashMap<_, _>>();
+
+        // Seed the traversal with the applicable direct requirements and the editables.
+        let mut seen = FxHashSet::default();
+        let mut queue = VecDeque::new();
+        for requirement in manifest.overrides.apply(manifest.requirements.iter()) {
+            if !requirement.evaluate_markers(Some(marker_env), &[]) {
+                continue;
+            }
+            let Some(&node) = inverse.get(&requirement.name) else {
+                continue;
+            };
+            if seen.insert(node) {
+                queue.push_back(node);
+            }
+        }
+        for (_, metadata, _) in self.editables.iter() {
+            let Some(&node) = inverse.get(&metadata.name) else {
+                continue;
+            };
+            if seen.insert(node) {
+                queue.push_back(node);
+            }
+        }
+
+        // Follow the dependency edges that are applicable to the environment.
+        let mut edges = Vec::new();
+        while let Some(parent) = queue.pop_front() {
+            let name = self.petgraph[parent].name();
+            let extras = self.extras.get(name).map_or(&[][..], Vec::as_slice);
+            let requirements = self.requirements_for(parent, index)?;
+            for requirement in manifest.overrides.apply(requirements.iter()) {
+                if !requirement.evaluate_markers(Some(marker_env), extras) {
+                    continue;
+                }
+                let Some(&child) = inverse.get(&requirement.name) else {
+                    continue;
+                };
+                if child == parent {
+                    continue;
+                }
+                edges.push((parent, child));
+                if seen.insert(child) {
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        Ok(self.retain(&seen, edges))
+    }
+
+    /// Return a copy of this resolution that only includes the packages whose markers evaluate to
+    /// `true` in the given environment.
+    ///
+    /// Unlike [`ResolutionGraph::for_environment`], this relies on the markers recorded for each
+    /// package during resolution, rather than re-evaluating the requirements of every package, so
+    /// it can be used to filter a cross-platform resolution down to a single platform without the
+    /// resolver's index. Packages that are required unconditionally, including editables, are
+    /// always retained.
+    pub fn marker_compatible(&self, env: &MarkerEnvironment) -> ResolutionGraph {
+        let nodes = self
+            .petgraph
+            .node_indices()
+            .filter(|index| {
+                let name = self.petgraph[*index].name();
+                self.editables.get(name).is_some()
+                    || self
+                        .markers
+                        .get(name)
+                        .map_or(true, |marker| marker.evaluate(env, &[]))
+            })
+            .collect::<FxHashSet<_>>();
+        let edges = self
+            .petgraph
+            .edge_references()
+            .map(|edge| (edge.source(), edge.target()));
+        self.retain(&nodes, edges)
+    }
+
+    /// Split this resolution into one graph per marker environment, as in
+    /// [`ResolutionGraph::for_environment`].
+    ///
+    /// Each graph is keyed by its platform, in the form
+    /// `{sys_platform}-{platform_machine}-py{python_version}` (e.g., `linux-x86_64-py3.12`).
+    pub fn split_by_platform(
+        &self,
+        manifest: &Manifest,
+        index: &InMemoryIndex,
+        marker_envs: &[MarkerEnvironment],
+    ) -> Result<FxHashMap<String, ResolutionGraph>, ResolveError> {
+        marker_envs
+            .iter()
+            .map(|marker_env| {
+                let key = format!(
+                    "{}-{}-py{}",
+                    marker_env.sys_platform(),
+                    marker_env.platform_machine(),
+                    marker_env.python_version()
+                );
+                Ok((key, self.for_environment(manifest, index, marker_env)?))
+            })
+            .collect()
+    }
+
+    /// Return a copy of this resolution that only includes the given packages, along with every
+    /// package reachable from them via their dependencies.
+    ///
+    /// The hashes, extras, editables, and diagnostics for the retained packages are preserved, so
+    /// the result can be rendered with [`DisplayResolutionGraph`] like any other resolution.
+    /// Returns an error if any of the given packages isn't present in the resolution.
+    pub fn subgraph(&self, roots: &[PackageName]) -> Result<ResolutionGraph, PackageNotFoundError> {
+        let inverse = self
+            .petgraph
+            .node_indices()
+            .map(|index| (self.petgraph[index].name(), index))
+            .collect::<FxHashMap<_, _>>();
+
+        let mut seen = FxHashSet::default();
+        let mut queue = VecDeque::new();
+        for root in roots {
+            let Some(&index) = inverse.get(root) else {
+                return Err(PackageNotFoundError {
+                    package: root.clone(),
+                });
+            };
+            if seen.insert(index) {
+                queue.push_back(index);
+            }
+        }
+        while let Some(index) = queue.pop_front() {
+            for dependency in self.petgraph.neighbors_directed(index, Direction::Outgoing) {
+                if seen.insert(dependency) {
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        let edges = self
+            .petgraph
+            .edge_references()
+            .map(|edge| (edge.source(), edge.target()));
+        Ok(self.retain(&seen, edges))
+    }
+
+    /// Merge two resolutions into one, e.g., to combine the independently-resolved members of a
+    /// workspace.
+    ///
+    /// Packages present in both resolutions must be pinned to the same version (or URL), in which
+    /// case the merged package takes the union of their hashes, extras, and dependents, and is
+    /// only conditional if it's conditional in both. Editables are always taken from `left`, with
+    /// a warning if the editable in `right` differs. Returns a [`MergeConflict`] listing every
+    /// package that's pinned differently in the two resolutions, including any package that's
+    /// editable in `right` but not in `left`.
+    pub fn merge(left: Self, right: Self) -> Result<Self, MergeConflict> {
+        let left_names = left
+            .petgraph
+            .node_weights()
+            .map(|dist| dist.name().clone())
+            .collect::<FxHashSet<_>>();
+        let right_names = right
+            .petgraph
+            .node_weights()
+            .map(|dist| dist.name().clone())
+            .collect::<FxHashSet<_>>();
+
+        let mut petgraph = left.petgraph;
+        let mut inverse = petgraph
+            .node_indices()
+            .map(|index| (petgraph[index].name().clone(), index))
+            .collect::<FxHashMap<_, _>>();
+
+        // Map each node in `right` onto the merged graph, adding any packages that aren't present
+        // in `left`.
+        let mut conflicts = Vec::new();
+        let mut mapping = FxHashMap::default();
+        for index in right.petgraph.node_indices() {
+            let dist = &right.petgraph[index];
+            let name = dist.name();
+            let Some(&existing) = inverse.get(name) else {
+                let new = petgraph.add_node(dist.clone());
+                inverse.insert(name.clone(), new);
+                mapping.insert(index, new);
+                continue;
+            };
+            mapping.insert(index, existing);
+
+            if let Some((editable, _, _)) = left.editables.get(name) {
+                if right
+                    .editables
+                    .get(name)
+                    .map_or(true, |(other, _, _)| other.url != editable.url)
+                {
+                    warn!(
+                        "Editable `{name}` differs between the merged resolutions; using `{editable}`"
+                    );
+                }
+                continue;
+            }
+
+            let left_version = petgraph[existing].version_or_url().to_string();
+
+            // The merged package is taken from `left`, so it can't become editable; rather than
+            // recording an editable that disagrees with the package in the graph, reject it.
+            if let Some((editable, _, _)) = right.editables.get(name) {
+                conflicts.push((name.clone(), left_version, format!("-e {editable}")));
+                continue;
+            }
+
+            let right_version = dist.version_or_url().to_string();
+            if left_version != right_version {
+                conflicts.push((name.clone(), left_version, right_version));
+            }
+        }
+        if !conflicts.is_empty() {
+            conflicts.sort_unstable();
+            return Err(MergeConflict { conflicts });
+        }
+
+        // Add the dependency edges from `right`, intersecting any ranges on shared edges. As for
+        // packages, a shared edge is only conditional if it's conditional in both resolutions.
+        let mut edge_markers = left.edge_markers;
+        for edge in right.petgraph.edge_references() {
+            let (source, target) = (mapping[&edge.source()], mapping[&edge.target()]);
+            let right_marker = right.edge_markers.get(&edge.id());
+            if let Some(existing) = petgraph.find_edge(source, target) {
+                petgraph[existing] = petgraph[existing].intersection(edge.weight());
+                match (edge_markers.remove(&existing), right_marker) {
+                    (Some(left), Some(right)) if left == *right => {
+                        edge_markers.insert(existing, left);
+                    }
+                    (Some(left), Some(right)) => {
+                        edge_markers.insert(existing, MarkerTree::Or(vec![left, right.clone()]));
+                    }
+                    _ => {}
+                }
+            } else {
+                let new = petgraph.add_edge(source, target, edge.weight().clone());
+                if let Some(marker) = right_marker {
+                    edge_markers.insert(new, marker.clone());
+                }
+            }
+        }
+
+        let mut hashes = left.hashes;
+        for (name, digests) in right.hashes {
+            let entry = hashes.entry(name).or_default();
+            for digest in digests {
+                if !entry.contains(&digest) {
+                    entry.push(digest);
+                }
+            }
+        }
+
+        let mut extras = left.extras;
+        for (name, names) in right.extras {
+            let entry = extras.entry(name).or_default();
+            for extra in names {
+                if !entry.contains(&extra) {
+                    entry.push(extra);
+                }
+            }
+        }
+
+        let mut editables = left.editables.iter().cloned().collect::<Vec<_>>();
+        editables.extend(
+            right
+                .editables
+                .iter()
+                .filter(|(_, metadata, _)| left.editables.get(&metadata.name).is_none())
+                .cloned(),
+        );
+
+        // A package is conditional in the merged resolution only if it's conditional in every
+        // resolution in which it's present.
+        let mut markers = FxHashMap::default();
+        for name in inverse.keys() {
+            let marker = match (
+                left_names.contains(name).then(|| left.markers.get(name)),
+                right_names.contains(name).then(|| right.markers.get(name)),
+            ) {
+                (Some(Some(left)), Some(Some(right))) if left == right => left.clone(),
+                (Some(Some(left)), Some(Some(right))) => {
+                    MarkerTree::Or(vec![left.clone(), right.clone()])
+                }
+                (Some(Some(marker)), None) | (None, Some(Some(marker))) => marker.clone(),
+                _ => continue,
+            };
+            markers.insert(name.clone(), marker);
+        }
+
+        let mut diagnostics = left.diagnostics;
+        diagnostics.extend(right.diagnostics);
+
+        Ok(Self {
+            petgraph,
+            hashes,
+            extras,
+            editables: Editables::from_requirements(editables),
+            requested: left.requested.into_iter().chain(right.requested).collect(),
+            markers,
+            edge_markers,
+            diagnostics,
+        })
+    }
+
+    /// Return a copy of this resolution that only includes the given nodes, along with the given
+    /// edges between them.
+    fn retain(
+        &self,
+        nodes: &FxHashSet<NodeIndex>,
+        edges: impl IntoIterator<Item = (NodeIndex, NodeIndex)>,
+    ) -> ResolutionGraph {
+        let mut petgraph = petgraph::graph::Graph::with_capacity(nodes.len(), nodes.len());
+        let mut mapping = FxHashMap::default();
+        for node in self.petgraph.node_indices() {
+            if nodes.contains(&node) {
+                mapping.insert(node, petgraph.add_node(self.petgraph[node].clone()));
+            }
+        }
+        let mut edge_markers = FxHashMap::default();
+        for (source, target) in edges {
+            let (Some(&new_source), Some(&new_target)) =
+                (mapping.get(&source), mapping.get(&target))
+            else {
+                continue;
+            };
+            let Some(edge) = self.petgraph.find_edge(source, target) else {
+                continue;
+            };
+            let new = petgraph.update_edge(new_source, new_target, self.petgraph[edge].clone());
+            if let Some(marker) = self.edge_markers.get(&edge) {
+                edge_markers.insert(new, marker.clone());
+            }
+        }
+
+        let names = mapping
+            .keys()
+            .map(|node| self.petgraph[*node].name())
+            .collect::<FxHashSet<_>>();
+        ResolutionGraph {
+            petgraph,
+            hashes: self
+                .hashes
+                .iter()
+                .filter(|(name, _)| names.contains(name))
+                .map(|(name, hashes)| (name.clone(), hashes.clone()))
+                .collect(),
+            extras: self
+                .extras
+                .iter()
+                .filter(|(name, _)| names.contains(name))
+                .map(|(name, extras)| (name.clone(), extras.clone()))
+                .collect(),
+            editables: Editables::from_requirements(
+                self.editables
+                    .iter()
+                    .filter(|(_, metadata, _)| names.contains(&metadata.name))
+                    .cloned()
+                    .collect(),
+            ),
+            requested: self
+                .requested
+                .iter()
+                .filter(|name| names.contains(name))
+                .cloned()
+                .collect(),
+            markers: self
+                .markers
+                .iter()
+                .filter(|(name, _)| names.contains(name))
+                .map(|(name, marker)| (name.clone(), marker.clone()))
+                .collect(),
+            edge_markers,
+            diagnostics: self
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| names.iter().any(|name| diagnostic.includes(name)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Return the resolution as a list of requirements, each annotated with the marker under which
+    /// it's required (e.g., `colorama==0.4.6 ; sys_platform == 'win32'`).
+    ///
+    /// Unlike the output of [`DisplayResolutionGraph`], the result is valid across platforms, since
+    /// conditional packages are only installed when their markers are satisfied.
+    pub fn to_requirements_with_markers(
+        &self,
+        manifest: &Manifest,
+        index: &InMemoryIndex,
+    ) -> Result<Vec<String>, ResolveError> {
+        let markers = self.conditional_packages(manifest, index)?;
+        Ok(self
+            .petgraph
+            .node_indices()
+            .map(|node| &self.petgraph[node])
+            .sorted_unstable_by_key(|dist| dist.name())
+            .map(|dist| {
+                if let Some((editable, ..)) = self.editables.get(dist.name()) {
+                    format!("-e {}", editable.verbatim())
+                } else if let Some(Some(marker)) = markers.get(dist.name()) {
+                    format!("{} ; {marker}", dist.verbatim())
+                } else {
+                    dist.verbatim().to_string()
+                }
+            })
+            .collect())
+    }
+
+    /// Return the resolution as a list of PEP 508 dependency strings, suitable for inclusion in
+    /// the `[project.dependencies]` array of a `pyproject.toml`.
+    ///
+    /// Distributions from a URL are emitted as direct references. Local distributions (i.e.,
+    /// editables and paths) are omitted unless `include_local` is set, since they typically
+    /// don't belong in a published project's dependencies.
+    pub fn to_pyproject_dependencies(
+        &self,
+        pin: DependencyPin,
+        include_local: bool,
+    ) -> Vec<String> {
+        self.petgraph
+            .node_indices()
+            .map(|index| &self.petgraph[index])
+            .sorted_unstable_by_key(|dist| dist.name())
+            .filter_map(|dist| {
+                let name = dist.name();
+                let is_local = self.editables.get(name).is_some()
+                    || matches!(
+                        dist,
+                        ResolvedDist::Installable(
+                            Dist::Built(BuiltDist::Path(_))
+                                | Dist::Source(SourceDist::Path(_) | SourceDist::Directory(_))
+                        )
+                    );
+                if is_local && !include_local {
+                    return None;
+                }
+
+                let extras = match self.extras.get(name) {
+                    Some(extras) if !extras.is_empty() => {
+                        format!("[{}]", extras.iter().sorted().dedup().join(", "))
+                    }
+                    _ => String::new(),
+                };
+
+                if let Some((editable, _, _)) = self.editables.get(name) {
+                    return Some(format!("{name}{extras} @ {}", editable.url));
+                }
+                Some(match dist.version_or_url() {
+                    VersionOrUrlRef::Version(version) => match pin {
+                        DependencyPin::Exact => format!("{name}{extras}=={version}"),
+                        DependencyPin::LowerBound => format!("{name}{extras}>={version}"),
+                    },
+                    VersionOrUrlRef::Url(url) => format!("{name}{extras} @ {url}"),
+                })
+            })
+            .collect()
+    }
+
+    /// Return the requirements declared by the distribution at the given node.
+    fn requirements_for(
+        &self,
+        node: NodeIndex,
+        index: &InMemoryIndex,
+    ) -> Result<Vec<Requirement>, ResolveError> {
+        let dist = &self.petgraph[node];
+        let requires_dist = self
+            .with_index_metadata(dist, index, |metadata| metadata.requires_dist.clone())
+            .ok_or_else(|| ResolveError::MissingMetadata(Self::version_id(dist)))?;
+        Ok(requires_dist
+            .into_iter()
+            .map(Requirement::from_pep508)
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Apply the given function to the metadata of the given distribution, as recorded in the
+    /// in-memory index, if it's available.
+    fn with_index_metadata<T>(
+        &self,
+        dist: &ResolvedDist,
+        index: &InMemoryIndex,
+        f: impl FnOnce(&Metadata23) -> T,
+    ) -> Option<T> {
+        // Editables aren't tracked in the index, so read their metadata directly.
+        if let Some((_, metadata, _)) = self.editables.get(dist.name()) {
+            return Some(f(metadata));
+        }
+
+        let response = index.distributions.get(&Self::version_id(dist))?;
+        let MetadataResponse::Found(archive, ..) = &*response else {
+            return None;
+        };
+        Some(f(&archive.metadata))
+    }
+
+    /// Return the [`VersionId`] under which the metadata for the given distribution is stored in
+    /// the in-memory index.
+    fn version_id(dist: &ResolvedDist) -> VersionId {
+        match dist.version_or_url() {
+            VersionOrUrlRef::Version(version) => {
+                VersionId::from_registry(dist.name().clone(), version.clone())
+            }
+            VersionOrUrlRef::Url(verbatim_url) => VersionId::from_url(verbatim_url.raw()),
+        }
+    }
+
+    /// Return a manifest of the artifacts required to populate a wheelhouse for this resolution,
+    /// sorted by package name.
+    ///
+    /// Each entry describes a downloadable archive (from a registry or a direct URL), such that
+    /// the resulting directory can later be installed from with `--no-index --find-links`. Git,
+    /// path, directory, and editable distributions are omitted, as they don't correspond to a
+    /// single downloadable artifact.
+    ///
+    /// The `index` must contain the metadata for any direct URL source distributions, as used
+    /// when producing this resolution; otherwise, a [`WheelhouseError::MissingMetadata`] is
+    /// returned.
+    pub fn to_wheelhouse_manifest(
+        &self,
+        index: &InMemoryIndex,
+    ) -> Result<Vec<WheelhouseEntry>, WheelhouseError> {
+        let mut entries = Vec::new();
+        for node in self.petgraph.node_indices() {
+            let ResolvedDist::Installable(dist) = &self.petgraph[node] else {
+                continue;
+            };
+            let (version, url, filename) = match dist {
+                Dist::Built(BuiltDist::Registry(wheel)) => (
+                    wheel.filename.version.clone(),
+                    wheel.file.url.to_url()?,
+                    wheel.file.filename.clone(),
+                ),
+                Dist::Source(SourceDist::Registry(sdist)) => (
+                    sdist.filename.version.clone(),
+                    sdist.file.url.to_url()?,
+                    sdist.file.filename.clone(),
+                ),
+                Dist::Built(BuiltDist::DirectUrl(wheel)) => (
+                    wheel.filename.version.clone(),
+                    wheel.url.to_url(),
+                    wheel.filename()?.into_owned(),
+                ),
+                Dist::Source(SourceDist::DirectUrl(sdist)) => {
+                    // The version isn't encoded in the URL, so read it from the metadata.
+                    let response = index
+                        .get_metadata(&dist.version_id())
+                        .ok_or_else(|| WheelhouseError::MissingMetadata(dist.version_id()))?;
+                    let MetadataResponse::Found(archive) = &*response else {
+                        return Err(WheelhouseError::MissingMetadata(dist.version_id()));
+                    };
+                    (
+                        archive.metadata.version.clone(),
+                        sdist.url.to_url(),
+                        sdist.filename()?.into_owned(),
+                    )
+                }
+                Dist::Built(BuiltDist::Path(_))
+                | Dist::Source(
+                    SourceDist::Git(_) | SourceDist::Path(_) | SourceDist::Directory(_),
+                ) => continue,
+            };
+            entries.push(WheelhouseEntry {
+                name: dist.name().clone(),
+                version,
+                url,
+                filename,
+                hashes: self.hashes.get(dist.name()).cloned().unwrap_or_default(),
+            });
+        }
+        entries.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Compare this resolution against the inputs in the given manifest, to detect stale output
+    /// (e.g., a compiled `requirements.txt` that is out-of-date with its `requirements.in`).
+    ///
+    /// Reports any unconditional direct requirements (or editables) that are missing from the
+    /// resolution, along with any top-level packages in the resolution that aren't requested by
+    /// the manifest. Requirements with markers are skipped, as they may legitimately be absent
+    /// from a resolution for a different environment.
+    pub fn check_against_inputs(&self, manifest: &Manifest) -> Vec<InputDrift> {
+        let requested = manifest
+            .requirements
+            .iter()
+            .map(|requirement| &requirement.name)
+            .chain(
+                manifest
+                    .editables
+                    .iter()
+                    .map(|(_, metadata, _)| &metadata.name),
+            )
+            .collect::<FxHashSet<_>>();
+
+        let mut drift = Vec::new();
+        for requirement in &manifest.requirements {
+            if requirement.marker.is_none() && !self.contains(&requirement.name) {
+                drift.push(InputDrift::Missing(requirement.name.clone()));
+            }
+        }
+        for (_, metadata, _) in &manifest.editables {
+            if !self.contains(&metadata.name) {
+                drift.push(InputDrift::Missing(metadata.name.clone()));
+            }
+        }
+        for dist in self.roots() {
+            if !requested.contains(dist.name()) {
+                drift.push(InputDrift::Unrequested(dist.name().clone()));
+            }
+        }
+        drift.sort_unstable();
+        drift.dedup();
+        drift
+    }
+
+    /// Compare two resolutions, returning the packages that were added, removed, or changed.
+    ///
+    /// A package is considered changed if its version or URL, index, hashes, or enabled extras
+    /// differ between the two resolutions.
+    pub fn diff<'a>(old: &'a Self, new: &'a Self) -> ResolutionDiff<'a> {
+        let old_dists = old
+            .petgraph
+            .node_weights()
+            .map(|dist| (dist.name(), dist))
+            .collect::<BTreeMap<_, _>>();
+        let new_dists = new
+            .petgraph
+            .node_weights()
+            .map(|dist| (dist.name(), dist))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut diff = ResolutionDiff::default();
+        for (name, old_dist) in &old_dists {
+            let Some(new_dist) = new_dists.get(name) else {
+                diff.removed.push(*old_dist);
+                continue;
+            };
+
+            let extras = |resolution: &'a ResolutionGraph| {
+                let mut extras = resolution.extras.get(*name).cloned().unwrap_or_default();
+                extras.sort_unstable();
+                extras.dedup();
+                extras
+            };
+            let hashes = |resolution: &'a ResolutionGraph| {
+                resolution.hashes.get(*name).map_or(&[][..], Vec::as_slice)
+            };
+            if old_dist.verbatim() != new_dist.verbatim()
+                || old_dist.index() != new_dist.index()
+                || hashes(old) != hashes(new)
+                || extras(old) != extras(new)
+            {
+                diff.changed.push((*name, *old_dist, *new_dist));
+            }
+        }
+        for (name, new_dist) in &new_dists {
+            if !old_dists.contains_key(name) {
+                diff.added.push(*new_dist);
+            }
+        }
+        diff
+    }
+
+    /// Return a constraints file that pins only the packages with multiple dependents (i.e.,
+    /// "diamond" dependencies), which are the most prone to conflicts.
+    ///
+    /// Each line is of the form `name==version`, sorted by package name. Packages that weren't
+    /// resolved to a registry version (e.g., URL and editable dependencies) are omitted.
+    pub fn to_minimal_constraints(&self) -> String {
+        self.petgraph
+            .node_indices()
+            .filter(|index| {
+                self.petgraph
+                    .neighbors_directed(*index, Direction::Incoming)
+                    .filter(|dependent| dependent != index)
+                    .nth(1)
+                    .is_some()
+            })
+            .filter_map(|index| {
+                let dist = &self.petgraph[index];
+                match dist.version_or_url() {
+                    VersionOrUrlRef::Version(version) => Some((dist.name(), version)),
+                    VersionOrUrlRef::Url(_) => None,
+                }
+            })
+            .sorted_unstable()
+            .map(|(name, version)| format!("{name}=={version}\n"))
+            .collect()
+    }
+
+    /// Return a machine-readable JSON representation of this resolution.
+    ///
+    /// The output includes every package along with its version or URL, enabled extras, hashes,
+    /// and outgoing dependency edges, and can be deserialized back into a [`ResolutionJson`]. The
+    /// messages for any diagnostics are always included, as an empty array if there are none.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(ResolutionJson::from(self))
+            .expect("resolution should be serializable to JSON")
+    }
+
+    /// Return this resolution in the `requirements.txt` format produced by `pip-compile`, to ease
+    /// migrations from pip-tools.
+    ///
+    /// If a command is provided, the output begins with a `pip-compile`-style header comment
+    /// containing the command, along with the generation timestamp and a hash of the command.
+    /// Each package is annotated with the packages that requested it (e.g., `# via flask`).
+    pub fn to_pip_compile_output(&self, opts: PipCompileOutputOptions) -> String {
+        let mut output = String::new();
+        if let Some(command) = opts.command.as_ref() {
+            let timestamp = opts.timestamp.unwrap_or_else(Utc::now);
+            output.push_str(&format!(
+                "#\n# This file is autogenerated by pip-compile\n# by the following command:\n#\n#    {command}\n#\n# Generated at {} (command hash: {})\n#\n",
+                timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+                cache_key::digest(command),
+            ));
+        }
+
+        let display = DisplayResolutionGraph::new(
+            self,
+            &[],
+            false,
+            false,
+            false,
+            true,
+            opts.emit_index_url,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        )
+        .with_verbatim_names(opts.verbatim_names)
+        .with_color(false);
+        output.push_str(&display.to_string());
+        output
+    }
+
+    /// Return a DOT representation of this resolution, for rendering with Graphviz.
+    ///
+    /// Each node is labeled with the pinned package (e.g., `flask==3.0.0`), and each edge with
+    /// the version range of the dependency.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with_options(&DotOptions::default())
+    }
+
+    /// Return a DOT representation of this resolution, rendered with the given [`DotOptions`].
+    pub fn to_dot_with_options(&self, options: &DotOptions) -> String {
+        let dot = petgraph::dot::Dot::with_attr_getters(
+            &self.petgraph,
+            &[
+                petgraph::dot::Config::NodeNoLabel,
+                petgraph::dot::Config::EdgeNoLabel,
+            ],
+            &|_graph, edge| {
+                if !options.edge_labels {
+                    return String::new();
+                }
+                let marker = options
+                    .edge_markers
+                    .then(|| self.edge_markers.get(&edge.id()))
+                    .flatten();
+                let label = match marker {
+                    Some(marker) => format!("{}; {marker}", edge.weight()),
+                    None => edge.weight().to_string(),
+                };
+                format!("label={label:?}")
+            },
+            &|_graph, (_index, dist)| {
+                let mut attrs = format!("label={:?}", dist.to_string());
+                if self.editables.get(dist.name()).is_some() {
+                    if let Some(color) = options.editable_color.as_ref() {
+                        attrs.push_str(&format!(" color={color:?}"));
+                    }
+                    if let Some(shape) = options.editable_shape.as_ref() {
+                        attrs.push_str(&format!(" shape={shape}"));
+                    }
+                }
+                attrs
+            },
+        );
+        format!("{dot:?}")
+    }
+
+    /// Return a Mermaid `flowchart` representation of this resolution, for embedding in Markdown.
+    ///
+    /// Each node is labeled with the pinned package (e.g., `flask==3.0.0`), and each edge with
+    /// the version range of the dependency. Editables are rendered as cylinders, to distinguish
+    /// them from the other packages.
+    ///
+    /// If `max_depth` is provided, only packages within that many dependency edges of a root
+    /// (i.e., a top-level package, or any package that no other package depends on) are
+    /// included. Nodes and edges are sorted by package name, such that the output is stable
+    /// across resolutions.
+    pub fn to_mermaid(&self, max_depth: Option<usize>) -> String {
+        // Determine the packages to include, by walking the graph breadth-first from the roots.
+        let mut seen = FxHashSet::default();
+        let mut queue = self
+            .petgraph
+            .node_indices()
+            .filter(|index| {
+                self.is_root_index(*index)
+                    || self
+                        .petgraph
+                        .neighbors_directed(*index, Direction::Incoming)
+                        .next()
+                        .is_none()
+            })
+            .map(|index| (index, 0))
+            .collect::<VecDeque<_>>();
+        seen.extend(queue.iter().map(|(index, _)| *index));
+        while let Some((index, distance)) = queue.pop_front() {
+            if max_depth.is_some_and(|max_depth| distance >= max_depth) {
+                continue;
+            }
+            for dependency in self.petgraph.neighbors_directed(index, Direction::Outgoing) {
+                if seen.insert(dependency) {
+                    queue.push_back((dependency, distance + 1));
+                }
+            }
+        }
+
+        // Package names are normalized, so they only contain alphanumerics and hyphens, the
+        // latter of which aren't valid in Mermaid identifiers.
+        let id = |index: NodeIndex| self.petgraph[index].name().as_ref().replace('-', "_");
+        let escape = |label: String| label.replace('"', "#quot;");
+
+        let nodes = seen
+            .iter()
+            .copied()
+            .sorted_by(|a, b| self.petgraph[*a].name().cmp(self.petgraph[*b].name()))
+            .map(|index| {
+                let dist = &self.petgraph[index];
+                let label = escape(dist.to_string());
+                if self.editables.get(dist.name()).is_some() {
+                    format!("    {}[(\"{label}\")]\n", id(index))
+                } else {
+                    format!("    {}[\"{label}\"]\n", id(index))
+                }
+            });
+        let edges = self
+            .petgraph
+            .edge_references()
+            .filter(|edge| seen.contains(&edge.source()) && seen.contains(&edge.target()))
+            .sorted_by(|a, b| {
+                self.petgraph[a.source()]
+                    .name()
+                    .cmp(self.petgraph[b.source()].name())
+                    .then_with(|| {
+                        self.petgraph[a.target()]
+                            .name()
+                            .cmp(self.petgraph[b.target()].name())
+                    })
+            })
+            .map(|edge| {
+                format!(
+                    "    {} -->|\"{}\"| {}\n",
+                    id(edge.source()),
+                    escape(edge.weight().to_string()),
+                    id(edge.target())
+                )
+            });
+
+        std::iter::once("flowchart TD\n".to_string())
+            .chain(nodes)
+            .chain(edges)
+            .collect()
+    }
+
+    /// Return a `Pipfile.lock` representation of this resolution, in the format written by
+    /// `pipenv lock`.
+    ///
+    /// The given `sources` are listed in the `_meta` section, with PyPI named `pypi` and any other
+    /// index named by its position (e.g., `index-1`), and each package that was resolved from one
+    /// of the sources references it by name. Packages in `develop` are written to the `develop`
+    /// section, while all others are written to `default`.
+    ///
+    /// Editables are written as `path` entries, and any other non-registry distributions as
+    /// `file` entries. Only SHA-256 hashes are included, as `pipenv` doesn't support other
+    /// algorithms. Since the resolution isn't derived from a `Pipfile`, the `_meta` section omits
+    /// the `Pipfile` hash.
+    pub fn to_pipfile_lock(
+        &self,
+        sources: &[IndexUrl],
+        develop: &FxHashSet<PackageName>,
+    ) -> serde_json::Value {
+        let source_name = |position: usize, source: &IndexUrl| match source {
+            IndexUrl::Pypi(_) => "pypi".to_string(),
+            IndexUrl::Url(_) | IndexUrl::Path(_) => format!("index-{position}"),
+        };
+
+        let mut default = serde_json::Map::new();
+        let mut dev = serde_json::Map::new();
+        for dist in self.petgraph.node_weights() {
+            let name = dist.name();
+            let mut entry = serde_json::Map::new();
+
+            if let Some((editable, _, _)) = self.editables.get(name) {
+                entry.insert("editable".to_string(), serde_json::Value::Bool(true));
+                entry.insert(
+                    "path".to_string(),
+                    serde_json::Value::String(editable.verbatim().to_string()),
+                );
+            } else {
+                match dist.version_or_url() {
+                    VersionOrUrlRef::Version(version) => {
+                        entry.insert(
+                            "version".to_string(),
+                            serde_json::Value::String(format!("=={version}")),
+                        );
+                    }
+                    VersionOrUrlRef::Url(url) => {
+                        entry.insert(
+                            "file".to_string(),
+                            serde_json::Value::String(url.verbatim().to_string()),
+                        );
+                    }
+                }
+            }
+
+            let hashes = self
+                .hashes
+                .get(name)
+                .into_iter()
+                .flatten()
+                .filter(|hash| hash.algorithm == HashAlgorithm::Sha256)
+                .map(|hash| serde_json::Value::String(hash.to_string()))
+                .collect::<Vec<_>>();
+            if !hashes.is_empty() {
+                entry.insert("hashes".to_string(), serde_json::Value::Array(hashes));
+            }
+
+            if let Some((position, source)) = dist.index().and_then(|index| {
+                sources
+                    .iter()
+                    .enumerate()
+                    .find(|(_, source)| *source == index)
+            }) {
+                entry.insert(
+                    "index".to_string(),
+                    serde_json::Value::String(source_name(position, source)),
+                );
+            }
+
+            if let Some(extras) = self.extras.get(name).filter(|extras| !extras.is_empty()) {
+                let extras = extras
+                    .iter()
+                    .map(ToString::to_string)
+                    .sorted()
+                    .dedup()
+                    .map(serde_json::Value::String)
+                    .collect();
+                entry.insert("extras".to_string(), serde_json::Value::Array(extras));
+            }
+
+            if let Some(marker) = self.markers.get(name) {
+                entry.insert(
+                    "markers".to_string(),
+                    serde_json::Value::String(marker.to_string()),
+                );
+            }
+
+            let section = if develop.contains(name) {
+                &mut dev
+            } else {
+                &mut default
+            };
+            section.insert(name.to_string(), serde_json::Value::Object(entry));
+        }
+
+        let sources = sources
+            .iter()
+            .enumerate()
+            .map(|(position, source)| {
+                serde_json::json!({
+                    "name": source_name(position, source),
+                    "url": source.url().as_str(),
+                    "verify_ssl": true,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "_meta": {
+                "pipfile-spec": 6,
+                "requires": {},
+                "sources": sources,
+            },
+            "default": default,
+            "develop": dev,
+        })
+    }
+
+    pub fn lock(&self) -> Result<Lock, LockError> {
+        let mut locked_dists = vec![];
+        for node_index in self.petgraph.node_indices() {
+            let dist = &self.petgraph[node_index];
+            let mut locked_dist = lock::Distribution::from_resolved_dist(dist)?;
+            for edge in self.petgraph.neighbors(node_index) {
+                let dependency_dist = &self.petgraph[edge];
+                locked_dist.add_dependency(dependency_dist);
+            }
+            locked_dists.push(locked_dist);
+        }
+        let lock = Lock::new(locked_dists)?;
+        Ok(lock)
+    }
+
+    /// Lock this resolution and serialize it to the canonical `uv.lock` TOML format.
+    ///
+    /// Packages are emitted in a stable, sorted order, independent of the order in which they
+    /// were added to the graph, such that the output can be compared across resolutions.
+    pub fn to_lock_toml(&self) -> Result<String, LockError> {
+        self.lock()?.to_toml()
+    }
+
+    /// Lock this resolution for each of the given marker environments, as in
+    /// [`ResolutionGraph::split_by_platform`], and merge the results into a single [`Lock`].
+    ///
+    /// Each distribution in the lock records the platforms that require it, such that the lock can
+    /// be filtered back down to the install set for any one of the target environments.
+    pub fn lock_for_platforms(
+        &self,
+        manifest: &Manifest,
+        index: &InMemoryIndex,
+        marker_envs: &[MarkerEnvironment],
+    ) -> Result<Lock, ResolveError> {
+        let platforms = marker_envs
+            .iter()
+            .map(|marker_env| {
+                let graph = self.for_environment(manifest, index, marker_env)?;
+                Ok((platform_marker(marker_env), graph.lock()?))
+            })
+            .collect::<Result<Vec<_>, ResolveError>>()?;
+        Ok(Lock::for_platforms(&platforms)?)
+    }
+}
+
+/// Return a marker that identifies the platform of the given environment, by its
+/// `sys_platform`, `platform_machine`, and `python_version`.
+fn platform_marker(marker_env: &MarkerEnvironment) -> MarkerTree {
+    use pep508_rs::{
+        MarkerExpression, MarkerOperator, MarkerValue, MarkerValueString, MarkerValueVersion,
+    };
+
+    let term = |l_value: MarkerValue, r_value: String| {
+        MarkerTree::Expression(MarkerExpression {
+            l_value,
+            operator: MarkerOperator::Equal,
+            r_value: MarkerValue::QuotedString(r_value),
+        })
+    };
+    MarkerTree::And(vec![
+        term(
+            MarkerValue::MarkerEnvString(MarkerValueString::SysPlatform),
+            marker_env.sys_platform().to_string(),
+        ),
+        term(
+            MarkerValue::MarkerEnvString(MarkerValueString::PlatformMachine),
+            marker_env.platform_machine().to_string(),
+        ),
+        term(
+            MarkerValue::MarkerEnvVersion(MarkerValueVersion::PythonVersion),
+            marker_env.python_version().to_string(),
+        ),
+    ])
+}
+
+/// A disjunction of conjunctions of markers, used to accumulate the conditions under which a
+/// package is reachable from the direct requirements.
+///
+/// An empty conjunction is unconditionally `true`.
+#[derive(Debug, Default)]
+struct MarkerDisjunction(Vec<Vec<MarkerTree>>);
+
+impl MarkerDisjunction {
+    /// Add a conjunction to the disjunction, returning `true` if the disjunction changed.
+    ///
+    /// Conjunctions that are implied by an existing, weaker conjunction are discarded, which
+    /// ensures that propagation through dependency cycles reaches a fixed point.
+    fn insert(&mut self, markers: Vec<MarkerTree>) -> bool {
+        let mut conjunction = Vec::with_capacity(markers.len());
+        for marker in markers {
+            if !conjunction.contains(&marker) {
+                conjunction.push(marker);
+            }
+        }
+
+        if self
+            .0
+            .iter()
+            .any(|existing| existing.iter().all(|marker| conjunction.contains(marker)))
+        {
+            return false;
+        }
+        self.0
+            .retain(|existing| !conjunction.iter().all(|marker| existing.contains(marker)));
+        self.0.push(conjunction);
+        true
+    }
+
+    /// Convert the disjunction into a [`MarkerTree`], returning `None` if it's always `true`.
+    fn into_marker(self) -> Option<MarkerTree> {
+        let mut disjuncts = Vec::with_capacity(self.0.len());
+        for mut conjunction in self.0 {
+            match conjunction.len() {
+                0 => return None,
+                1 => disjuncts.push(conjunction.remove(0)),
+                _ => disjuncts.push(MarkerTree::And(conjunction)),
+            }
+        }
+        match disjuncts.len() {
+            0 => None,
+            1 => disjuncts.pop(),
+            _ => Some(MarkerTree::Or(disjuncts)),
+        }
+    }
+}
+
+/// Returns `true` if the [`VersionSpecifiers`] exclude all versions below some bound.
+fn has_lower_bound(specifier: &VersionSpecifiers) -> bool {
+    specifier.iter().any(|specifier| {
+        matches!(
+            specifier.operator(),
+            Operator::Equal
+                | Operator::EqualStar
+                | Operator::ExactEqual
+                | Operator::TildeEqual
+                | Operator::GreaterThan
+                | Operator::GreaterThanEqual
+        )
+    })
+}
+
+/// Remove redundant terms from a conjunction of markers, as produced by
+/// [`ResolutionGraph::marker_tree`].
+///
+/// Identical terms are deduplicated, and any term that's implied by the strict equality terms is
+/// dropped (e.g., `python_version == '3.11'` given `python_full_version == '3.11.4'`). Every term
+/// is assumed to hold in the environment from which the equality terms were derived, such that the
+/// result is logically equivalent to the input.
+fn simplify_conjuncts(conjuncts: Vec<MarkerTree>) -> Vec<MarkerTree> {
+    use pep508_rs::{MarkerExpression, MarkerOperator, MarkerValue, MarkerValueVersion};
+
+    /// Return the marker value pinned by the given term, if it's a strict equality term.
+    fn pinned_value(marker_tree: &MarkerTree) -> Option<&MarkerValue> {
+        match marker_tree {
+            MarkerTree::Expression(MarkerExpression {
+                l_value:
+                    l_value @ (MarkerValue::MarkerEnvVersion(_) | MarkerValue::MarkerEnvString(_)),
+                operator: MarkerOperator::Equal,
+                r_value: MarkerValue::QuotedString(_),
+            }) => Some(l_value),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if every marker value referenced by the given tree satisfies the predicate.
+    fn all_values(marker_tree: &MarkerTree, predicate: &impl Fn(&MarkerValue) -> bool) -> bool {
+        match marker_tree {
+            MarkerTree::Expression(expr) => [&expr.l_value, &expr.r_value]
+                .into_iter()
+                .all(|value| matches!(value, MarkerValue::QuotedString(_)) || predicate(value)),
+            MarkerTree::And(exprs) | MarkerTree::Or(exprs) => {
+                exprs.iter().all(|expr| all_values(expr, predicate))
+            }
+        }
+    }
+
+    let python_version = MarkerValue::MarkerEnvVersion(MarkerValueVersion::PythonVersion);
+    let python_full_version = MarkerValue::MarkerEnvVersion(MarkerValueVersion::PythonFullVersion);
+
+    // Collect the marker values pinned by a strict equality term. The Python version is derived
+    // from the full Python version, so pinning the latter pins the former.
+    let mut pinned = conjuncts
+        .iter()
+        .filter_map(pinned_value)
+        .cloned()
+        .collect::<FxHashSet<_>>();
+    let full_version_pinned = pinned.contains(&python_full_version);
+    if full_version_pinned {
+        pinned.insert(python_version.clone());
+    }
+
+    let mut simplified = Vec::with_capacity(conjuncts.len());
+    for conjunct in conjuncts {
+        if simplified.contains(&conjunct) {
+            continue;
+        }
+        match pinned_value(&conjunct) {
+            // Drop the coarse Python version if the full version is pinned.
+            Some(value) => {
+                if full_version_pinned && *value == python_version {
+                    continue;
+                }
+            }
+            // Drop any other term that only references pinned values.
+            None => {
+                if all_values(&conjunct, &|value| pinned.contains(value)) {
+                    continue;
+                }
+            }
+        }
+        simplified.push(conjunct);
+    }
+    simplified
+}
+
+/// The location from which the digests of a distribution's archive can be recovered, as used by
+/// [`ResolutionGraph::check_hashes`].
+enum ArchiveDigests {
+    /// An archive on the local filesystem, whose digests are recomputed from its contents.
+    Local(PathBuf),
+    /// A remote wheel, whose digests were recorded in the cache when it was downloaded.
+    Cached(PathBuf),
+}
+
+impl ArchiveDigests {
+    /// Locate the archive for the given distribution, if it can be hashed.
+    fn from_dist(dist: &ResolvedDist, cache: &Cache) -> Option<Self> {
+        let ResolvedDist::Installable(dist) = dist else {
+            return None;
+        };
+        match dist {
+            Dist::Built(BuiltDist::Registry(wheel)) => match &wheel.file.url {
+                FileLocation::Path(path) => Some(Self::Local(path.clone())),
+                FileLocation::RelativeUrl(..) | FileLocation::AbsoluteUrl(..) => {
+                    Some(Self::Cached(
+                        cache
+                            .entry(
+                                CacheBucket::Wheels,
+                                WheelCache::Index(&wheel.index).wheel_dir(wheel.name().as_ref()),
+                                format!("{}.http", wheel.filename.stem()),
+                            )
+                            .into_path_buf(),
+                    ))
+                }
+            },
+            Dist::Built(BuiltDist::DirectUrl(wheel)) => Some(Self::Cached(
+                cache
+                    .entry(
+                        CacheBucket::Wheels,
+                        WheelCache::Url(&wheel.url).wheel_dir(wheel.name().as_ref()),
+                        format!("{}.http", wheel.filename.stem()),
+                    )
+                    .into_path_buf(),
+            )),
+            Dist::Built(BuiltDist::Path(wheel)) => Some(Self::Local(wheel.path.clone())),
+            Dist::Source(SourceDist::Registry(sdist)) => match &sdist.file.url {
+                FileLocation::Path(path) => Some(Self::Local(path.clone())),
+                FileLocation::RelativeUrl(..) | FileLocation::AbsoluteUrl(..) => None,
+            },
+            Dist::Source(SourceDist::Path(sdist)) => Some(Self::Local(sdist.path.clone())),
+            Dist::Source(
+                SourceDist::DirectUrl(_) | SourceDist::Git(_) | SourceDist::Directory(_),
+            ) => None,
+        }
+    }
+
+    /// Read the digests of the archive, computing any of the algorithms used by `expected`.
+    ///
+    /// Returns `None` if the archive isn't present on disk.
+    fn read(&self, expected: &[HashDigest]) -> Result<Option<Vec<HashDigest>>> {
+        match self {
+            Self::Local(path) => {
+                let mut file = match fs_err::File::open(path) {
+                    Ok(file) => file,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                    Err(err) => return Err(err.into()),
+                };
+
+                let mut hashers = expected
+                    .iter()
+                    .map(HashDigest::algorithm)
+                    .collect::<BTreeSet<HashAlgorithm>>()
+                    .into_iter()
+                    .map(Hasher::from)
+                    .collect::<Vec<_>>();
+                let mut buffer = vec![0; 64 * 1024];
+                loop {
+                    let read = file.read(&mut buffer)?;
+                    if read == 0 {
+                        break;
+                    }
+                    for hasher in &mut hashers {
+                        hasher.update(&buffer[..read]);
+                    }
+                }
+
+                Ok(Some(hashers.into_iter().map(HashDigest::from).collect()))
+            }
+            Self::Cached(path) => {
+                Ok(HttpArchivePointer::read_from(path)?
+                    .map(|pointer| pointer.into_archive().hashes))
+            }
+        }
+    }
+}
+
+/// Return the hashes in `expected` that contradict those in `actual`, paired with an actual hash
+/// computed using the same algorithm.
+///
+/// Hashes computed using an algorithm that's absent from `actual` can't be verified, and so are
+/// never considered contradictory.
+fn hash_mismatches(
+    expected: &[HashDigest],
+    actual: &[HashDigest],
+) -> Vec<(HashDigest, HashDigest)> {
+    expected
+        .iter()
+        .filter(|digest| !actual.contains(digest))
+        .filter_map(|digest| {
+            let actual = actual
+                .iter()
+                .find(|actual| actual.algorithm() == digest.algorithm())?;
+            Some((digest.clone(), actual.clone()))
+        })
+        .collect()
+}
+
+/// A discrepancy between a [`ResolutionGraph`] and the [`Manifest`] from which it should have been
+/// produced, as computed by [`ResolutionGraph::check_against_inputs`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InputDrift {
+    /// A direct requirement that isn't present in the resolution.
+    Missing(PackageName),
+    /// A top-level package in the resolution that isn't requested by the manifest.
+    Unrequested(PackageName),
+}
+
+impl std::fmt::Display for InputDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(name) => {
+                write!(f, "The requirement `{name}` is missing from the resolution")
+            }
+            Self::Unrequested(name) => {
+                write!(
+                    f,
+                    "The package `{name}` is not requested by the input requirements"
+                )
+            }
+        }
+    }
+}
+
+/// The differences between two [`ResolutionGraph`]s, as computed by [`ResolutionGraph::diff`].
+///
+/// Each list is sorted by package name.
+#[derive(Debug, Default)]
+pub struct ResolutionDiff<'a> {
+    added: Vec<&'a ResolvedDist>,
+    removed: Vec<&'a ResolvedDist>,
+    changed: Vec<(&'a PackageName, &'a ResolvedDist, &'a ResolvedDist)>,
+}
+
+impl<'a> ResolutionDiff<'a> {
+    /// Return the packages that are present in the new resolution, but not the old resolution.
+    pub fn added(&self) -> impl Iterator<Item = &'a ResolvedDist> + '_ {
+        self.added.iter().copied()
+    }
+
+    /// Return the packages that are present in the old resolution, but not the new resolution.
+    pub fn removed(&self) -> impl Iterator<Item = &'a ResolvedDist> + '_ {
+        self.removed.iter().copied()
+    }
+
+    /// Return the packages that are present in both resolutions, but differ, as
+    /// `(name, old, new)` triples.
+    pub fn changed(
+        &self,
+    ) -> impl Iterator<Item = (&'a PackageName, &'a ResolvedDist, &'a ResolvedDist)> + '_ {
+        self.changed.iter().copied()
+    }
+
+    /// Returns `true` if the two resolutions are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Write the differences in the style of an install summary (e.g., ` - flask==2.0.0` and
+/// ` + flask==3.0.0`).
+impl std::fmt::Display for ResolutionDiff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        enum Event<'a> {
+            Added(&'a ResolvedDist),
+            Removed(&'a ResolvedDist),
+            Changed(&'a ResolvedDist, &'a ResolvedDist),
+        }
+
+        let events = self
+            .added
+            .iter()
+            .map(|dist| (dist.name(), Event::Added(dist)))
+            .chain(
+                self.removed
+                    .iter()
+                    .map(|dist| (dist.name(), Event::Removed(dist))),
+            )
+            .chain(
+                self.changed
+                    .iter()
+                    .map(|(name, old, new)| (*name, Event::Changed(old, new))),
+            )
+            .sorted_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (_, event) in events {
+            match event {
+                Event::Added(dist) => writeln!(f, " {} {}", "+".green(), dist.verbatim())?,
+                Event::Removed(dist) => writeln!(f, " {} {}", "-".red(), dist.verbatim())?,
+                Event::Changed(old, new) if old.verbatim() == new.verbatim() => {
+                    // The version is unchanged, but the hashes, extras, or index differ.
+                    writeln!(f, " {} {}", "~".yellow(), new.verbatim())?;
+                }
+                Event::Changed(old, new) => {
+                    writeln!(f, " {} {}", "-".red(), old.verbatim())?;
+                    writeln!(f, " {} {}", "+".green(), new.verbatim())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A machine-readable representation of a [`ResolutionGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ResolutionJson {
+    /// The packages in the resolution, sorted by name.
+    pub packages: Vec<PackageJson>,
+    /// The messages for any diagnostics that were encountered while building the graph.
+    ///
+    /// Always present (even if empty), such that consumers can rely on the field's existence.
+    #[serde(default)]
+    pub diagnostics: Vec<String>,
+}
+
+/// A single package in a [`ResolutionJson`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageJson {
+    /// The name of the package.
+    pub name: PackageName,
+    /// The pinned version of the package, for registry distributions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    /// The URL of the package, for URL-based and editable distributions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Whether the package is installed in editable mode.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub editable: bool,
+    /// The enabled extras for the package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extras: Vec<ExtraName>,
+    /// The hashes of the package's distribution (e.g., `sha256:...`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<String>,
+    /// The dependencies of the package, sorted by name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<DependencyJson>,
+}
+
+/// A dependency edge in a [`ResolutionJson`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DependencyJson {
+    /// The name of the dependency.
+    pub name: PackageName,
+    /// The version range that the dependent requires (e.g., `>=2.0, <3.0`).
+    pub range: String,
+}
+
+impl From<&ResolutionGraph> for ResolutionJson {
+    fn from(resolution: &ResolutionGraph) -> Self {
+        let mut packages = resolution
+            .petgraph
+            .node_indices()
+            .map(|index| {
+                let dist = &resolution.petgraph[index];
+                let name = dist.name();
+                let (version, url) = if let Some((editable, _, _)) = resolution.editables.get(name)
+                {
+                    (None, Some(editable.verbatim().to_string()))
+                } else {
+                    match dist.version_or_url() {
+                        VersionOrUrlRef::Version(version) => (Some(version.clone()), None),
+                        VersionOrUrlRef::Url(url) => (None, Some(url.verbatim().to_string())),
+                    }
+                };
+
+                let mut extras = resolution.extras.get(name).cloned().unwrap_or_default();
+                extras.sort_unstable();
+                extras.dedup();
+
+                let mut dependencies = resolution
+                    .petgraph
+                    .edges_directed(index, Direction::Outgoing)
+                    .map(|edge| DependencyJson {
+                        name: resolution.petgraph[edge.target()].name().clone(),
+                        range: edge.weight().to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                dependencies.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+                PackageJson {
+                    name: name.clone(),
+                    version,
+                    url,
+                    editable: dist.is_editable(),
+                    extras,
+                    hashes: resolution
+                        .hashes
+                        .get(name)
+                        .map(|hashes| hashes.iter().map(ToString::to_string).collect())
+                        .unwrap_or_default(),
+                    dependencies,
+                }
+            })
+            .collect::<Vec<_>>();
+        packages.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            packages,
+            diagnostics: resolution
+                .diagnostics
+                .iter()
+                .map(Diagnostic::message)
+                .collect(),
+        }
+    }
+}
+
+/// An artifact to download when populating a wheelhouse for offline installation.
+#[derive(Debug, Clone)]
+pub struct WheelhouseEntry {
+    /// The name of the package.
+    pub name: PackageName,
+    /// The resolved version of the package.
+    pub version: Version,
+    /// The URL from which the artifact can be downloaded.
+    pub url: Url,
+    /// The expected filename of the artifact (e.g., `flask-3.0.0-py3-none-any.whl`).
+    pub filename: String,
+    /// The expected hashes of the artifact, if known.
+    pub hashes: Vec<HashDigest>,
+}
+
+/// An error encountered while building a wheelhouse manifest.
+#[derive(Debug, thiserror::Error)]
+pub enum WheelhouseError {
+    #[error(transparent)]
+    ToUrl(#[from] ToUrlError),
+    #[error(transparent)]
+    Distribution(#[from] distribution_types::Error),
+    #[error("Missing metadata for `{0}`")]
+    MissingMetadata(VersionId),
+}
+
+/// An error indicating that a [`ResolutionGraph`] can't be ordered topologically, as returned by
+/// [`ResolutionGraph::install_order`].
+#[derive(Debug, thiserror::Error)]
+#[error("Dependency cycle detected involving `{package}`")]
+pub struct CyclicDependencyError {
+    /// A package that participates in the cycle.
+    pub package: PackageName,
+}
+
+/// An error indicating that a package isn't present in a [`ResolutionGraph`], as returned by
+/// [`ResolutionGraph::subgraph`].
+#[derive(Debug, thiserror::Error)]
+#[error("Package `{package}` is not present in the resolution")]
+pub struct PackageNotFoundError {
+    /// The package that couldn't be found.
+    pub package: PackageName,
+}
+
+/// An error indicating that two [`ResolutionGraph`]s can't be merged, as returned by
+/// [`ResolutionGraph::merge`].
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Packages are pinned differently in the merged resolutions: {}",
+    conflicts
+        .iter()
+        .map(|(name, left, right)| format!("`{name}` (`{left}` vs. `{right}`)"))
+        .join(", ")
+)]
+pub struct MergeConflict {
+    /// The packages that are pinned differently, sorted by name, along with their version (or
+    /// URL) in the left and right resolutions, respectively.
+    pub conflicts: Vec<(PackageName, String, String)>,
+}
+
+/// A violated invariant of a [`ResolutionGraph`], as returned by [`ResolutionGraph::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// A package that appears in the graph more than once.
+    #[error("Package `{0}` appears in the resolution more than once")]
+    DuplicateNode(PackageName),
+    /// An edge whose source or target isn't the node recorded for its package (e.g., an edge into
+    /// a duplicate node).
+    #[error("Dependency edge from node {dependent} to node {dependency} refers to a missing node")]
+    MissingEdgeTarget { dependent: usize, dependency: usize },
+    /// A hash entry for a package that isn't in the graph.
+    #[error("Hashes are recorded for `{0}`, which is not in the resolution")]
+    OrphanedHashEntry(PackageName),
+    /// An extra entry for a package that isn't in the graph.
+    #[error("Extras are recorded for `{0}`, which is not in the resolution")]
+    OrphanedExtraEntry(PackageName),
+    /// A marker entry for a package that isn't in the graph.
+    #[error("A marker is recorded for `{0}`, which is not in the resolution")]
+    OrphanedMarkerEntry(PackageName),
+}
+
+/// A [`std::fmt::Display`] implementation for the resolution graph.
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DisplayResolutionGraph<'a> {
+    /// The underlying graph.
+    resolution: &'a ResolutionGraph,
+    /// The packages to exclude from the output.
+    no_emit_packages: &'a [PackageName],
+    /// Whether to include hashes in the output.
+    show_hashes: bool,
+    /// The algorithm to which hashes in the output are restricted, if any.
+    hash_algorithm: Option<HashAlgorithm>,
+    /// Whether to include extras in the output (e.g., `black[colorama]`).
+    include_extras: bool,
+    /// Whether to include the marker under which each package was selected in the output (e.g.,
+    /// `colorama==0.4.6 ; sys_platform == 'win32'`).
+    include_markers: bool,
+    /// Whether to include annotations in the output, to indicate which dependency or dependencies
+    /// requested each package.
+    include_annotations: bool,
+    /// Whether to include indexes in the output, to indicate which index was used for each package.
+    include_index_annotation: bool,
+    /// How to render index URLs in the output, when index annotations are enabled.
+    index_redaction: IndexRedaction,
+    /// The style of annotation comments, used to indicate the dependencies that requested each
+    /// package.
+    annotation_style: AnnotationStyle,
+    /// The indentation to use for annotation comments in the [`AnnotationStyle::Split`] style.
+    indent: String,
+    /// The order in which packages should be emitted.
+    sort_order: SortOrder,
+    /// The maximum line length, beyond which annotation comments are wrapped.
+    max_line_length: Option<usize>,
+    /// External sources for each package: requirements, constraints, and overrides.
+    sources: SourceAnnotations,
+    /// Whether to colorize annotation and warning comments in the output.
+    color: bool,
+    /// Whether to group the output by the top-level requirement that pulled in each package.
+    group_by_root: bool,
+    /// Whether to render each package name as published (e.g., `Flask`), rather than normalized.
+    verbatim_names: bool,
+}
+
+impl<'a> From<&'a ResolutionGraph> for DisplayResolutionGraph<'a> {
+    fn from(resolution: &'a ResolutionGraph) -> Self {
+        Self::new(
+            resolution,
+            &[],
+            false,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::default(),
+            SourceAnnotations::default(),
+        )
+    }
+}
+
+impl<'a> DisplayResolutionGraph<'a> {
+    /// Create a new [`DisplayResolutionGraph`] for the given graph.
+    #[allow(clippy::fn_params_excessive_bools, clippy::too_many_arguments)]
+    pub fn new(
+        underlying: &'a ResolutionGraph,
+        no_emit_packages: &'a [PackageName],
+        show_hashes: bool,
+        include_extras: bool,
+        include_markers: bool,
+        include_annotations: bool,
+        include_index_annotation: bool,
+        annotation_style: AnnotationStyle,
+        sources: SourceAnnotations,
+    ) -> DisplayResolutionGraph<'a> {
+        Self {
+            resolution: underlying,
+            no_emit_packages,
+            show_hashes,
+            hash_algorithm: None,
+            include_extras,
+            include_markers,
+            include_annotations,
+            include_index_annotation,
+            index_redaction: IndexRedaction::default(),
+            annotation_style,
+            indent: "    ".to_string(),
+            sort_order: SortOrder::default(),
+            max_line_length: None,
+            sources,
+            color: true,
+            group_by_root: false,
+            verbatim_names: false,
+        }
+    }
+
+    /// Set the maximum line length for the output.
+    ///
+    /// Annotation comments that exceed the limit are wrapped onto continuation lines. Requirements,
+    /// URLs, and hashes can't be broken; use [`DisplayResolutionGraph::overlong_entries`] to
+    /// determine whether any exceed the limit.
+    #[must_use]
+    pub fn with_max_line_length(self, max_line_length: usize) -> Self {
+        Self {
+            max_line_length: Some(max_line_length),
+            ..self
+        }
+    }
+
+    /// Return the names of any packages whose requirement (including its URL and hashes) exceeds
+    /// the maximum line length, sorted by name, such that the caller can warn about them.
+    ///
+    /// Returns an empty `Vec` if no maximum line length is set.
+    pub fn overlong_entries(&self) -> Vec<&PackageName> {
+        let Some(max_line_length) = self.max_line_length else {
+            return Vec::new();
+        };
+        let mut names = self
+            .resolution
+            .petgraph
+            .node_indices()
+            .filter_map(|index| self.node(index))
+            .filter(|node| {
+                self.requirement(node)
+                    .lines()
+                    .any(|line| line.len() > max_line_length)
+            })
+            .map(|node| node.name())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// Set the [`SortOrder`] in which packages should be emitted.
+    #[must_use]
+    pub fn with_sort_order(self, sort_order: SortOrder) -> Self {
+        Self { sort_order, ..self }
+    }
+
+    /// Set the indentation to use for annotation comments in the [`AnnotationStyle::Split`]
+    /// style (e.g., two spaces or a tab). Defaults to four spaces.
+    #[must_use]
+    pub fn with_indent(self, indent: impl Into<String>) -> Self {
+        Self {
+            indent: indent.into(),
+            ..self
+        }
+    }
+
+    /// Set the [`IndexRedaction`] mode used to render index URLs in the output.
+    #[must_use]
+    pub fn with_index_redaction(self, index_redaction: IndexRedaction) -> Self {
+        Self {
+            index_redaction,
+            ..self
+        }
+    }
+
+    /// Restrict the hashes in the output to the given [`HashAlgorithm`] (e.g., omitting any `md5`
+    /// hashes in favor of `sha256`).
+    ///
+    /// Packages without any hash in the given algorithm are emitted with all of their hashes,
+    /// along with a warning, such that hashes are never silently omitted.
+    #[must_use]
+    pub fn with_hash_algorithm(self, hash_algorithm: HashAlgorithm) -> Self {
+        Self {
+            hash_algorithm: Some(hash_algorithm),
+            ..self
+        }
+    }
+
+    /// Set whether to colorize annotation and warning comments in the output. Defaults to `true`.
+    ///
+    /// Disabling color produces plain text regardless of the global color settings, e.g., when
+    /// writing a resolution to a file.
+    #[must_use]
+    pub fn with_color(self, color: bool) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Set whether to render each package name as published (e.g., `Flask`), as inferred from the
+    /// filename of the pinned artifact, rather than normalized (e.g., `flask`).
+    ///
+    /// Editables and URL requirements are always rendered as given.
+    #[must_use]
+    pub fn with_verbatim_names(self, verbatim_names: bool) -> Self {
+        Self {
+            verbatim_names,
+            ..self
+        }
+    }
+
+    /// Set whether to group the output by top-level requirement, as in `pip-compile`'s layered
+    /// output.
+    ///
+    /// When enabled, each top-level requirement is emitted under a `# --- via <root> ---` header,
+    /// along with the packages that are only required by that requirement. Packages that are
+    /// required by multiple top-level requirements are emitted once, under a final
+    /// `# --- shared ---` header. Grouping is ignored by the [`AnnotationStyle::Json`] and
+    /// [`AnnotationStyle::Tree`] styles.
+    #[must_use]
+    pub fn with_group_by_root(self, group_by_root: bool) -> Self {
+        Self {
+            group_by_root,
+            ..self
+        }
+    }
+}
+
+/// A single package in the [`AnnotationStyle::Json`] output.
+#[derive(Debug, serde::Serialize)]
+struct AnnotationJson<'a> {
+    /// The name of the package.
+    name: &'a PackageName,
+    /// The pinned version of the package, or `null` for URL-based and editable distributions.
+    version: Option<&'a Version>,
+    /// The URL of the package, or `null` for registry distributions.
+    url: Option<Cow<'a, str>>,
+    /// The hashes of the package's distribution, if hashes are shown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hashes: Option<Vec<String>>,
+    /// The enabled extras for the package, if extras are included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extras: Option<Vec<&'a ExtraName>>,
+    /// The marker under which the package is required, if markers are included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    marker: Option<String>,
+    /// The index from which the package was resolved, if index annotations are included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<Cow<'a, str>>,
+    /// The names of the package's dependencies, if annotations are included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requires: Option<Vec<&'a PackageName>>,
+    /// The names of the package's dependents, if annotations are included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required_by: Option<Vec<&'a PackageName>>,
+}
+
+#[derive(Debug)]
+enum Node<'a> {
+    /// A node linked to an editable distribution.
+    Editable(&'a PackageName, &'a LocalEditable),
+    /// A node linked to a non-editable distribution.
+    Distribution(&'a PackageName, &'a ResolvedDist, &'a [ExtraName]),
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum NodeKey<'a> {
+    /// A node linked to an editable distribution, sorted by verbatim representation (with
+    /// normalized path separators).
+    Editable(Cow<'a, str>),
+    /// A node linked to a non-editable distribution, sorted by package name.
+    Distribution(&'a PackageName),
+}
+
+impl<'a> Node<'a> {
+    /// Return the name of the package.
+    fn name(&self) -> &'a PackageName {
+        match self {
+            Node::Editable(name, _) => name,
+            Node::Distribution(name, _, _) => name,
+        }
+    }
+
+    /// Return a comparable key for the node.
+    fn key(&self) -> NodeKey<'a> {
+        match self {
+            Node::Editable(_, editable) => {
+                NodeKey::Editable(normalize_separators(editable.verbatim()))
+            }
+            Node::Distribution(name, _, _) => NodeKey::Distribution(name),
+        }
+    }
+
+    /// Return the [`IndexUrl`] of the distribution, if any.
+    fn index(&self) -> Option<&IndexUrl> {
+        match self {
+            Node::Editable(_, _) => None,
+            Node::Distribution(_, dist, _) => dist.index(),
+        }
+    }
+}
+
+/// Wrap the lines of an annotation comment (e.g., `    # via foo, bar`) to the given width, using
+/// `#   ` as the continuation prefix.
+fn wrap_comment(comment: &str, width: usize) -> String {
+    comment
+        .lines()
+        .map(|line| {
+            let text = line.trim_start();
+            let indent = &line[..line.len() - text.len()];
+            match text.strip_prefix("# ") {
+                Some(text) if line.len() > width => {
+                    let initial_indent = format!("{indent}# ");
+                    let subsequent_indent = format!("{indent}#   ");
+                    let options = textwrap::Options::new(width)
+                        .initial_indent(&initial_indent)
+                        .subsequent_indent(&subsequent_indent)
+                        .break_words(false);
+                    textwrap::wrap(text.trim_start(), options).join("\n")
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Normalize the path separators in a verbatim path, such that the output ordering is identical
+/// regardless of the platform on which it was generated (e.g., `..\foo` and `../foo`).
+fn normalize_separators(path: Cow<'_, str>) -> Cow<'_, str> {
+    if path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        path
+    }
+}
+
+impl Verbatim for Node<'_> {
+    fn verbatim(&self) -> Cow<'_, str> {
+        match self {
+            Node::Editable(_, editable) => Cow::Owned(format!("-e {}", editable.verbatim())),
+            Node::Distribution(_, dist, &[]) => dist.verbatim(),
+            Node::Distribution(_, dist, extras) => {
+                let mut extras = extras.to_vec();
+                extras.sort_unstable();
+                extras.dedup();
+                Cow::Owned(format!(
+                    "{}[{}]{}",
+                    dist.name(),
+                    extras.into_iter().join(", "),
+                    dist.version_or_url().verbatim()
+                ))
+            }
+        }
+    }
+}
+
+impl DisplayResolutionGraph<'_> {
+    /// Assign each package to the top-level requirement whose closure it belongs to, or to `None`
+    /// if it's shared between multiple top-level requirements (or required by none).
+    ///
+    /// Each top-level requirement belongs to its own group, and the closure of a top-level
+    /// requirement stops at any other top-level requirement, such that the dependencies of a
+    /// top-level requirement are grouped with it even if it's also required by another.
+    fn root_groups(&self) -> FxHashMap<NodeIndex, Option<NodeIndex>> {
+        let petgraph = &self.resolution.petgraph;
+        let mut groups = FxHashMap::default();
+        for root in petgraph
+            .node_indices()
+            .filter(|index| self.resolution.is_root_index(*index))
+        {
+            groups.insert(root, Some(root));
+
+            let mut seen = FxHashSet::from_iter([root]);
+            let mut queue = VecDeque::from([root]);
+            while let Some(index) = queue.pop_front() {
+                for dependency in petgraph.neighbors_directed(index, Direction::Outgoing) {
+                    if self.resolution.is_root_index(dependency) || !seen.insert(dependency) {
+                        continue;
+                    }
+                    groups
+                        .entry(dependency)
+                        .and_modify(|group: &mut Option<NodeIndex>| {
+                            if *group != Some(root) {
+                                *group = None;
+                            }
+                        })
+                        .or_insert(Some(root));
+                    queue.push_back(dependency);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Return every shortest chain of dependents from a top-level requirement to the given
+    /// node, sorted by name, for the [`AnnotationStyle::Verbose`] style. Each chain starts at the
+    /// top-level requirement and ends at the immediate dependent of the node.
+    ///
+    /// Returns an empty list for top-level requirements, and for any node that isn't reachable
+    /// from a top-level requirement.
+    fn shortest_paths(&self, target: NodeIndex) -> Vec<Vec<NodeIndex>> {
+        let petgraph = &self.resolution.petgraph;
+        if self.resolution.is_root_index(target) {
+            return Vec::new();
+        }
+
+        // Walk the graph backwards from the target, recording the distance from each dependent
+        // to the target. The walk stops at top-level requirements.
+        let mut distances = FxHashMap::from_iter([(target, 0usize)]);
+        let mut roots = Vec::new();
+        let mut queue = VecDeque::from([target]);
+        while let Some(index) = queue.pop_front() {
+            let distance = distances[&index];
+            for dependent in petgraph.neighbors_directed(index, Direction::Incoming) {
+                if distances.contains_key(&dependent) {
+                    continue;
+                }
+                distances.insert(dependent, distance + 1);
+                if self.resolution.is_root_index(dependent) {
+                    roots.push(dependent);
+                } else {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+        let Some(shortest) = roots.iter().map(|root| distances[root]).min() else {
+            return Vec::new();
+        };
+
+        // Expand each path from the nearest top-level requirements, following only the edges
+        // that lead one step closer to the target.
+        let mut paths = Vec::new();
+        let mut stack = roots
+            .into_iter()
+            .filter(|root| distances[root] == shortest)
+            .map(|root| vec![root])
+            .collect::<Vec<_>>();
+        while let Some(path) = stack.pop() {
+            let last = *path.last().expect("paths are non-empty");
+            let distance = distances[&last];
+            if distance == 1 {
+                paths.push(path);
+                continue;
+            }
+            for dependency in petgraph.neighbors_directed(last, Direction::Outgoing) {
+                if distances.get(&dependency) == Some(&(distance - 1)) {
+                    let mut path = path.clone();
+                    path.push(dependency);
+                    stack.push(path);
+                }
+            }
+        }
+
+        paths.sort_by(|a, b| {
+            a.iter()
+                .map(|index| petgraph[*index].name())
+                .cmp(b.iter().map(|index| petgraph[*index].name()))
+        });
+        paths.dedup();
+        paths
+    }
+
+    /// Return the [`Node`] to display for the given index, or `None` if it should be omitted.
+    fn node(&self, index: NodeIndex) -> Option<Node<'_>> {
+        let dist = &self.resolution.petgraph[index];
+        let name = dist.name();
+        if self.no_emit_packages.contains(name) {
+            return None;
+        }
+
+        let node = if let Some((editable, _, _)) = self.resolution.editables.get(name) {
+            Node::Editable(name, editable)
+        } else if self.include_extras {
+            Node::Distribution(
+                name,
+                dist,
+                self.resolution
+                    .extras
+                    .get(name)
+                    .map_or(&[], |extras| extras.as_slice()),
+            )
+        } else {
+            Node::Distribution(name, dist, &[])
+        };
+        Some(node)
+    }
+
+    /// Return the hashes to display for the given package, restricted to the requested
+    /// [`HashAlgorithm`], if any.
+    ///
+    /// If the package has no hashes in the requested algorithm, all of its hashes are returned,
+    /// and the second element is `true`.
+    fn hashes(&self, name: &PackageName) -> (Vec<&HashDigest>, bool) {
+        let hashes = self
+            .resolution
+            .hashes
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let Some(algorithm) = self.hash_algorithm else {
+            return (hashes.iter().collect(), false);
+        };
+        let filtered = hashes
+            .iter()
+            .filter(|hash| hash.algorithm == algorithm)
+            .collect::<Vec<_>>();
+        if filtered.is_empty() && !hashes.is_empty() {
+            (hashes.iter().collect(), true)
+        } else {
+            (filtered, false)
+        }
+    }
+
+    /// Render the requirement for the given node, along with the marker under which it was
+    /// selected (if enabled) and its hashes (if enabled), without any annotations.
+    fn requirement(&self, node: &Node) -> String {
+        let mut line = node.verbatim().to_string();
+
+        // Display the name as published, if requested. The rendered requirement always begins
+        // with the normalized name.
+        if self.verbatim_names {
+            if let Node::Distribution(name, dist, ..) = node {
+                if let Some(verbatim) = verbatim_name(dist) {
+                    line.replace_range(..name.as_ref().len(), verbatim);
+                }
+            }
+        }
+
+        // Display the marker under which the package was selected, if any. Editables are always
+        // requested directly, and so are never conditional.
+        if self.include_markers {
+            if let Node::Distribution(name, ..) = node {
+                if let Some(marker) = self.resolution.markers.get(name) {
+                    line.push_str(" ; ");
+                    line.push_str(&marker.to_string());
+                }
+            }
+        }
+
+        // Display the distribution hashes, if any.
+        if self.show_hashes {
+            let (hashes, _) = self.hashes(node.name());
+            for hash in hashes {
+                line.push_str(" \\\n");
+                line.push_str("    --hash=");
+                line.push_str(&hash.to_string());
+            }
+        }
+
+        line
+    }
+
+    /// Render the given text in the given color, if color is enabled.
+    fn paint(&self, text: String, color: AnsiColors) -> String {
+        if self.color {
+            text.color(color).to_string()
+        } else {
+            text
+        }
+    }
+
+    /// Render the given [`IndexUrl`] according to the [`IndexRedaction`] mode.
+    fn index_url<'index>(&self, index: &'index IndexUrl) -> Cow<'index, str> {
+        match self.index_redaction {
+            IndexRedaction::Redacted => Cow::Owned(index.redacted().to_string()),
+            IndexRedaction::Verbatim => index.verbatim(),
+            IndexRedaction::Expanded => Cow::Owned(index.url().to_string()),
+        }
+    }
+
+    /// Write the given node as a single line of JSON.
+    ///
+    /// Fields that correspond to disabled output options (e.g., hashes, when hashes aren't shown)
+    /// are omitted entirely.
+    fn fmt_json(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        index: NodeIndex,
+        node: &Node<'_>,
+    ) -> std::fmt::Result {
+        let petgraph = &self.resolution.petgraph;
+        let name = node.name();
+        let (version, url) = match node {
+            Node::Editable(_, editable) => (None, Some(editable.verbatim())),
+            Node::Distribution(_, dist, _) => match dist.version_or_url() {
+                VersionOrUrlRef::Version(version) => (Some(version), None),
+                VersionOrUrlRef::Url(url) => (None, Some(url.verbatim())),
+            },
+        };
+
+        let hashes = self.show_hashes.then(|| {
+            self.hashes(name)
+                .0
+                .into_iter()
+                .map(ToString::to_string)
+                .collect()
+        });
+        let extras = self.include_extras.then(|| {
+            let mut extras = self
+                .resolution
+                .extras
+                .get(name)
+                .map(|extras| extras.iter().collect::<Vec<_>>())
+                .unwrap_or_default();
+            extras.sort_unstable();
+            extras.dedup();
+            extras
+        });
+        let marker = self
+            .include_markers
+            .then(|| self.resolution.markers.get(name).map(ToString::to_string))
+            .flatten();
+        let index_url = self
+            .include_index_annotation
+            .then(|| node.index().map(|index| self.index_url(index)))
+            .flatten();
+
+        let (requires, required_by) = if self.include_annotations {
+            let neighbors = |direction| {
+                petgraph
+                    .neighbors_directed(index, direction)
+                    .map(|neighbor| petgraph[neighbor].name())
+                    .sorted_unstable()
+                    .dedup()
+                    .collect::<Vec<_>>()
+            };
+            (
+                Some(neighbors(Direction::Outgoing)),
+                Some(neighbors(Direction::Incoming)),
+            )
+        } else {
+            (None, None)
+        };
+
+        let json = AnnotationJson {
+            name,
+            version,
+            url,
+            hashes,
+            extras,
+            marker,
+            index: index_url,
+            requires,
+            required_by,
+        };
+        let line = serde_json::to_string(&json).map_err(|_| std::fmt::Error)?;
+        writeln!(f, "{line}")
+    }
+
+    /// Write the graph as a nested tree, with each top-level requirement followed by its
+    /// transitive dependencies.
+    ///
+    /// Packages that appear in multiple subtrees are expanded the first time they're written, and
+    /// marked with `(*)` thereafter.
+    fn fmt_tree(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut roots = self
+            .resolution
+            .petgraph
+            .node_indices()
+            .filter(|index| self.resolution.is_root_index(*index))
+            .filter_map(|index| Some((index, self.node(index)?)))
+            .collect::<Vec<_>>();
+        roots.sort_unstable_by_key(|(index, node)| (node.key(), *index));
+
+        // Top-level requirements are always expanded at the root, even if another requirement
+        // depends on them.
+        let mut seen = roots
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<FxHashSet<_>>();
+        for (index, node) in roots {
+            writeln!(f, "{}", node.verbatim())?;
+            self.fmt_subtree(f, index, "", &mut seen)?;
+        }
+        Ok(())
+    }
+
+    /// Write the dependencies of the given node, each prefixed with the given indentation.
+    fn fmt_subtree(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        index: NodeIndex,
+        prefix: &str,
+        seen: &mut FxHashSet<NodeIndex>,
+    ) -> std::fmt::Result {
+        let mut dependencies = self
+            .resolution
+            .petgraph
+            .edges_directed(index, Direction::Outgoing)
+            .filter_map(|edge| Some((edge.target(), edge.weight(), self.node(edge.target())?)))
+            .collect::<Vec<_>>();
+        dependencies.sort_unstable_by_key(|(index, _, node)| (node.key(), *index));
+
+        let count = dependencies.len();
+        for (position, (dependency, range, node)) in dependencies.into_iter().enumerate() {
+            let (branch, continuation) = if position + 1 == count {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            let required = if *range == Range::full() {
+                Cow::Borrowed("any")
+            } else {
+                Cow::Owned(range.to_string())
+            };
+            write!(
+                f,
+                "{prefix}{branch}{} [required: {required}]",
+                node.verbatim()
+            )?;
+            if seen.insert(dependency) {
+                writeln!(f)?;
+                self.fmt_subtree(f, dependency, &format!("{prefix}{continuation}"), seen)?;
+            } else {
+                writeln!(f, " (*)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write the graph in the `{name}=={version}` format of requirements.txt that pip uses.
+impl std::fmt::Display for DisplayResolutionGraph<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.annotation_style {
+            AnnotationStyle::Tree => return self.fmt_tree(f),
+            AnnotationStyle::Line
+            | AnnotationStyle::Split
+            | AnnotationStyle::Json
+            | AnnotationStyle::Verbose => {}
+        }
+
+        // Collect all packages.
+        let mut nodes = self
+            .resolution
+            .petgraph
+            .node_indices()
+            .filter_map(|index| Some((index, self.node(index)?)))
+            .collect::<Vec<_>>();
+
+        match self.sort_order {
+            SortOrder::Alphabetical => {
+                // Sort the nodes by name, but with editable packages first.
+                nodes.sort_unstable_by_key(|(index, node)| (node.key(), *index));
+            }
+            SortOrder::Topological => {
+                // Sort the nodes such that dependencies come before their dependents.
+                let position = self
+                    .resolution
+                    .topological_order()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(position, index)| (index, position))
+                    .collect::<FxHashMap<_, _>>();
+                nodes.sort_unstable_by_key(|(index, _)| position[index]);
+            }
+        }
+
+        // If enabled, partition the nodes by top-level requirement, preserving the sort order
+        // within each group, with the shared packages last.
+        let groups = (self.group_by_root && self.annotation_style != AnnotationStyle::Json)
+            .then(|| self.root_groups());
+        if let Some(groups) = groups.as_ref() {
+            nodes.sort_by_key(|(index, _)| {
+                groups
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .map(|root| self.resolution.petgraph[root].name())
+                    .map_or((1, None), |name| (0, Some(name)))
+            });
+        }
+
+        // Print out the dependency graph.
+        let mut current = None;
+        for (index, node) in nodes {
+            if self.annotation_style == AnnotationStyle::Json {
+                self.fmt_json(f, index, &node)?;
+                continue;
+            }
+
+            // If grouping, open a new group with a header.
+            if let Some(groups) = groups.as_ref() {
+                let group = groups.get(&index).copied().flatten();
+                if current != Some(group) {
+                    match group {
+                        Some(root) => {
+                            writeln!(f, "# --- via {} ---", self.resolution.petgraph[root].name())?
+                        }
+                        None => writeln!(f, "# --- shared ---")?,
+                    }
+                    current = Some(group);
+                }
+            }
+
+            // Display the node itself, along with its marker and hashes.
+            let line = self.requirement(&node);
+            let (has_hashes, missing_algorithm) = if self.show_hashes {
+                let (hashes, fallback) = self.hashes(node.name());
+                (!hashes.is_empty(), fallback)
+            } else {
+                (false, false)
+            };
+
+            // Determine the annotation comment and separator (between comment and requirement).
+            let mut annotation = None;
+
+            // If enabled, include annotations to indicate the dependencies that requested each
+            // package (e.g., `# via mypy`).
+            if self.include_annotations {
+                // Display all dependencies.
+                let mut edges = self
+                    .resolution
+                    .requiring(index)
+                    .map(|(dist, _)| dist)
+                    .collect::<Vec<_>>();
+                edges.sort_unstable_by(|a, b| {
+                    a.name()
+                        .cmp(b.name())
+                        .then_with(|| a.verbatim().cmp(&b.verbatim()))
+                });
+
+                // Include all external sources (e.g., requirements files).
+                let default = BTreeSet::default();
+                let source = match node {
+                    Node::Editable(_, editable) => {
+                        self.sources.get_editable(&editable.url).unwrap_or(&default)
+                    }
+                    Node::Distribution(name, _, _) => self.sources.get(name).unwrap_or(&default),
+                };
+
+                match self.annotation_style {
+                    AnnotationStyle::Line => {
+                        if !edges.is_empty() {
+                            let separator = if has_hashes { "\n    " } else { "  " };
+                            let deps = edges
+                                .into_iter()
+                                .map(|dependency| format!("{}", dependency.name()))
+                                .chain(source.iter().map(std::string::ToString::to_string))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            let comment = format!("# via {deps}");
+                            annotation = Some((separator, comment));
+                        }
+                    }
+                    AnnotationStyle::Split | AnnotationStyle::Verbose => {
+                        // Order the annotations such that the external sources come first,
+                        // followed by the dependents in the graph, each in sorted order. In the
+                        // verbose style, each dependent is expanded to the full chain from a
+                        // top-level requirement.
+                        let dependents = if self.annotation_style == AnnotationStyle::Verbose {
+                            self.shortest_paths(index)
+                                .into_iter()
+                                .map(|path| {
+                                    path.into_iter()
+                                        .map(|index| self.resolution.petgraph[index].name())
+                                        .join(" -> ")
+                                })
+                                .collect::<Vec<_>>()
+                        } else {
+                            edges
+                                .iter()
+                                .map(|dependency| format!("{}", dependency.name()))
+                                .dedup()
+                                .collect::<Vec<_>>()
+                        };
+                        let deps = source
+                            .iter()
+                            .map(std::string::ToString::to_string)
+                            .chain(dependents)
+                            .collect::<Vec<_>>();
+                        match deps.as_slice() {
+                            [] => {}
+                            [dep] => {
+                                let separator = "\n";
+                                let indent = &self.indent;
+                                let comment = format!("{indent}# via {dep}");
+                                annotation = Some((separator, comment));
+                            }
+                            deps => {
+                                let separator = "\n";
+                                let indent = &self.indent;
+                                let deps = deps
+                                    .iter()
+                                    .map(|name| format!("{indent}#   {name}"))
+                                    .collect::<Vec<_>>()
+                                    .join("\n");
+                                let comment = format!("{indent}# via\n{deps}");
+                                annotation = Some((separator, comment));
+                            }
+                        }
+                    }
+                    // The tree and JSON styles are rendered separately, above.
+                    AnnotationStyle::Tree | AnnotationStyle::Json => {}
+                }
+            }
+
+            if let Some((separator, comment)) = annotation {
+                let (separator, comment) = match self.max_line_length {
+                    Some(max_line_length) => {
+                        // If the annotation doesn't fit alongside the requirement, move it onto
+                        // its own line.
+                        let (separator, comment) = if let Some(indent) =
+                            separator.strip_prefix('\n')
+                        {
+                            ("\n", format!("{indent}{comment}"))
+                        } else if format!("{line:24}{separator}{comment}").len() > max_line_length {
+                            ("\n", format!("    {comment}"))
+                        } else {
+                            (separator, comment)
+                        };
+                        (separator, wrap_comment(&comment, max_line_length))
+                    }
+                    None => (separator, comment),
+                };
+                let comment = self.paint(comment, AnsiColors::Green);
+
+                // Assemble the line with the annotations and remove trailing whitespaces.
+                for line in format!("{line:24}{separator}{comment}").lines() {
+                    let line = line.trim_end();
+                    writeln!(f, "{line}")?;
+                }
+            } else {
+                // Write the line as is.
+                writeln!(f, "{line}")?;
+            }
+
+            // If enabled, include indexes to indicate which index was used for each package (e.g.,
+            // `# from https://pypi.org/simple`).
+            if self.include_index_annotation {
+                if let Some(index) = node.index() {
+                    let url = self.index_url(index);
+                    let comment = format!("    # from {url}");
+                    writeln!(f, "{}", self.paint(comment, AnsiColors::Green))?;
+                }
+            }
+
+            // If extras are enabled, flag any extras that didn't add any packages.
+            if self.include_extras {
+                for diagnostic in &self.resolution.diagnostics {
+                    if let Diagnostic::UnusedExtra { dist, extra } = diagnostic {
+                        if dist.name() == node.name() {
+                            let comment =
+                                format!("    # warning: extra `{extra}` added no dependencies");
+                            writeln!(f, "{}", self.paint(comment, AnsiColors::Yellow))?;
+                        }
+                    }
+                }
+            }
+
+            // If hashes are restricted to a single algorithm, flag any packages for which all
+            // hashes were emitted, since none used the requested algorithm.
+            if missing_algorithm {
+                if let Some(algorithm) = self.hash_algorithm {
+                    let comment = format!("    # warning: no {algorithm} hash found");
+                    writeln!(f, "{}", self.paint(comment, AnsiColors::Yellow))?;
+                }
+            }
+
+            // If hashes are enabled, flag any hashes that didn't match the fetched content, along
+            // with any packages for which no hash was found.
+            if self.show_hashes {
+                for diagnostic in &self.resolution.diagnostics {
+                    match diagnostic {
+                        Diagnostic::InvalidHash {
+                            dist,
+                            expected,
+                            actual,
+                        } if dist.name() == node.name() => {
+                            let comment =
+                                format!("    # warning: expected {expected}, but found {actual}");
+                            writeln!(f, "{}", self.paint(comment, AnsiColors::Yellow))?;
+                        }
+                        Diagnostic::MissingHash { dist } if dist.name() == node.name() => {
+                            let comment = "    # warning: no hash found".to_string();
+                            writeln!(f, "{}", self.paint(comment, AnsiColors::Yellow))?;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<ResolutionGraph> for distribution_types::Resolution {
+    fn from(graph: ResolutionGraph) -> Self {
+        Self::new(
+            graph
+                .petgraph
+                .node_indices()
+                .map(|node| {
+                    (
+                        graph.petgraph[node].name().clone(),
+                        graph.petgraph[node].clone(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Return the name of the given distribution as published (e.g., `Flask`), as inferred from the
+/// filename of the pinned artifact, if it differs from the normalized name.
+fn verbatim_name(dist: &ResolvedDist) -> Option<&str> {
+    let ResolvedDist::Installable(dist) = dist else {
+        return None;
+    };
+    let filename = dist.file()?.filename.as_str();
+    filename
+        .match_indices('-')
+        .map(|(index, _)| &filename[..index])
+        .find(|prefix| PackageName::from_str(prefix).is_ok_and(|name| name == *dist.name()))
+        .filter(|prefix| *prefix != dist.name().as_ref())
+}
+
+/// Return the expanded tags of the given wheel (e.g., `cp312-cp312-win_amd64`), if none of them
+/// are supported by the target interpreter.
+fn incompatible_wheel_tags(filename: &WheelFilename, tags: &Tags) -> Option<Vec<Tag>> {
+    if filename.is_compatible(tags) {
+        return None;
+    }
+    Some(
+        itertools::iproduct!(
+            &filename.python_tag,
+            &filename.abi_tag,
+            &filename.platform_tag
+        )
+        .map(|(python, abi, platform)| Tag {
+            python: python.clone(),
+            abi: abi.clone(),
+            platform: platform.clone(),
+        })
+        .collect(),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub enum Diagnostic {
+    MissingExtra {
+        /// The distribution that was requested with an non-existent extra. For example,
+        /// `black==23.10.0`.
+        dist: ResolvedDist,
+        /// The extra that was requested. For example, `colorama` in `black[colorama]`.
+        extra: ExtraName,
+    },
+    PinnedByDependent {
+        /// The package that was pinned to a single version. For example, `idna`.
+        package: PackageName,
+        /// The dependent that requested the exact version. For example, `requests`.
+        pinned_by: PackageName,
+        /// The version to which the package was pinned. For example, `3.7`.
+        version: Version,
+    },
+    InvalidHash {
+        /// The distribution whose hash doesn't match the expected hash. For example,
+        /// `idna==3.7`.
+        dist: ResolvedDist,
+        /// The expected hash (e.g., from a lockfile or `--hash` flag).
+        expected: HashDigest,
+        /// The hash of the fetched content, using the same algorithm as the expected hash.
+        actual: HashDigest,
+    },
+    IncompatiblePythonVersion {
+        /// The distribution whose `Requires-Python` excludes the installed interpreter. For
+        /// example, `numpy==2.0.0`.
+        dist: ResolvedDist,
+        /// The `Requires-Python` specifier of the distribution. For example, `>=3.9`.
+        requires_python: VersionSpecifiers,
+        /// The version of the installed interpreter. For example, `3.8.18`.
+        installed_python: Version,
+    },
+    YankedPackage {
+        /// The distribution that was selected despite being yanked. For example,
+        /// `attrs==21.1.0`.
+        dist: ResolvedDist,
+        /// The reason that the distribution was yanked, if provided by the index.
+        reason: Option<String>,
+    },
+    MissingHash {
+        /// The distribution for which no hash was recorded, despite hashes being required. For
+        /// example, `idna==3.7`.
+        dist: ResolvedDist,
+    },
+    CyclicDependency {
+        /// The packages that form the cycle, sorted by name. For example, `["a", "b"]` if `a`
+        /// depends on `b` and `b` depends on `a`.
+        cycle: Vec<PackageName>,
+    },
+    MissingLowerBound {
+        /// The direct requirement that was specified without a lower bound. For example,
+        /// `requests` in `requests<3`.
+        name: PackageName,
+    },
+    ConflictingRequiresPython {
+        /// The distribution whose `Requires-Python` lower bound conflicts with the upper bound of
+        /// another. For example, `numpy==2.0.0`.
+        dist: ResolvedDist,
+        /// The `Requires-Python` specifier of the distribution. For example, `>=3.12`.
+        requires_python: VersionSpecifiers,
+        /// The distribution whose `Requires-Python` upper bound conflicts with the lower bound.
+        /// For example, `legacy==1.0.0`.
+        conflicting_dist: ResolvedDist,
+        /// The `Requires-Python` specifier of the conflicting distribution. For example, `<3.11`.
+        conflicting_requires_python: VersionSpecifiers,
+    },
+    DuplicatePackage {
+        /// The package that appears in the resolution more than once. For example, `flask`.
+        name: PackageName,
+        /// The distributions of the package. For example, `flask==3.0.0` and an editable
+        /// `flask @ file:///home/ferris/flask`.
+        dists: Vec<ResolvedDist>,
+    },
+    UnusedExtra {
+        /// The distribution that was requested with an extra that added no dependencies. For
+        /// example, `black==23.10.0`.
+        dist: ResolvedDist,
+        /// The extra that added no dependencies. For example, `colorama` in `black[colorama]`.
+        extra: ExtraName,
+    },
+    PreReleaseIncluded {
+        /// The pre-release distribution that was selected, since no stable version satisfies the
+        /// requirements. For example, `black==24.1b1`.
+        dist: ResolvedDist,
+        /// The newest stable version of the package, if any. For example, `23.12.1`.
+        stable_version: Option<Version>,
+    },
+    PlatformIncompatible {
+        /// The distribution that will be built from source, since its only wheel targets another
+        /// platform. For example, `pywin32==306`.
+        dist: ResolvedDist,
+        /// The tags of the incompatible wheel. For example, `cp312-cp312-win_amd64`.
+        dist_tags: Vec<Tag>,
+        /// The component of the wheel's tags that the target interpreter doesn't support. For
+        /// example, the platform.
+        incompatibility: IncompatibleTag,
+    },
+}
+
+impl Diagnostic {
+    /// Convert the diagnostic into a user-facing message.
+    pub fn message(&self) -> String {
+        match self {
+            Self::MissingExtra { dist, extra } => {
+                format!("The package `{dist}` does not have an extra named `{extra}`.")
+            }
+            Self::PinnedByDependent {
+                package,
+                pinned_by,
+                version,
+            } => {
+                format!("The package `{package}` was pinned to `=={version}` by `{pinned_by}`.")
+            }
+            Self::InvalidHash {
+                dist,
+                expected,
+                actual,
+            } => {
+                format!("The hash for `{dist}` does not match: expected `{expected}`, but found `{actual}`.")
+            }
+            Self::IncompatiblePythonVersion {
+                dist,
+                requires_python,
+                installed_python,
+            } => {
+                format!("The package `{dist}` requires Python {requires_python}, but the resolved interpreter is Python {installed_python}.")
+            }
+            Self::YankedPackage { dist, reason } => match reason {
+                Some(reason) => format!("The package `{dist}` is yanked (reason: \"{reason}\")."),
+                None => format!("The package `{dist}` is yanked."),
+            },
+            Self::MissingHash { dist } => {
+                format!("No hash found for package `{dist}`; use `--hash` to specify one or regenerate the lock file.")
+            }
+            Self::CyclicDependency { cycle } => {
+                format!(
+                    "Detected a dependency cycle between: {}.",
+                    cycle.iter().map(|name| format!("`{name}`")).join(", ")
+                )
+            }
+            Self::MissingLowerBound { name } => {
+                format!("The requirement `{name}` has no lower bound, so its resolution may change as new versions are published.")
+            }
+            Self::ConflictingRequiresPython {
+                dist,
+                requires_python,
+                conflicting_dist,
+                conflicting_requires_python,
+            } => {
+                format!("The package `{dist}` requires Python {requires_python}, but `{conflicting_dist}` requires Python {conflicting_requires_python}.")
+            }
+            Self::DuplicatePackage { name, dists } => {
+                format!(
+                    "The package `{name}` appears in the resolution more than once: {}.",
+                    dists.iter().map(|dist| format!("`{dist}`")).join(", ")
+                )
+            }
+            Self::UnusedExtra { dist, extra } => {
+                format!("The extra `{extra}` of `{dist}` did not add any dependencies.")
+            }
+            Self::PreReleaseIncluded {
+                dist,
+                stable_version,
+            } => match stable_version {
+                Some(stable_version) => format!(
+                    "The package `{dist}` is a pre-release, selected since no stable version satisfies the requirements; pin to `{}=={stable_version}` or pass `--pre` to allow pre-releases explicitly.",
+                    dist.name()
+                ),
+                None => format!(
+                    "The package `{dist}` is a pre-release, selected since the package has no stable versions; pass `--pre` to allow pre-releases explicitly."
+                ),
+            },
+            Self::PlatformIncompatible {
+                dist, dist_tags, ..
+            } => {
+                format!(
+                    "The package `{dist}` has no wheel for the target platform (found: {}), so it will be built from source.",
+                    dist_tags.iter().map(|tag| format!("`{tag}`")).join(", ")
+                )
+            }
+        }
+    }
+
+    /// Returns `true` if the [`PackageName`] is involved in this diagnostic.
+    pub fn includes(&self, name: &PackageName) -> bool {
+        match self {
+            Self::MissingExtra { dist, .. } | Self::UnusedExtra { dist, .. } => name == dist.name(),
+            Self::PinnedByDependent {
+                package, pinned_by, ..
+            } => name == package || name == pinned_by,
+            Self::InvalidHash { dist, .. }
+            | Self::IncompatiblePythonVersion { dist, .. }
+            | Self::YankedPackage { dist, .. }
+            | Self::PreReleaseIncluded { dist, .. }
+            | Self::PlatformIncompatible { dist, .. }
+            | Self::MissingHash { dist } => name == dist.name(),
+            Self::ConflictingRequiresPython {
+                dist,
+                conflicting_dist,
+                ..
+            } => name == dist.name() || name == conflicting_dist.name(),
+            Self::CyclicDependency { cycle } => cycle.contains(name),
+            Self::DuplicatePackage { name: package, .. } => name == package,
+            Self::MissingLowerBound { name: package } => name == package,
+        }
+    }
+
+    /// Returns `true` if the diagnostic is advisory, i.e., it describes a valid resolution that
+    /// may nonetheless be surprising, and so should only be reported to users on request.
+    ///
+    /// Missing hashes and duplicate packages are also considered advisory, since `--require-hashes`
+    /// rejects unhashed requirements upfront, and duplicates indicate a resolver bug rather than
+    /// something the user can act on.
+    pub fn is_advisory(&self) -> bool {
+        match self {
+            Self::MissingExtra { .. } | Self::InvalidHash { .. } | Self::YankedPackage { .. } => {
+                false
+            }
+            Self::PinnedByDependent { .. }
+            | Self::IncompatiblePythonVersion { .. }
+            | Self::MissingHash { .. }
+            | Self::CyclicDependency { .. }
+            | Self::MissingLowerBound { .. }
+            | Self::ConflictingRequiresPython { .. }
+            | Self::DuplicatePackage { .. }
+            | Self::UnusedExtra { .. }
+            | Self::PreReleaseIncluded { .. }
+            | Self::PlatformIncompatible { .. } => true,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    use indexmap::IndexMap;
+    use pubgrub::range::Range;
+    use pubgrub::solver::State;
+    use pubgrub::type_aliases::SelectedDependencies;
+    use rustc_hash::{FxHashMap, FxHashSet};
+    use url::Url;
+
+    use distribution_filename::{DistFilename, WheelFilename};
+    use distribution_types::{
+        BuildableSource, BuiltDist, Dist, DistributionMetadata, File, FileLocation, Hash,
+        IncompatibleWheel, IndexUrl, LocalEditable, Name, PathBuiltDist, PrioritizedDist,
+        Requirement, Requirements, ResolvedDist, SourceAnnotation, SourceAnnotations,
+        SourceDistCompatibility, Verbatim, VersionId, VersionOrUrlRef, WheelCompatibility,
+    };
+    use pep440_rs::{Version, VersionSpecifiers, MIN_VERSION};
+    use pep508_rs::{
+        MarkerEnvironment, MarkerEnvironmentBuilder, MarkerTree, RequirementOrigin, VerbatimUrl,
+    };
+    use platform_tags::{Arch, IncompatibleTag, Os, Platform, Tag, TagPriority, Tags};
+    use pypi_types::{HashAlgorithm, HashDigest, Metadata23, Yanked};
+    use uv_cache::Cache;
+    use uv_distribution::ArchiveMetadata;
+    use uv_interpreter::Interpreter;
+    use uv_normalize::{ExtraName, PackageName};
+    use uv_types::HashStrategy;
+
+    use crate::dependency_provider::UvDependencyProvider;
+    use crate::editables::Editables;
+    use crate::pins::FilePins;
+    use crate::preferences::Preferences;
+    use crate::pubgrub::PubGrubPackage;
+    use crate::python_requirement::PythonRequirement;
+    use crate::resolver::Reporter as ResolverReporter;
+    use crate::{
+        InMemoryIndex, Lock, Manifest, MetadataResponse, ResolveError, VersionMap, VersionsResponse,
+    };
+
+    use super::{
+        has_lower_bound, incompatible_wheel_tags, simplify_conjuncts, AnnotationStyle,
+        DependencyPin, Diagnostic, DisplayResolutionGraph, DotOptions, Node, NodeKey,
+        PipCompileOutputOptions, ResolutionGraph, SortOrder, ValidationError, WheelhouseError,
+    };
+
+    /// Create a registry distribution for the given package name and version.
+    fn registry_dist(name: &str, version: &str) -> ResolvedDist {
+        let filename =
+            WheelFilename::from_str(&format!("{name}-{version}-py3-none-any.whl")).unwrap();
+        let file = File {
+            dist_info_metadata: false,
+            filename: filename.to_string(),
+            hashes: vec![],
+            requires_python: None,
+            size: None,
+            upload_time_utc_ms: None,
+            url: FileLocation::AbsoluteUrl(format!("https://example.org/{filename}")),
+            yanked: None,
+        };
+        let index = IndexUrl::Pypi(VerbatimUrl::from_url(
+            Url::parse("https://pypi.org/simple").unwrap(),
+        ));
+        Dist::from_registry(DistFilename::WheelFilename(filename), file, index).into()
+    }
+
+    /// Create a [`ResolutionGraph`] from a list of `name==version` packages and a list of
+    /// `(dependent, dependency)` edges.
+    pub(crate) fn graph(packages: &[(&str, &str)], edges: &[(&str, &str)]) -> ResolutionGraph {
+        let mut petgraph = petgraph::graph::Graph::new();
+        let mut inverse = FxHashMap::default();
+        for (name, version) in packages {
+            let index = petgraph.add_node(registry_dist(name, version));
+            inverse.insert(*name, index);
+        }
+        for (source, target) in edges {
+            petgraph.add_edge(inverse[source], inverse[target], Range::<Version>::full());
+        }
+        ResolutionGraph {
+            petgraph,
+            hashes: FxHashMap::default(),
+            extras: FxHashMap::default(),
+            editables: Editables::default(),
+            requested: FxHashSet::default(),
+            markers: FxHashMap::default(),
+            edge_markers: FxHashMap::default(),
+            diagnostics: vec![],
+        }
+    }
+
+    /// Mark the given packages as requested directly.
+    pub(crate) fn set_requested(resolution: &mut ResolutionGraph, names: &[&str]) {
+        resolution.requested = names
+            .iter()
+            .map(|name| PackageName::from_str(name).unwrap())
+            .collect();
+    }
+
+    /// Set the hashes recorded for the given package.
+    pub(crate) fn set_hashes(
+        resolution: &mut ResolutionGraph,
+        name: &str,
+        hashes: Vec<HashDigest>,
+    ) {
+        resolution
+            .hashes
+            .insert(PackageName::from_str(name).unwrap(), hashes);
+    }
+
+    fn editable(given: &str) -> LocalEditable {
+        LocalEditable {
+            url: VerbatimUrl::from_url(Url::parse("file:///home/ferris/project").unwrap())
+                .with_given(given),
+            path: PathBuf::from("/home/ferris/project"),
+            extras: vec![],
+        }
+    }
+
+    /// Build a [`ResolutionGraph`] via [`ResolutionGraph::from_state`], as if the root required
+    /// each of the selected packages directly.
+    fn from_state(
+        selection: &[(PubGrubPackage, &str)],
+        pins: Vec<ResolvedDist>,
+        index: &InMemoryIndex,
+        python_version: &str,
+        reporter: Option<&Arc<dyn ResolverReporter>>,
+    ) -> Result<ResolutionGraph, ResolveError> {
+        let root = PubGrubPackage::Root(None);
+        let selection = selection
+            .iter()
+            .map(|(package, version)| (package.clone(), Version::from_str(version).unwrap()))
+            .collect::<Vec<_>>();
+
+        let mut state = State::<UvDependencyProvider>::init(root.clone(), MIN_VERSION.clone());
+        state.add_incompatibility_from_dependencies(
+            root.clone(),
+            MIN_VERSION.clone(),
+            selection
+                .iter()
+                .map(|(package, version)| (package.clone(), Range::singleton(version.clone())))
+                .collect::<Vec<_>>(),
+        );
+        let selection: SelectedDependencies<UvDependencyProvider> = selection
+            .into_iter()
+            .chain(std::iter::once((root, MIN_VERSION.clone())))
+            .collect();
+
+        let pins = pins
+            .into_iter()
+            .map(|dist| {
+                let VersionOrUrlRef::Version(version) = dist.version_or_url() else {
+                    panic!("Expected a registry distribution: {dist}");
+                };
+                (dist.name().clone(), version.clone(), dist)
+            })
+            .collect::<FilePins>();
+
+        let markers = marker_env("linux", python_version);
+        let interpreter = Interpreter::artificial(
+            Platform::new(
+                Os::Manylinux {
+                    major: 2,
+                    minor: 17,
+                },
+                Arch::X86_64,
+            ),
+            markers.clone(),
+        );
+        let python_requirement = PythonRequirement::from_marker_environment(&interpreter, &markers);
+
+        ResolutionGraph::from_state(
+            &selection,
+            &pins,
+            &FxHashMap::default(),
+            &index.packages,
+            &index.distributions,
+            &state,
+            &Preferences::from_iter(std::iter::empty(), None),
+            &HashStrategy::None,
+            &python_requirement,
+            None,
+            &[],
+            Editables::default(),
+            reporter,
+        )
+    }
+
+    /// Create the PubGrub package for the given name and, optionally, extra.
+    fn pubgrub_package(name: &str, extra: Option<&str>) -> PubGrubPackage {
+        PubGrubPackage::Package(
+            PackageName::from_str(name).unwrap(),
+            extra.map(|extra| ExtraName::from_str(extra).unwrap()),
+            None,
+        )
+    }
+
+    /// Editables should be sorted identically regardless of the path separators used to specify
+    /// them.
+    #[test]
+    fn editable_key_separators() {
+        let name = PackageName::from_str("project").unwrap();
+        let editables = [
+            editable("..\\b\\project"),
+            editable("./c"),
+            editable("../a/project"),
+            editable(".\\b"),
+        ];
+
+        let keys = editables
+            .iter()
+            .map(|editable| Node::Editable(&name, editable).key())
+            .map(|key| match key {
+                NodeKey::Editable(verbatim) => verbatim.into_owned(),
+                NodeKey::Distribution(name) => name.to_string(),
+            })
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        insta::assert_snapshot!(keys, @r###"
+        ../a/project
+        ../b/project
+        ./b
+        ./c
+        "###);
+    }
+
+    /// The `# via` annotations should list the external sources first, followed by the
+    /// dependents in the graph, regardless of the order in which the edges were added.
+    #[test]
+    fn split_annotation_order() {
+        let resolution = graph(
+            &[("b", "1.0.0"), ("c", "1.0.0"), ("a", "1.0.0")],
+            &[("b", "c"), ("a", "c")],
+        );
+
+        let mut sources = SourceAnnotations::default();
+        sources.add(
+            &PackageName::from_str("c").unwrap(),
+            SourceAnnotation::Requirement(RequirementOrigin::File(PathBuf::from(
+                "requirements.in",
+            ))),
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::Split,
+            sources,
+        );
+        let output = anstream::adapter::strip_str(&display.to_string()).to_string();
+
+        insta::assert_snapshot!(output, @r###"
+        a==1.0.0
+        b==1.0.0
+        c==1.0.0
+            # via
+            #   -r requirements.in
+            #   a
+            #   b
+        "###);
+    }
+
+    /// With a topological sort order, dependencies should precede their dependents, with ties
+    /// broken alphabetically.
+    #[test]
+    fn topological_sort_order() {
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c")],
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            false,
+            false,
+            false,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        )
+        .with_sort_order(SortOrder::Topological);
+        let output = anstream::adapter::strip_str(&display.to_string()).to_string();
+
+        insta::assert_snapshot!(output, @r###"
+        c==1.0.0
+        d==1.0.0
+        b==1.0.0
+        a==1.0.0
+        "###);
+    }
+
+    /// The JSON output should include every package with its dependency edges, along with a
+    /// top-level list of diagnostics that's present even if it's empty.
+    #[test]
+    fn to_json() {
+        let resolution = graph(
+            &[("a", "1.0.0"), ("b", "2.0.0"), ("c", "3.0.0")],
+            &[("a", "b"), ("a", "c"), ("b", "c")],
+        );
+
+        let json = serde_json::to_string_pretty(&resolution.to_json()).unwrap();
+
+        insta::assert_snapshot!(json, @r###"
+        {
+          "packages": [
+            {
+              "name": "a",
+              "version": "1.0.0",
+              "dependencies": [
+                {
+                  "name": "b",
+                  "range": "*"
+                },
+                {
+                  "name": "c",
+                  "range": "*"
+                }
+              ]
+            },
+            {
+              "name": "b",
+              "version": "2.0.0",
+              "dependencies": [
+                {
+                  "name": "c",
+                  "range": "*"
+                }
+              ]
+            },
+            {
+              "name": "c",
+              "version": "3.0.0"
+            }
+          ],
+          "diagnostics": []
+        }
+        "###);
+
+        let mut resolution = graph(&[("a", "1.0.0")], &[]);
+        resolution.diagnostics.push(Diagnostic::MissingLowerBound {
+            name: PackageName::from_str("a").unwrap(),
+        });
+        assert_eq!(
+            resolution.to_json()["diagnostics"],
+            serde_json::json!([
+                "The requirement `a` has no lower bound, so its resolution may change as new versions are published."
+            ])
+        );
+    }
+
+    /// Direct dependents should be reported by name, with unknown packages yielding nothing.
+    #[test]
+    fn dependents() {
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("b", "d"), ("a", "d"), ("a", "c"), ("d", "c")],
+        );
+
+        let dependents = |name: &str| {
+            resolution
+                .dependents(&PackageName::from_str(name).unwrap())
+                .map(|dist| dist.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(dependents("d"), vec!["a", "b"]);
+        assert_eq!(dependents("c"), vec!["a", "d"]);
+        assert!(dependents("a").is_empty());
+        assert!(dependents("e").is_empty());
+    }
+
+    /// Packages that nothing depends on should be reported as leaves, unless they were requested
+    /// directly.
+    #[test]
+    fn leaf_packages() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "c"), ("b", "c")],
+        );
+        resolution
+            .requested
+            .insert(PackageName::from_str("a").unwrap());
+
+        let mut leaves = resolution
+            .leaf_packages()
+            .map(|dist| dist.name().to_string())
+            .collect::<Vec<_>>();
+        leaves.sort_unstable();
+
+        assert_eq!(leaves, vec!["b", "d"]);
+    }
+
+    /// When enabled, each conditional package should be emitted with the marker under which it
+    /// was selected.
+    #[test]
+    fn include_markers() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        resolution.markers.insert(
+            PackageName::from_str("b").unwrap(),
+            MarkerTree::from_str("sys_platform == 'win32'").unwrap(),
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            false,
+            true,
+            false,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        );
+
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0
+        b==1.0.0 ; sys_platform == 'win32'
+        "###);
+    }
+
+    /// The install order should place dependencies first, breaking ties alphabetically, and should
+    /// reject cycles.
+    #[test]
+    fn install_order() {
+        let resolution = graph(
+            &[
+                ("d", "1.0.0"),
+                ("a", "1.0.0"),
+                ("c", "1.0.0"),
+                ("b", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c"), ("d", "c")],
+        );
+        let order = resolution
+            .install_order()
+            .unwrap()
+            .into_iter()
+            .map(|dist| dist.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec!["c", "b", "d", "a"]);
+
+        let resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b"), ("b", "a")]);
+        assert!(resolution.install_order().is_err());
+    }
+
+    /// The subgraph should include the given roots along with their transitive dependencies (and
+    /// their hashes and diagnostics), and should reject unknown roots.
+    #[test]
+    fn subgraph() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c"), ("d", "c")],
+        );
+        for name in ["a", "c"] {
+            resolution.hashes.insert(
+                PackageName::from_str(name).unwrap(),
+                vec![HashDigest {
+                    algorithm: HashAlgorithm::Sha256,
+                    digest: "1234".into(),
+                }],
+            );
+            resolution.diagnostics.push(Diagnostic::MissingLowerBound {
+                name: PackageName::from_str(name).unwrap(),
+            });
+        }
+
+        let subgraph = resolution
+            .subgraph(&[PackageName::from_str("b").unwrap()])
+            .unwrap();
+        insta::assert_snapshot!(DisplayResolutionGraph::from(&subgraph).to_string(), @r###"
+        b==1.0.0
+        c==1.0.0
+            # via b
+        "###);
+        assert_eq!(
+            subgraph.hashes.keys().collect::<Vec<_>>(),
+            vec![&PackageName::from_str("c").unwrap()]
+        );
+        assert!(matches!(
+            subgraph.diagnostics(),
+            [Diagnostic::MissingLowerBound { name }] if name.as_ref() == "c"
+        ));
+
+        assert!(resolution
+            .subgraph(&[PackageName::from_str("e").unwrap()])
+            .is_err());
+    }
+
+    /// With hashes enabled, a hash mismatch should be flagged alongside the requirement.
+    #[test]
+    fn invalid_hash_annotation() {
+        let mut resolution = graph(&[("a", "1.0.0")], &[]);
+        let digest = |digest: &str| HashDigest {
+            algorithm: HashAlgorithm::Sha256,
+            digest: digest.into(),
+        };
+        resolution
+            .hashes
+            .insert(PackageName::from_str("a").unwrap(), vec![digest("1234")]);
+        resolution.diagnostics.push(Diagnostic::InvalidHash {
+            dist: registry_dist("a", "1.0.0"),
+            expected: digest("1234"),
+            actual: digest("5678"),
+        });
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            true,
+            false,
+            false,
+            false,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        );
+
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0 \
+            --hash=sha256:1234
+            # warning: expected sha256:1234, but found sha256:5678
+        "###);
+    }
+
+    /// Hashes should be restricted to the requested algorithm, falling back to every hash (with a
+    /// warning) for packages that lack a hash in that algorithm.
+    #[test]
+    fn hash_algorithm() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[]);
+        let digest = |algorithm: HashAlgorithm, digest: &str| HashDigest {
+            algorithm,
+            digest: digest.into(),
+        };
+        resolution.hashes.insert(
+            PackageName::from_str("a").unwrap(),
+            vec![
+                digest(HashAlgorithm::Md5, "1234"),
+                digest(HashAlgorithm::Sha256, "5678"),
+            ],
+        );
+        resolution.hashes.insert(
+            PackageName::from_str("b").unwrap(),
+            vec![
+                digest(HashAlgorithm::Md5, "1234"),
+                digest(HashAlgorithm::Sha512, "5678"),
+            ],
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            true,
+            false,
+            false,
+            false,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        )
+        .with_hash_algorithm(HashAlgorithm::Sha256);
+
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0 \
+            --hash=sha256:5678
+        b==1.0.0 \
+            --hash=md5:1234 \
+            --hash=sha512:5678
+            # warning: no sha256 hash found
+        "###);
+    }
+
+    /// Packages without a hash should be flagged when hashes are displayed.
+    #[test]
+    fn missing_hash_annotation() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[]);
+        resolution.hashes.insert(
+            PackageName::from_str("a").unwrap(),
+            vec![HashDigest {
+                algorithm: HashAlgorithm::Sha256,
+                digest: "1234".into(),
+            }],
+        );
+        resolution.diagnostics.push(Diagnostic::MissingHash {
+            dist: registry_dist("b", "1.0.0"),
+        });
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            true,
+            false,
+            false,
+            false,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        );
+
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0 \
+            --hash=sha256:1234
+        b==1.0.0
+            # warning: no hash found
+        "###);
+        assert_eq!(
+            resolution.diagnostics[0].message(),
+            "No hash found for package `b==1.0.0`; use `--hash` to specify one or regenerate the lock file."
+        );
+    }
+
+    /// Local archives should be re-hashed and compared against the recorded hashes.
+    #[tokio::test]
+    async fn check_hashes() {
+        let path_dist = |filename: &str| -> ResolvedDist {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../../scripts/links")
+                .join(filename);
+            Dist::Built(BuiltDist::Path(PathBuiltDist {
+                filename: WheelFilename::from_str(filename).unwrap(),
+                url: VerbatimUrl::from_url(Url::from_file_path(&path).unwrap()),
+                path,
+            }))
+            .into()
+        };
+        let digest = |digest: &str| HashDigest {
+            algorithm: HashAlgorithm::Sha256,
+            digest: digest.into(),
+        };
+
+        let mut resolution = graph(&[("a", "1.0.0")], &[]);
+        resolution
+            .petgraph
+            .add_node(path_dist("validation-3.0.0-py3-none-any.whl"));
+        resolution
+            .petgraph
+            .add_node(path_dist("maturin-1.4.0-py3-none-any.whl"));
+        resolution.hashes.insert(
+            PackageName::from_str("validation").unwrap(),
+            vec![
+                digest("1234"),
+                digest("62936ba8c6d5b0674c5a91cfdbacccfab5f3e59f67fd5cd70877952112d96ef4"),
+            ],
+        );
+        resolution.hashes.insert(
+            PackageName::from_str("maturin").unwrap(),
+            vec![digest("1234")],
+        );
+
+        let cache = Cache::temp().unwrap();
+
+        // Without a hash policy, packages without hashes are ignored.
+        let diagnostics = resolution
+            .check_hashes(&cache, &HashStrategy::None)
+            .await
+            .unwrap();
+        let [Diagnostic::InvalidHash {
+            dist,
+            expected,
+            actual,
+        }] = diagnostics.as_slice()
+        else {
+            panic!("expected a single hash mismatch, but found: {diagnostics:?}");
+        };
+        assert_eq!(dist.name().as_ref(), "maturin");
+        assert_eq!(expected.to_string(), "sha256:1234");
+        assert_eq!(
+            actual.to_string(),
+            "sha256:a5450c5be448066e98860a80b55892c765ae269953e9926d5b9735ea70015b63"
+        );
+
+        // When hashes are required, `a` should also be reported as missing a hash.
+        let diagnostics = resolution
+            .check_hashes(&cache, &HashStrategy::Validate(FxHashMap::default()))
+            .await
+            .unwrap();
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| match diagnostic {
+                    Diagnostic::MissingHash { dist } => format!("missing: {}", dist.name()),
+                    Diagnostic::InvalidHash { dist, .. } => format!("invalid: {}", dist.name()),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+            vec!["missing: a", "invalid: maturin"]
+        );
+    }
+
+    /// Every package involved in a cycle should be reported, while packages outside of it are
+    /// not.
+    #[test]
+    fn cyclic_dependency() {
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")],
+        );
+        let cycles = resolution.cycles();
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| PackageName::from_str(name).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(cycles, vec![names(&["a", "b", "c"])]);
+
+        let diagnostic = Diagnostic::CyclicDependency {
+            cycle: cycles[0].clone(),
+        };
+        assert_eq!(
+            diagnostic.message(),
+            "Detected a dependency cycle between: `a`, `b`, `c`."
+        );
+        assert!(diagnostic.includes(&PackageName::from_str("b").unwrap()));
+        assert!(!diagnostic.includes(&PackageName::from_str("d").unwrap()));
+
+        let acyclic = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        assert!(acyclic.cycles().is_empty());
+    }
+
+    /// Disjoint cycles should be reported separately, with the members of each cycle and the
+    /// cycles themselves sorted by name.
+    #[test]
+    fn cycles() {
+        let resolution = graph(
+            &[
+                ("z", "1.0.0"),
+                ("y", "1.0.0"),
+                ("c", "1.0.0"),
+                ("b", "1.0.0"),
+                ("a", "1.0.0"),
+            ],
+            &[("z", "y"), ("y", "z"), ("c", "b"), ("b", "c"), ("b", "a")],
+        );
+        let cycles = resolution
+            .cycles()
+            .into_iter()
+            .map(|cycle| cycle.iter().map(ToString::to_string).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(cycles, vec![vec!["b", "c"], vec!["y", "z"]]);
+    }
+
+    /// Only specifiers that exclude all versions below some bound should be considered bounded.
+    #[test]
+    fn lower_bound() {
+        for (specifier, expected) in [
+            ("", false),
+            ("<3", false),
+            ("!=2.0.0", false),
+            ("<=3,!=2.*", false),
+            (">=2", true),
+            (">2,<3", true),
+            ("==2.31.0", true),
+            ("==2.*", true),
+            ("~=2.31", true),
+            ("===2.31.0", true),
+        ] {
+            let specifier = VersionSpecifiers::from_str(specifier).unwrap();
+            assert_eq!(has_lower_bound(&specifier), expected, "{specifier}");
+        }
+
+        let diagnostic = Diagnostic::MissingLowerBound {
+            name: PackageName::from_str("requests").unwrap(),
+        };
+        assert_eq!(
+            diagnostic.message(),
+            "The requirement `requests` has no lower bound, so its resolution may change as new versions are published."
+        );
+        assert!(diagnostic.includes(&PackageName::from_str("requests").unwrap()));
+    }
+
+    /// The tree style should expand each package once, and mark repeated appearances (including
+    /// cycles back to a root) with `(*)`.
+    #[test]
+    fn tree_annotation_style() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("d", "a")],
+        );
+        resolution
+            .requested
+            .insert(PackageName::from_str("a").unwrap());
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::Tree,
+            SourceAnnotations::default(),
+        );
+
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0
+        ├── b==1.0.0 [required: any]
+        │   └── d==1.0.0 [required: any]
+        │       └── a==1.0.0 [required: any] (*)
+        └── c==1.0.0 [required: any]
+            └── d==1.0.0 [required: any] (*)
+        "###);
+    }
+
+    /// Create a marker environment for CPython 3.12 on the given platform.
+    fn marker_env(sys_platform: &str, python_version: &str) -> MarkerEnvironment {
+        MarkerEnvironment::try_from(MarkerEnvironmentBuilder {
+            implementation_name: "cpython",
+            implementation_version: python_version,
+            os_name: "posix",
+            platform_machine: "x86_64",
+            platform_python_implementation: "CPython",
+            platform_release: "",
+            platform_system: "",
+            platform_version: "",
+            python_full_version: python_version,
+            python_version,
+            sys_platform,
+        })
+        .unwrap()
+    }
+
+    /// Disjunctions that hold in the resolution environment should be preserved, such that the
+    /// resulting marker holds on every platform for which the resolution is valid.
+    #[test]
+    fn marker_tree_disjunction() {
+        let requirement = |requirement: &str| {
+            Requirement::from_pep508(pep508_rs::Requirement::from_str(requirement).unwrap())
+                .unwrap()
+        };
+        let manifest = Manifest::simple(vec![
+            requirement("a ; sys_platform == 'linux' or sys_platform == 'win32'"),
+            requirement("b ; python_version < '3.8'"),
+        ]);
+        let resolution = graph(&[], &[]);
+
+        let marker = resolution
+            .marker_tree(
+                &manifest,
+                &InMemoryIndex::default(),
+                &marker_env("linux", "3.12"),
+            )
+            .unwrap();
+        assert_eq!(
+            marker.to_string(),
+            "python_version == '3.12' and (sys_platform == 'linux' or sys_platform == 'win32')"
+        );
+
+        // The resolution is valid on both Linux and Windows...
+        assert!(marker.evaluate(&marker_env("linux", "3.12"), &[]));
+        assert!(marker.evaluate(&marker_env("win32", "3.12"), &[]));
+
+        // ...but not on macOS, or on versions for which `b` would be included.
+        assert!(!marker.evaluate(&marker_env("darwin", "3.12"), &[]));
+        assert!(!marker.evaluate(&marker_env("linux", "3.7"), &[]));
+    }
+
+    /// Entries whose requirement or hashes exceed the maximum line length should be reported to
+    /// the caller, rather than warned about while rendering.
+    #[test]
+    fn overlong_entries() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "2.0.0")], &[("a", "b")]);
+        resolution.hashes.insert(
+            PackageName::from_str("b").unwrap(),
+            vec![HashDigest {
+                algorithm: HashAlgorithm::Sha256,
+                digest: "0123456789abcdef0123456789abcdef".into(),
+            }],
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            true,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        );
+        assert!(display.overlong_entries().is_empty());
+
+        let display = display.with_max_line_length(40);
+        assert_eq!(
+            display.overlong_entries(),
+            vec![&PackageName::from_str("b").unwrap()]
+        );
+    }
+
+    /// The JSON style should emit one object per package, omitting any fields for disabled output
+    /// options.
+    #[test]
+    fn json_annotation_style() {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "2.0.0"), ("c", "3.0.0")],
+            &[("a", "b"), ("a", "c"), ("b", "c")],
+        );
+        resolution.hashes.insert(
+            PackageName::from_str("c").unwrap(),
+            vec![HashDigest {
+                algorithm: HashAlgorithm::Sha256,
+                digest: "1234".into(),
+            }],
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            true,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::Json,
+            SourceAnnotations::default(),
+        );
+        insta::assert_snapshot!(display.to_string(), @r###"
+        {"name":"a","version":"1.0.0","url":null,"hashes":[],"requires":["b","c"],"required_by":[]}
+        {"name":"b","version":"2.0.0","url":null,"hashes":[],"requires":["c"],"required_by":["a"]}
+        {"name":"c","version":"3.0.0","url":null,"hashes":["sha256:1234"],"requires":[],"required_by":["a","b"]}
+        "###);
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            true,
+            false,
+            false,
+            false,
+            AnnotationStyle::Json,
+            SourceAnnotations::default(),
+        );
+        insta::assert_snapshot!(display.to_string(), @r###"
+        {"name":"a","version":"1.0.0","url":null,"extras":[]}
+        {"name":"b","version":"2.0.0","url":null,"extras":[]}
+        {"name":"c","version":"3.0.0","url":null,"extras":[]}
+        "###);
+    }
+
+    /// Redundant terms should be removed from the marker tree, without changing its meaning.
+    #[test]
+    fn simplify_marker_conjuncts() {
+        let marker = |marker: &str| MarkerTree::from_str(marker).unwrap();
+        let conjuncts = vec![
+            marker("python_version == '3.11'"),
+            marker("python_full_version == '3.11.4'"),
+            marker("sys_platform == 'linux'"),
+            marker("sys_platform == 'linux'"),
+            marker("python_version >= '3.8' or sys_platform == 'linux'"),
+            marker("platform_machine != 'arm64' or sys_platform == 'win32'"),
+        ];
+        let simplified = MarkerTree::And(simplify_conjuncts(conjuncts));
+        assert_eq!(
+            simplified.to_string(),
+            "python_full_version == '3.11.4' and sys_platform == 'linux' and (platform_machine != 'arm64' or sys_platform == 'win32')"
+        );
+    }
+
+    /// Only packages whose markers hold in the given environment should be retained.
+    #[test]
+    fn marker_compatible() {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")],
+            &[("a", "b"), ("a", "c"), ("c", "b")],
+        );
+        resolution.markers.insert(
+            PackageName::from_str("c").unwrap(),
+            MarkerTree::from_str("sys_platform == 'win32'").unwrap(),
+        );
+
+        let linux = resolution.marker_compatible(&marker_env("linux", "3.12"));
+        assert_eq!(
+            linux
+                .petgraph()
+                .node_weights()
+                .map(|dist| dist.name().to_string())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(linux.petgraph().edge_count(), 1);
+
+        let windows = resolution.marker_compatible(&marker_env("win32", "3.12"));
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows.petgraph().edge_count(), 3);
     }
 
-    pub fn lock(&self) -> Result<Lock, LockError> {
-        let mut locked_dists = vec![];
-        for node_index in self.petgraph.node_indices() {
-            let dist = &self.petgraph[node_index];
-            let mut locked_dist = lock::Distribution::from_resolved_dist(dist)?;
-            for edge in self.petgraph.neighbors(node_index) {
-                let dependency_dist = &self.petgraph[edge];
-                locked_dist.add_dependency(dependency_dist);
-            }
-            locked_dists.push(locked_dist);
-        }
-        let lock = Lock::new(locked_dists)?;
-        Ok(lock)
+    /// Packages should be partitioned into the `default` and `develop` sections, with exact
+    /// versions, SHA-256 hashes, and references to the index from which they were resolved.
+    #[test]
+    fn to_pipfile_lock() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "2.0.0")], &[("a", "b")]);
+        resolution.hashes.insert(
+            PackageName::from_str("a").unwrap(),
+            vec![
+                HashDigest {
+                    algorithm: HashAlgorithm::Sha256,
+                    digest: "deadbeef".into(),
+                },
+                HashDigest {
+                    algorithm: HashAlgorithm::Md5,
+                    digest: "cafebabe".into(),
+                },
+            ],
+        );
+        resolution.markers.insert(
+            PackageName::from_str("b").unwrap(),
+            MarkerTree::from_str("sys_platform == 'win32'").unwrap(),
+        );
+
+        let sources = [IndexUrl::Pypi(VerbatimUrl::from_url(
+            Url::parse("https://pypi.org/simple").unwrap(),
+        ))];
+        let develop = FxHashSet::from_iter([PackageName::from_str("b").unwrap()]);
+        assert_eq!(
+            resolution.to_pipfile_lock(&sources, &develop),
+            serde_json::json!({
+                "_meta": {
+                    "pipfile-spec": 6,
+                    "requires": {},
+                    "sources": [
+                        {"name": "pypi", "url": "https://pypi.org/simple", "verify_ssl": true},
+                    ],
+                },
+                "default": {
+                    "a": {"hashes": ["sha256:deadbeef"], "index": "pypi", "version": "==1.0.0"},
+                },
+                "develop": {
+                    "b": {"index": "pypi", "markers": "sys_platform == 'win32'", "version": "==2.0.0"},
+                },
+            })
+        );
     }
-}
 
-/// A [`std::fmt::Display`] implementation for the resolution graph.
-#[derive(Debug)]
-#[allow(clippy::struct_excessive_bools)]
-pub struct DisplayResolutionGraph<'a> {
-    /// The underlying graph.
-    resolution: &'a ResolutionGraph,
-    /// The packages to exclude from the output.
-    no_emit_packages: &'a [PackageName],
-    /// Whether to include hashes in the output.
-    show_hashes: bool,
-    /// Whether to include extras in the output (e.g., `black[colorama]`).
-    include_extras: bool,
-    /// Whether to include annotations in the output, to indicate which dependency or dependencies
-    /// requested each package.
-    include_annotations: bool,
-    /// Whether to include indexes in the output, to indicate which index was used for each package.
-    include_index_annotation: bool,
-    /// The style of annotation comments, used to indicate the dependencies that requested each
-    /// package.
-    annotation_style: AnnotationStyle,
-    /// External sources for each package: requirements, constraints, and overrides.
-    sources: SourceAnnotations,
-}
+    /// The packages requiring a given package should be returned with the range on each edge.
+    #[test]
+    fn packages_requiring() {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")],
+            &[("a", "c"), ("b", "c")],
+        );
+        let edge = resolution.petgraph.edge_indices().next().unwrap();
+        resolution.petgraph[edge] = Range::higher_than(Version::new([1]));
 
-impl<'a> From<&'a ResolutionGraph> for DisplayResolutionGraph<'a> {
-    fn from(resolution: &'a ResolutionGraph) -> Self {
-        Self::new(
-            resolution,
+        let mut requiring = resolution
+            .packages_requiring(&PackageName::from_str("c").unwrap())
+            .map(|(dist, range)| (dist.name().to_string(), range.clone()))
+            .collect::<Vec<_>>();
+        requiring.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            requiring,
+            vec![
+                ("a".to_string(), Range::higher_than(Version::new([1]))),
+                ("b".to_string(), Range::full()),
+            ]
+        );
+
+        assert_eq!(
+            resolution
+                .packages_requiring(&PackageName::from_str("a").unwrap())
+                .count(),
+            0
+        );
+        assert_eq!(
+            resolution
+                .packages_requiring(&PackageName::from_str("d").unwrap())
+                .count(),
+            0
+        );
+    }
+
+    /// Distributions should be yielded after their dependencies, with ties (and cycles) broken
+    /// alphabetically.
+    #[test]
+    fn into_distributions_topo() {
+        let names = |resolution: ResolutionGraph| {
+            resolution
+                .into_distributions_topo()
+                .iter()
+                .map(|dist| dist.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c")],
+        );
+        assert_eq!(names(resolution), vec!["c", "d", "b", "a"]);
+
+        let resolution = graph(
+            &[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")],
+            &[("b", "a"), ("a", "b"), ("b", "c")],
+        );
+        assert_eq!(names(resolution), vec!["c", "a", "b"]);
+    }
+
+    /// Merged resolutions should share packages pinned to the same version, and reject packages
+    /// pinned to different versions or that are only editable in the right-hand resolution.
+    #[test]
+    fn merge() {
+        let left = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        let right = graph(&[("b", "1.0.0"), ("c", "1.0.0")], &[("c", "b")]);
+        let merged = ResolutionGraph::merge(left, right).unwrap();
+        insta::assert_snapshot!(DisplayResolutionGraph::from(&merged).to_string(), @r###"
+        a==1.0.0
+        b==1.0.0
+            # via
+            #   a
+            #   c
+        c==1.0.0
+        "###);
+
+        let left = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        let right = graph(&[("b", "2.0.0")], &[]);
+        let err = ResolutionGraph::merge(left, right).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Packages are pinned differently in the merged resolutions: `b` (`==1.0.0` vs. `==2.0.0`)"
+        );
+
+        let left = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        let mut right = graph(&[("b", "1.0.0")], &[]);
+        let name = PackageName::from_str("b").unwrap();
+        *right.petgraph.node_weights_mut().next().unwrap() =
+            Dist::from_editable(name.clone(), editable("../b"))
+                .unwrap()
+                .into();
+        right.editables = Editables::from_requirements(vec![(
+            editable("../b"),
+            Metadata23 {
+                name,
+                version: Version::from_str("1.0.0").unwrap(),
+                requires_dist: vec![],
+                requires_python: None,
+                provides_extras: vec![],
+            },
+            Requirements {
+                dependencies: vec![],
+                optional_dependencies: IndexMap::default(),
+            },
+        )]);
+        let err = ResolutionGraph::merge(left, right).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Packages are pinned differently in the merged resolutions: `b` (`==1.0.0` vs. `-e file:///home/ferris/project`)"
+        );
+    }
+
+    /// The DOT output should label each node with its pin and each edge with its range, unless
+    /// edge labels are disabled.
+    #[test]
+    fn to_dot() {
+        let resolution = graph(&[("a", "1.0.0"), ("b", "2.0.0")], &[("a", "b")]);
+        let range = format!("{:?}", Range::<Version>::full().to_string());
+        assert_eq!(
+            resolution.to_dot(),
+            format!(
+                "digraph {{\n    0 [ label=\"a==1.0.0\"]\n    1 [ label=\"b==2.0.0\"]\n    0 -> 1 [ label={range}]\n}}\n"
+            )
+        );
+
+        let options = DotOptions {
+            edge_labels: false,
+            ..DotOptions::default()
+        };
+        assert_eq!(
+            resolution.to_dot_with_options(&options),
+            "digraph {\n    0 [ label=\"a==1.0.0\"]\n    1 [ label=\"b==2.0.0\"]\n    0 -> 1 [ ]\n}\n"
+        );
+    }
+
+    /// The detailed DOT output should append the marker of each conditional dependency to its
+    /// edge label, such that a package reached via both a conditional and an unconditional edge
+    /// is only labeled as conditional on the former.
+    #[test]
+    fn to_dot_detailed() {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "2.0.0"), ("c", "3.0.0")],
+            &[("a", "b"), ("a", "c"), ("c", "b")],
+        );
+        // The first edge is the one from `a` to `b`.
+        let edge = resolution.petgraph.edge_indices().next().unwrap();
+        resolution.edge_markers.insert(
+            edge,
+            MarkerTree::from_str("sys_platform == 'win32'").unwrap(),
+        );
+        let range = Range::<Version>::full().to_string();
+        let conditional = format!("{range}; sys_platform == 'win32'");
+        let options = DotOptions {
+            editable_shape: Some("box".to_string()),
+            edge_markers: true,
+            ..DotOptions::default()
+        };
+        assert_eq!(
+            resolution.to_dot_with_options(&options),
+            format!(
+                "digraph {{\n    0 [ label=\"a==1.0.0\"]\n    1 [ label=\"b==2.0.0\"]\n    2 [ label=\"c==3.0.0\"]\n    0 -> 1 [ label={conditional:?}]\n    0 -> 2 [ label={range:?}]\n    2 -> 1 [ label={range:?}]\n}}\n"
+            )
+        );
+    }
+
+    /// The Mermaid output should be sorted by name, and should respect the depth limit.
+    #[test]
+    fn to_mermaid() {
+        let resolution = graph(
+            &[("c-d", "3.0.0"), ("b", "2.0.0"), ("a", "1.0.0")],
+            &[("b", "c-d"), ("a", "b")],
+        );
+        let range = Range::<Version>::full().to_string();
+        assert_eq!(
+            resolution.to_mermaid(None),
+            format!(
+                "flowchart TD\n    a[\"a==1.0.0\"]\n    b[\"b==2.0.0\"]\n    c_d[\"c-d==3.0.0\"]\n    a -->|\"{range}\"| b\n    b -->|\"{range}\"| c_d\n"
+            )
+        );
+        assert_eq!(
+            resolution.to_mermaid(Some(1)),
+            format!(
+                "flowchart TD\n    a[\"a==1.0.0\"]\n    b[\"b==2.0.0\"]\n    a -->|\"{range}\"| b\n"
+            )
+        );
+    }
+
+    /// Disabling color should omit any ANSI escape codes from the annotations.
+    #[test]
+    fn without_color() {
+        let resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+
+        let display = DisplayResolutionGraph::from(&resolution);
+        assert!(display.to_string().contains('\x1b'));
+
+        let display = DisplayResolutionGraph::from(&resolution).with_color(false);
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0
+        b==1.0.0
+            # via a
+        "###);
+    }
+
+    /// Grouping by root should emit each top-level requirement with its exclusive dependencies,
+    /// followed by any shared dependencies.
+    #[test]
+    fn group_by_root() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[("a", "c"), ("b", "d"), ("a", "e"), ("b", "e")],
+        );
+        resolution.requested = FxHashSet::from_iter([
+            PackageName::from_str("a").unwrap(),
+            PackageName::from_str("b").unwrap(),
+        ]);
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
             &[],
             false,
             false,
+            false,
+            false,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        )
+        .with_group_by_root(true);
+        insta::assert_snapshot!(display.to_string(), @r###"
+        # --- via a ---
+        a==1.0.0
+        c==1.0.0
+        # --- via b ---
+        b==1.0.0
+        d==1.0.0
+        # --- shared ---
+        e==1.0.0
+        "###);
+    }
+
+    /// The critical path should be the longest chain in the graph, with ties broken by name.
+    #[test]
+    fn critical_path() {
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[("a", "d"), ("b", "c"), ("c", "d"), ("d", "e"), ("a", "e")],
+        );
+        let path = resolution
+            .critical_path()
+            .into_iter()
+            .map(|dist| dist.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(path, ["b", "c", "d", "e"]);
+
+        let resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b"), ("b", "a")]);
+        assert!(resolution.critical_path().is_empty());
+
+        assert!(graph(&[], &[]).critical_path().is_empty());
+    }
+
+    /// Unused extras should be reported, and annotated in the output when extras are shown.
+    #[test]
+    fn unused_extras() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        resolution.extras.insert(
+            PackageName::from_str("a").unwrap(),
+            vec![ExtraName::from_str("bar").unwrap()],
+        );
+        resolution.diagnostics.push(Diagnostic::UnusedExtra {
+            dist: registry_dist("a", "1.0.0"),
+            extra: ExtraName::from_str("bar").unwrap(),
+        });
+
+        assert_eq!(
+            resolution.unused_extras(),
+            [(
+                PackageName::from_str("a").unwrap(),
+                ExtraName::from_str("bar").unwrap()
+            )]
+        );
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
             true,
             false,
-            AnnotationStyle::default(),
+            false,
+            false,
+            AnnotationStyle::Split,
             SourceAnnotations::default(),
         )
+        .with_color(false);
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a[bar]==1.0.0
+            # warning: extra `bar` added no dependencies
+        b==1.0.0
+        "###);
     }
-}
 
-impl<'a> DisplayResolutionGraph<'a> {
-    /// Create a new [`DisplayResolutionGraph`] for the given graph.
-    #[allow(clippy::fn_params_excessive_bools, clippy::too_many_arguments)]
-    pub fn new(
-        underlying: &'a ResolutionGraph,
-        no_emit_packages: &'a [PackageName],
-        show_hashes: bool,
-        include_extras: bool,
-        include_annotations: bool,
-        include_index_annotation: bool,
-        annotation_style: AnnotationStyle,
-        sources: SourceAnnotations,
-    ) -> DisplayResolutionGraph<'a> {
-        Self {
-            resolution: underlying,
-            no_emit_packages,
-            show_hashes,
-            include_extras,
-            include_annotations,
-            include_index_annotation,
-            annotation_style,
-            sources,
+    /// The serialized lock should be independent of the order of the nodes in the graph, and
+    /// should round-trip.
+    #[test]
+    fn to_lock_toml() {
+        let forward = graph(&[("a", "1.0.0"), ("b", "2.0.0")], &[("a", "b")]);
+        let reverse = graph(&[("b", "2.0.0"), ("a", "1.0.0")], &[("a", "b")]);
+
+        let encoded = forward.to_lock_toml().unwrap();
+        assert_eq!(encoded, reverse.to_lock_toml().unwrap());
+        assert!(encoded.find("name = \"a\"").unwrap() < encoded.find("name = \"b\"").unwrap());
+
+        let lock: Lock = toml::from_str(&encoded).unwrap();
+        assert_eq!(lock.to_toml().unwrap(), encoded);
+    }
+
+    /// Validation should flag any entries for packages that aren't in the graph.
+    #[test]
+    fn validate() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        assert_eq!(resolution.validate(), Ok(()));
+
+        resolution
+            .hashes
+            .insert(PackageName::from_str("c").unwrap(), vec![]);
+        resolution.extras.insert(
+            PackageName::from_str("d").unwrap(),
+            vec![ExtraName::from_str("bar").unwrap()],
+        );
+        resolution.petgraph.add_node(registry_dist("a", "2.0.0"));
+        assert_eq!(
+            resolution.validate(),
+            Err(vec![
+                ValidationError::DuplicateNode(PackageName::from_str("a").unwrap()),
+                ValidationError::MissingEdgeTarget {
+                    dependent: 0,
+                    dependency: 1,
+                },
+                ValidationError::OrphanedHashEntry(PackageName::from_str("c").unwrap()),
+                ValidationError::OrphanedExtraEntry(PackageName::from_str("d").unwrap()),
+            ])
+        );
+    }
+
+    /// An edge into a node that isn't the one recorded for its package should be reported, even
+    /// though the node itself exists.
+    #[test]
+    fn validate_missing_edge_target() {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")],
+            &[("a", "b"), ("b", "c")],
+        );
+        assert_eq!(resolution.validate(), Ok(()));
+
+        // Shadow `c` with a second node, such that the `b -> c` edge points to a node that's no
+        // longer reachable by name.
+        resolution.petgraph.add_node(registry_dist("c", "2.0.0"));
+        assert_eq!(
+            resolution.validate(),
+            Err(vec![
+                ValidationError::DuplicateNode(PackageName::from_str("c").unwrap()),
+                ValidationError::MissingEdgeTarget {
+                    dependent: 1,
+                    dependency: 2,
+                },
+            ])
+        );
+    }
+
+    /// Building a graph from a selection that's missing a pin, or an extra whose metadata is
+    /// missing from the index, should fail with an error identifying the package, rather than
+    /// panicking.
+    #[test]
+    fn missing_metadata() {
+        let index = InMemoryIndex::default();
+
+        let selection = [(pubgrub_package("a", None), "1.0.0")];
+        let err = from_state(&selection, vec![], &index, "3.12", None).unwrap_err();
+        assert!(matches!(err, ResolveError::MissingPin(..)));
+        assert_eq!(err.to_string(), "Missing pin for `a==1.0.0`");
+
+        let selection = [(pubgrub_package("a", Some("dev")), "1.0.0")];
+        let err = from_state(
+            &selection,
+            vec![registry_dist("a", "1.0.0")],
+            &index,
+            "3.12",
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ResolveError::MissingMetadata(..)));
+        assert_eq!(err.to_string(), "Missing metadata for `a-1.0.0`");
+    }
+
+    /// Narrowing a graph against an index that's missing a package's metadata should fail with
+    /// an error identifying the package, rather than panicking.
+    #[test]
+    fn for_environment_missing_metadata() {
+        let requirement =
+            Requirement::from_pep508(pep508_rs::Requirement::from_str("a").unwrap()).unwrap();
+        let manifest = Manifest::simple(vec![requirement]);
+        let resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+
+        let err = resolution
+            .for_environment(
+                &manifest,
+                &InMemoryIndex::default(),
+                &marker_env("linux", "3.12"),
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Missing metadata for `a-1.0.0`");
+    }
+
+    /// Direct URL source distributions should read their version from the index, and report an
+    /// error (rather than panicking) if it's missing.
+    #[test]
+    fn wheelhouse_manifest_direct_url() {
+        let name = PackageName::from_str("a").unwrap();
+        let url = VerbatimUrl::from_url(Url::parse("https://example.com/a-1.0.0.tar.gz").unwrap());
+        let dist = Dist::from_url(name.clone(), url).unwrap();
+        let mut resolution = graph(&[("a", "1.0.0")], &[]);
+        *resolution.petgraph.node_weights_mut().next().unwrap() =
+            ResolvedDist::Installable(dist.clone());
+
+        let index = InMemoryIndex::default();
+        let err = resolution.to_wheelhouse_manifest(&index).unwrap_err();
+        assert!(matches!(err, WheelhouseError::MissingMetadata(_)));
+
+        index.insert_metadata(
+            dist.version_id(),
+            MetadataResponse::Found(ArchiveMetadata::from(Metadata23 {
+                name,
+                version: Version::from_str("1.0.0").unwrap(),
+                requires_dist: vec![],
+                requires_python: None,
+                provides_extras: vec![],
+            })),
+        );
+        let entries = resolution.to_wheelhouse_manifest(&index).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].version.to_string(), "1.0.0");
+        assert_eq!(entries[0].filename, "a-1.0.0.tar.gz");
+    }
+
+    /// The effective `Requires-Python` should be the tightest lower bound, and conflicting bounds
+    /// should be reported.
+    #[test]
+    fn requires_python() {
+        let index = InMemoryIndex::default();
+        let insert = |name: &str, requires_python: Option<&str>| {
+            let name = PackageName::from_str(name).unwrap();
+            index.insert_metadata(
+                VersionId::from_registry(name.clone(), Version::new([1])),
+                MetadataResponse::Found(ArchiveMetadata::from(Metadata23 {
+                    name,
+                    version: Version::new([1]),
+                    requires_dist: vec![],
+                    requires_python: requires_python.map(|requires_python| {
+                        VersionSpecifiers::from_str(requires_python).unwrap()
+                    }),
+                    provides_extras: vec![],
+                })),
+            );
+        };
+        insert("a", Some(">=3.8, <4"));
+        insert("b", Some(">=3.10"));
+        insert("c", None);
+
+        let resolution = graph(&[("a", "1"), ("b", "1"), ("c", "1")], &[("a", "b")]);
+        assert_eq!(
+            resolution.requires_python(&index).unwrap(),
+            Some(VersionSpecifiers::from_str(">=3.10").unwrap())
+        );
+
+        insert("d", Some("<3.9"));
+        let resolution = graph(&[("b", "1"), ("d", "1")], &[]);
+        let diagnostic = resolution.requires_python(&index).unwrap_err();
+        assert_eq!(
+            diagnostic.message(),
+            "The package `b==1` requires Python >=3.10, but `d==1` requires Python <3.9."
+        );
+
+        assert_eq!(
+            graph(&[("c", "1")], &[]).requires_python(&index).unwrap(),
+            None
+        );
+    }
+
+    /// Build a resolution in which `b` is only required on Windows, `d` is only required on old
+    /// Python versions, and `c` is required both unconditionally (via `a`) and on Windows (via
+    /// `b`).
+    fn conditional_resolution() -> (ResolutionGraph, Manifest, InMemoryIndex) {
+        let index = InMemoryIndex::default();
+        let insert = |name: &str, requires_dist: &[&str]| {
+            let name = PackageName::from_str(name).unwrap();
+            index.insert_metadata(
+                VersionId::from_registry(name.clone(), Version::new([1])),
+                MetadataResponse::Found(ArchiveMetadata::from(Metadata23 {
+                    name,
+                    version: Version::new([1]),
+                    requires_dist: requires_dist
+                        .iter()
+                        .map(|requirement| pep508_rs::Requirement::from_str(requirement).unwrap())
+                        .collect(),
+                    requires_python: None,
+                    provides_extras: vec![],
+                })),
+            );
+        };
+        insert("a", &["c", "d ; python_version < '3.8'"]);
+        insert("b", &["c"]);
+        insert("c", &[]);
+        insert("d", &[]);
+
+        let requirement = |requirement: &str| {
+            Requirement::from_pep508(pep508_rs::Requirement::from_str(requirement).unwrap())
+                .unwrap()
+        };
+        let manifest = Manifest::simple(vec![
+            requirement("a"),
+            requirement("b ; sys_platform == 'win32'"),
+        ]);
+        let resolution = graph(
+            &[("a", "1"), ("b", "1"), ("c", "1"), ("d", "1")],
+            &[("a", "c"), ("a", "d"), ("b", "c")],
+        );
+        (resolution, manifest, index)
+    }
+
+    /// Packages that are only reachable via marked edges should carry the conjunction of those
+    /// markers, while packages that are reachable unconditionally should carry none.
+    #[test]
+    fn conditional_packages() {
+        let (resolution, manifest, index) = conditional_resolution();
+        let mut markers = resolution
+            .conditional_packages(&manifest, &index)
+            .unwrap()
+            .into_iter()
+            .map(|(name, marker)| (name.to_string(), marker.map(|marker| marker.to_string())))
+            .collect::<Vec<_>>();
+        markers.sort();
+        assert_eq!(
+            markers,
+            vec![
+                ("a".to_string(), None),
+                ("b".to_string(), Some("sys_platform == 'win32'".to_string())),
+                ("c".to_string(), None),
+                ("d".to_string(), Some("python_version < '3.8'".to_string())),
+            ]
+        );
+    }
+
+    /// Conditional packages should be emitted with their markers, and unconditional packages
+    /// without.
+    #[test]
+    fn to_requirements_with_markers() {
+        let (resolution, manifest, index) = conditional_resolution();
+        assert_eq!(
+            resolution
+                .to_requirements_with_markers(&manifest, &index)
+                .unwrap(),
+            vec![
+                "a==1",
+                "b==1 ; sys_platform == 'win32'",
+                "c==1",
+                "d==1 ; python_version < '3.8'",
+            ]
+        );
+    }
+
+    /// Names that map to multiple nodes should be reported, along with each of their
+    /// distributions.
+    #[test]
+    fn duplicate_names() {
+        let mut resolution = graph(&[("a", "1.0.0"), ("b", "1.0.0")], &[("a", "b")]);
+        assert!(resolution.duplicate_names().is_empty());
+
+        resolution.petgraph.add_node(registry_dist("b", "2.0.0"));
+        let duplicates = resolution
+            .duplicate_names()
+            .into_iter()
+            .map(|(name, dists)| {
+                (
+                    name.to_string(),
+                    dists.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            duplicates,
+            [(
+                "b".to_string(),
+                vec!["b==1.0.0".to_string(), "b==2.0.0".to_string()]
+            )]
+        );
+        assert_eq!(
+            resolution.validate(),
+            Err(vec![ValidationError::DuplicateNode(
+                PackageName::from_str("b").unwrap()
+            )])
+        );
+    }
+
+    /// A wheel whose tags are all unsupported by the target should be flagged, along with its
+    /// expanded tags.
+    #[test]
+    fn platform_incompatible() {
+        let tags = Tags::new(vec![
+            (
+                "cp312".to_string(),
+                "cp312".to_string(),
+                "manylinux_2_17_x86_64".to_string(),
+            ),
+            ("py3".to_string(), "none".to_string(), "any".to_string()),
+        ]);
+
+        let compatible = WheelFilename::from_str("pywin32-306-py3-none-any.whl").unwrap();
+        assert_eq!(incompatible_wheel_tags(&compatible, &tags), None);
+
+        let incompatible =
+            WheelFilename::from_str("pywin32-306-cp312-cp312-win32.win_amd64.whl").unwrap();
+        let dist_tags = incompatible_wheel_tags(&incompatible, &tags).unwrap();
+        assert_eq!(
+            dist_tags,
+            vec![
+                Tag {
+                    python: "cp312".to_string(),
+                    abi: "cp312".to_string(),
+                    platform: "win32".to_string(),
+                },
+                Tag {
+                    python: "cp312".to_string(),
+                    abi: "cp312".to_string(),
+                    platform: "win_amd64".to_string(),
+                },
+            ]
+        );
+
+        let diagnostic = Diagnostic::PlatformIncompatible {
+            dist: registry_dist("pywin32", "306"),
+            dist_tags,
+            incompatibility: IncompatibleTag::Platform,
+        };
+        assert_eq!(
+            diagnostic.message(),
+            "The package `pywin32==306` has no wheel for the target platform (found: `cp312-cp312-win32`, `cp312-cp312-win_amd64`), so it will be built from source."
+        );
+        assert!(diagnostic.includes(&PackageName::from_str("pywin32").unwrap()));
+    }
+
+    #[test]
+    fn prerelease_included() {
+        let diagnostic = Diagnostic::PreReleaseIncluded {
+            dist: registry_dist("black", "24.1b1"),
+            stable_version: Some(Version::from_str("23.12.1").unwrap()),
+        };
+        assert_eq!(
+            diagnostic.message(),
+            "The package `black==24.1b1` is a pre-release, selected since no stable version satisfies the requirements; pin to `black==23.12.1` or pass `--pre` to allow pre-releases explicitly."
+        );
+        assert!(diagnostic.includes(&PackageName::from_str("black").unwrap()));
+        assert!(diagnostic.is_advisory());
+
+        let diagnostic = Diagnostic::PreReleaseIncluded {
+            dist: registry_dist("black", "24.1b1"),
+            stable_version: None,
+        };
+        assert_eq!(
+            diagnostic.message(),
+            "The package `black==24.1b1` is a pre-release, selected since the package has no stable versions; pass `--pre` to allow pre-releases explicitly."
+        );
+    }
+
+    /// The verbose style should annotate each package with every shortest chain of dependents
+    /// from a top-level requirement.
+    #[test]
+    fn verbose_annotation_style() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[("a", "b"), ("d", "b"), ("b", "c"), ("a", "e"), ("e", "b")],
+        );
+        resolution.requested = FxHashSet::from_iter([
+            PackageName::from_str("a").unwrap(),
+            PackageName::from_str("d").unwrap(),
+        ]);
+
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::Verbose,
+            SourceAnnotations::default(),
+        )
+        .with_color(false);
+        insta::assert_snapshot!(display.to_string(), @r###"
+        a==1.0.0
+        b==1.0.0
+            # via
+            #   a
+            #   d
+        c==1.0.0
+            # via
+            #   a -> b
+            #   d -> b
+        d==1.0.0
+        e==1.0.0
+            # via a
+        "###);
+    }
+
+    /// The `pip-compile` output should include the header, and should optionally render names as
+    /// published.
+    #[test]
+    fn pip_compile_output() {
+        let mut resolution = graph(
+            &[("foo-bar", "1.0.0"), ("baz", "2.0.0")],
+            &[("foo-bar", "baz")],
+        );
+        for dist in resolution.petgraph.node_weights_mut() {
+            if let ResolvedDist::Installable(Dist::Built(BuiltDist::Registry(wheel))) = dist {
+                if wheel.filename.name.as_ref() == "foo-bar" {
+                    wheel.file.filename = "Foo_Bar-1.0.0-py3-none-any.whl".to_string();
+                }
+            }
         }
+
+        let options = PipCompileOutputOptions {
+            command: Some("pip-compile requirements.in".to_string()),
+            timestamp: Some(
+                chrono::DateTime::parse_from_rfc3339("2024-05-01T00:00:00Z")
+                    .unwrap()
+                    .with_timezone(&chrono::Utc),
+            ),
+            ..PipCompileOutputOptions::default()
+        };
+        let output = resolution.to_pip_compile_output(options.clone());
+        let (header, body) = output.split_at(output.find("baz").unwrap());
+        assert!(header.starts_with(
+            "#\n# This file is autogenerated by pip-compile\n# by the following command:\n#\n#    pip-compile requirements.in\n#\n# Generated at 2024-05-01T00:00:00Z (command hash: "
+        ));
+        insta::assert_snapshot!(body, @r###"
+        baz==2.0.0
+            # via foo-bar
+        foo-bar==1.0.0
+        "###);
+
+        let output = resolution.to_pip_compile_output(PipCompileOutputOptions {
+            command: None,
+            verbatim_names: true,
+            ..options
+        });
+        insta::assert_snapshot!(output, @r###"
+        baz==2.0.0
+            # via foo-bar
+        Foo_Bar==1.0.0
+        "###);
     }
-}
 
-#[derive(Debug)]
-enum Node<'a> {
-    /// A node linked to an editable distribution.
-    Editable(&'a PackageName, &'a LocalEditable),
-    /// A node linked to a non-editable distribution.
-    Distribution(&'a PackageName, &'a ResolvedDist, &'a [ExtraName]),
-}
+    /// Verbatim names should only apply to requirements pinned to a published artifact, leaving
+    /// URL requirements untouched.
+    #[test]
+    fn display_verbatim_names() {
+        let mut resolution = graph(&[("foo", "1.0.0"), ("bar", "1.0.0")], &[("bar", "foo")]);
+        for dist in resolution.petgraph.node_weights_mut() {
+            if dist.name().as_ref() == "foo" {
+                if let ResolvedDist::Installable(Dist::Built(BuiltDist::Registry(wheel))) = dist {
+                    wheel.file.filename = "FOO-1.0.0-py3-none-any.whl".to_string();
+                }
+            } else {
+                let url = VerbatimUrl::from_url(
+                    Url::parse("https://example.com/Bar-1.0.0.tar.gz").unwrap(),
+                );
+                *dist = ResolvedDist::Installable(
+                    Dist::from_url(PackageName::from_str("bar").unwrap(), url).unwrap(),
+                );
+            }
+        }
+
+        let display = DisplayResolutionGraph::from(&resolution)
+            .with_verbatim_names(true)
+            .with_color(false);
+        insta::assert_snapshot!(display.to_string(), @r###"
+        bar @ https://example.com/Bar-1.0.0.tar.gz
+        FOO==1.0.0
+            # via bar
+        "###);
+    }
+
+    /// The recorded hashes should be exposed per package, sorted by name.
+    #[test]
+    fn hash_map() {
+        let mut resolution = graph(&[("b", "1.0.0"), ("a", "1.0.0"), ("c", "1.0.0")], &[]);
+        let digest = |digest: &str| HashDigest {
+            algorithm: HashAlgorithm::Sha256,
+            digest: digest.into(),
+        };
+        set_hashes(&mut resolution, "b", vec![digest("5678"), digest("9abc")]);
+        set_hashes(&mut resolution, "a", vec![digest("1234")]);
+
+        let hashes = resolution
+            .hash_map()
+            .into_iter()
+            .map(|(name, hashes)| {
+                let digests = hashes
+                    .iter()
+                    .map(|hash| hash.digest.to_string())
+                    .collect::<Vec<_>>();
+                format!("{name}: {}", digests.join(", "))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, vec!["a: 1234", "b: 5678, 9abc"]);
+    }
+
+    /// Registry distributions should be grouped by their index, and all other distributions
+    /// under `None`, with each group sorted by name.
+    #[test]
+    fn group_by_index() {
+        let mut resolution = graph(&[("b", "1.0.0"), ("c", "1.0.0"), ("a", "1.0.0")], &[]);
+        for dist in resolution.petgraph.node_weights_mut() {
+            if dist.name().as_ref() == "c" {
+                let url = VerbatimUrl::from_url(
+                    Url::parse("https://example.com/c-1.0.0.tar.gz").unwrap(),
+                );
+                *dist = ResolvedDist::Installable(
+                    Dist::from_url(PackageName::from_str("c").unwrap(), url).unwrap(),
+                );
+            }
+        }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum NodeKey<'a> {
-    /// A node linked to an editable distribution, sorted by verbatim representation.
-    Editable(Cow<'a, str>),
-    /// A node linked to a non-editable distribution, sorted by package name.
-    Distribution(&'a PackageName),
-}
+        let groups = resolution.group_by_index();
+        let names = |index: Option<&IndexUrl>| {
+            groups[&index]
+                .iter()
+                .map(|dist| dist.name().to_string())
+                .collect::<Vec<_>>()
+        };
+        let pypi = IndexUrl::Pypi(VerbatimUrl::from_url(
+            Url::parse("https://pypi.org/simple").unwrap(),
+        ));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(names(Some(&pypi)), vec!["a", "b"]);
+        assert_eq!(names(None), vec!["c"]);
+    }
 
-impl<'a> Node<'a> {
-    /// Return the name of the package.
-    fn name(&self) -> &'a PackageName {
-        match self {
-            Node::Editable(name, _) => name,
-            Node::Distribution(name, _, _) => name,
+    /// Registry distributions should be pinned as requested, with their extras, while URL
+    /// distributions are emitted as direct references and local distributions are omitted unless
+    /// requested.
+    #[test]
+    fn to_pyproject_dependencies() {
+        let mut resolution = graph(
+            &[
+                ("b", "2.0.0"),
+                ("a", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b")],
+        );
+        for dist in resolution.petgraph.node_weights_mut() {
+            if dist.name().as_ref() == "c" {
+                let url = VerbatimUrl::from_url(
+                    Url::parse("https://example.com/c-1.0.0.tar.gz").unwrap(),
+                );
+                *dist = ResolvedDist::Installable(
+                    Dist::from_url(PackageName::from_str("c").unwrap(), url).unwrap(),
+                );
+            } else if dist.name().as_ref() == "d" {
+                let filename = "d-1.0.0-py3-none-any.whl";
+                *dist = Dist::Built(BuiltDist::Path(PathBuiltDist {
+                    filename: WheelFilename::from_str(filename).unwrap(),
+                    url: VerbatimUrl::from_url(
+                        Url::parse(&format!("file:///home/ferris/{filename}")).unwrap(),
+                    ),
+                    path: PathBuf::from("/home/ferris").join(filename),
+                }))
+                .into();
+            }
         }
+        resolution.extras.insert(
+            PackageName::from_str("a").unwrap(),
+            vec![ExtraName::from_str("socks").unwrap()],
+        );
+
+        assert_eq!(
+            resolution.to_pyproject_dependencies(DependencyPin::Exact, false),
+            vec![
+                "a[socks]==1.0.0",
+                "b==2.0.0",
+                "c @ https://example.com/c-1.0.0.tar.gz",
+            ]
+        );
+        assert_eq!(
+            resolution.to_pyproject_dependencies(DependencyPin::LowerBound, true),
+            vec![
+                "a[socks]>=1.0.0",
+                "b>=2.0.0",
+                "c @ https://example.com/c-1.0.0.tar.gz",
+                "d @ file:///home/ferris/d-1.0.0-py3-none-any.whl",
+            ]
+        );
     }
 
-    /// Return a comparable key for the node.
-    fn key(&self) -> NodeKey<'a> {
-        match self {
-            Node::Editable(_, editable) => NodeKey::Editable(editable.verbatim()),
-            Node::Distribution(name, _, _) => NodeKey::Distribution(name),
+    /// Packages annotated with the given source file should be included, along with their
+    /// transitive dependencies.
+    #[test]
+    fn packages_from_source() {
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+                ("f", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c"), ("d", "e")],
+        );
+
+        let mut sources = SourceAnnotations::default();
+        for (name, source) in [
+            ("a", "requirements.in"),
+            ("c", "other.in"),
+            ("d", "other.in"),
+        ] {
+            sources.add(
+                &PackageName::from_str(name).unwrap(),
+                SourceAnnotation::Requirement(RequirementOrigin::File(PathBuf::from(source))),
+            );
         }
+
+        let packages = |source: &str| {
+            resolution
+                .packages_from_source(source, &sources)
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(packages("requirements.in"), vec!["a", "b", "c"]);
+        assert_eq!(packages("other.in"), vec!["c", "d", "e"]);
+        assert!(packages("missing.in").is_empty());
     }
 
-    /// Return the [`IndexUrl`] of the distribution, if any.
-    fn index(&self) -> Option<&IndexUrl> {
-        match self {
-            Node::Editable(_, _) => None,
-            Node::Distribution(_, dist, _) => dist.index(),
+    /// Only edges that constrain a dependency to a single version should be reported, sorted by
+    /// package and then by dependent.
+    #[test]
+    fn pinned_by_dependents() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("b", "c"), ("a", "c"), ("a", "b"), ("c", "d")],
+        );
+        for edge in resolution.petgraph.edge_indices().collect::<Vec<_>>() {
+            let (_, target) = resolution.petgraph.edge_endpoints(edge).unwrap();
+            resolution.petgraph[edge] = match resolution.petgraph[target].name().as_ref() {
+                "c" => Range::singleton(Version::from_str("1.0.0").unwrap()),
+                "d" => Range::higher_than(Version::from_str("1.0.0").unwrap()),
+                _ => Range::full(),
+            };
         }
+
+        let pins = resolution
+            .pinned_by_dependents()
+            .iter()
+            .map(Diagnostic::message)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pins,
+            vec![
+                "The package `c` was pinned to `==1.0.0` by `a`.",
+                "The package `c` was pinned to `==1.0.0` by `b`.",
+            ]
+        );
     }
-}
 
-impl Verbatim for Node<'_> {
-    fn verbatim(&self) -> Cow<'_, str> {
-        match self {
-            Node::Editable(_, editable) => Cow::Owned(format!("-e {}", editable.verbatim())),
-            Node::Distribution(_, dist, &[]) => dist.verbatim(),
-            Node::Distribution(_, dist, extras) => {
-                let mut extras = extras.to_vec();
-                extras.sort_unstable();
-                extras.dedup();
-                Cow::Owned(format!(
-                    "{}[{}]{}",
-                    dist.name(),
-                    extras.into_iter().join(", "),
-                    dist.version_or_url().verbatim()
-                ))
+    /// Registry packages should only be reported if the index lacks a compatible wheel for the
+    /// resolved version, while URL source distributions always require a build.
+    #[test]
+    fn sdist_only_packages() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[],
+        );
+        for dist in resolution.petgraph.node_weights_mut() {
+            if dist.name().as_ref() == "e" {
+                let url = VerbatimUrl::from_url(
+                    Url::parse("https://example.com/e-1.0.0.tar.gz").unwrap(),
+                );
+                *dist = ResolvedDist::Installable(
+                    Dist::from_url(PackageName::from_str("e").unwrap(), url).unwrap(),
+                );
             }
         }
+
+        // `a` has a compatible wheel, `b` only has a source distribution, and `c` has a source
+        // distribution alongside an incompatible wheel. `d` is missing from the index.
+        let index = InMemoryIndex::default();
+        let wheel = |name: &str| match registry_dist(name, "1.0.0") {
+            ResolvedDist::Installable(dist) => dist,
+            ResolvedDist::Installed(_) => unreachable!(),
+        };
+        let sdist = |name: &str| {
+            let url = VerbatimUrl::from_url(
+                Url::parse(&format!("https://example.org/{name}-1.0.0.tar.gz")).unwrap(),
+            );
+            Dist::from_url(PackageName::from_str(name).unwrap(), url).unwrap()
+        };
+        let compatible =
+            WheelCompatibility::Compatible(Hash::Matched, TagPriority::try_from(1).unwrap());
+        let incompatible =
+            WheelCompatibility::Incompatible(IncompatibleWheel::Tag(IncompatibleTag::Platform));
+        let source = SourceDistCompatibility::Compatible(Hash::Matched);
+        let mut c = PrioritizedDist::from_source(sdist("c"), vec![], source.clone());
+        c.insert_built(wheel("c"), vec![], incompatible);
+        for (name, dist) in [
+            (
+                "a",
+                PrioritizedDist::from_built(wheel("a"), vec![], compatible),
+            ),
+            (
+                "b",
+                PrioritizedDist::from_source(sdist("b"), vec![], source),
+            ),
+            ("c", c),
+        ] {
+            index.insert_package(
+                PackageName::from_str(name).unwrap(),
+                VersionsResponse::Found(vec![VersionMap::from(BTreeMap::from([(
+                    Version::from_str("1.0.0").unwrap(),
+                    dist,
+                )]))]),
+            );
+        }
+
+        let packages = resolution
+            .sdist_only_packages(&index)
+            .into_iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(packages, vec!["b", "c", "e"]);
     }
-}
 
-/// Write the graph in the `{name}=={version}` format of requirements.txt that pip uses.
-impl std::fmt::Display for DisplayResolutionGraph<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Collect all packages.
-        let mut nodes = self
-            .resolution
-            .petgraph
-            .node_indices()
-            .filter_map(|index| {
-                let dist = &self.resolution.petgraph[index];
-                let name = dist.name();
-                if self.no_emit_packages.contains(name) {
-                    return None;
-                }
+    /// Each marker environment should yield its own graph, keyed by platform and including only
+    /// the packages that apply to it.
+    #[test]
+    fn split_by_platform() {
+        let (resolution, manifest, index) = conditional_resolution();
+        let graphs = resolution
+            .split_by_platform(
+                &manifest,
+                &index,
+                &[marker_env("linux", "3.12"), marker_env("win32", "3.7")],
+            )
+            .unwrap();
 
-                let node = if let Some((editable, _, _)) = self.resolution.editables.get(name) {
-                    Node::Editable(name, editable)
-                } else if self.include_extras {
-                    Node::Distribution(
-                        name,
-                        dist,
-                        self.resolution
-                            .extras
-                            .get(name)
-                            .map_or(&[], |extras| extras.as_slice()),
-                    )
-                } else {
-                    Node::Distribution(name, dist, &[])
-                };
-                Some((index, node))
+        let mut graphs = graphs
+            .into_iter()
+            .map(|(key, graph)| {
+                let mut names = graph
+                    .petgraph
+                    .node_weights()
+                    .map(|dist| dist.name().to_string())
+                    .collect::<Vec<_>>();
+                names.sort();
+                (key, names)
             })
             .collect::<Vec<_>>();
+        graphs.sort();
+        assert_eq!(
+            graphs,
+            vec![
+                (
+                    "linux-x86_64-py3.12".to_string(),
+                    vec!["a".to_string(), "c".to_string()]
+                ),
+                (
+                    "win32-x86_64-py3.7".to_string(),
+                    vec![
+                        "a".to_string(),
+                        "b".to_string(),
+                        "c".to_string(),
+                        "d".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
 
-        // Sort the nodes by name, but with editable packages first.
-        nodes.sort_unstable_by_key(|(index, node)| (node.key(), *index));
+    /// Packages should be reported as added, removed, or changed, where changes include both the
+    /// version and the hashes of a package.
+    #[test]
+    fn diff() {
+        let old = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+            ],
+            &[("a", "b")],
+        );
+        let mut new = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "2.0.0"),
+                ("c", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[("a", "b")],
+        );
+        set_hashes(
+            &mut new,
+            "c",
+            vec![HashDigest {
+                algorithm: HashAlgorithm::Sha256,
+                digest: "1234".into(),
+            }],
+        );
 
-        // Print out the dependency graph.
-        for (index, node) in nodes {
-            // Display the node itself.
-            let mut line = node.verbatim().to_string();
+        assert!(ResolutionGraph::diff(&old, &old).is_empty());
 
-            // Display the distribution hashes, if any.
-            let mut has_hashes = false;
-            if self.show_hashes {
-                if let Some(hashes) = self
-                    .resolution
-                    .hashes
-                    .get(node.name())
-                    .filter(|hashes| !hashes.is_empty())
-                {
-                    for hash in hashes {
-                        has_hashes = true;
-                        line.push_str(" \\\n");
-                        line.push_str("    --hash=");
-                        line.push_str(&hash.to_string());
-                    }
-                }
+        let diff = ResolutionGraph::diff(&old, &new);
+        let names = |dists: Vec<&ResolvedDist>| {
+            dists
+                .into_iter()
+                .map(|dist| dist.name().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(diff.added().collect()), vec!["e"]);
+        assert_eq!(names(diff.removed().collect()), vec!["d"]);
+        assert_eq!(
+            diff.changed()
+                .map(|(name, old, new)| format!("{name}: {} -> {}", old.verbatim(), new.verbatim()))
+                .collect::<Vec<_>>(),
+            vec!["b: b==1.0.0 -> b==2.0.0", "c: c==1.0.0 -> c==1.0.0"]
+        );
+
+        let output = anstream::adapter::strip_str(&diff.to_string()).to_string();
+        assert_eq!(
+            output,
+            " - b==1.0.0\n + b==2.0.0\n ~ c==1.0.0\n - d==1.0.0\n + e==1.0.0\n"
+        );
+    }
+
+    /// Packages that were requested directly and editables should be reported as roots, even if
+    /// other packages depend on them, while transitive packages should not, even if nothing
+    /// depends on them.
+    #[test]
+    fn roots() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c"), ("d", "c"), ("d", "a")],
+        );
+        set_requested(&mut resolution, &["a"]);
+        resolution.editables = Editables::from_requirements(vec![(
+            editable("../d"),
+            Metadata23 {
+                name: PackageName::from_str("d").unwrap(),
+                version: Version::from_str("1.0.0").unwrap(),
+                requires_dist: vec![],
+                requires_python: None,
+                provides_extras: vec![],
+            },
+            Requirements {
+                dependencies: vec![],
+                optional_dependencies: IndexMap::default(),
+            },
+        )]);
+
+        let mut roots = resolution
+            .roots()
+            .map(|dist| dist.name().to_string())
+            .collect::<Vec<_>>();
+        roots.sort();
+        assert_eq!(roots, vec!["a", "d"]);
+
+        let is_root = |name: &str| resolution.is_root(&PackageName::from_str(name).unwrap());
+        assert!(is_root("a"));
+        assert!(!is_root("b"));
+        assert!(is_root("d"));
+        assert!(!is_root("e"));
+        assert!(!is_root("f"));
+    }
+
+    /// Unconditional requirements that are absent from the resolution, and roots that aren't
+    /// requested by the manifest, should both be reported.
+    #[test]
+    fn check_against_inputs() {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")],
+            &[("a", "b")],
+        );
+        set_requested(&mut resolution, &["a", "c"]);
+
+        let requirement = |requirement: &str| {
+            Requirement::from_pep508(pep508_rs::Requirement::from_str(requirement).unwrap())
+                .unwrap()
+        };
+        let manifest = Manifest::simple(vec![
+            requirement("a"),
+            requirement("d"),
+            requirement("e ; sys_platform == 'win32'"),
+        ]);
+
+        let drift = resolution
+            .check_against_inputs(&manifest)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            drift,
+            vec![
+                "The requirement `d` is missing from the resolution",
+                "The package `c` is not requested by the input requirements",
+            ]
+        );
+
+        let manifest = Manifest::simple(vec![requirement("a"), requirement("c")]);
+        assert!(resolution.check_against_inputs(&manifest).is_empty());
+    }
+
+    /// The closure should include every package reachable from the given package (but never the
+    /// package itself, even within a cycle), up to the given depth.
+    #[test]
+    fn transitive_dependencies() {
+        let resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "1.0.0"),
+            ],
+            &[("a", "b"), ("b", "c"), ("c", "d"), ("a", "e"), ("c", "a")],
+        );
+
+        let names = |dists: Option<Vec<&ResolvedDist>>| {
+            let mut names = dists?
+                .into_iter()
+                .map(|dist| dist.name().to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            Some(names)
+        };
+        let closure = |name: &str| {
+            names(
+                resolution
+                    .transitive_dependencies(&PackageName::from_str(name).unwrap())
+                    .map(Iterator::collect),
+            )
+        };
+        let closure_up_to = |name: &str, depth: usize| {
+            names(
+                resolution
+                    .transitive_dependencies_up_to_depth(
+                        &PackageName::from_str(name).unwrap(),
+                        depth,
+                    )
+                    .map(Iterator::collect),
+            )
+        };
+
+        assert_eq!(closure("a").unwrap(), vec!["b", "c", "d", "e"]);
+        assert_eq!(closure("c").unwrap(), vec!["a", "b", "d", "e"]);
+        assert!(closure("d").unwrap().is_empty());
+        assert!(closure("f").is_none());
+
+        assert!(closure_up_to("a", 0).unwrap().is_empty());
+        assert_eq!(closure_up_to("a", 1).unwrap(), vec!["b", "e"]);
+        assert_eq!(closure_up_to("a", 2).unwrap(), vec!["b", "c", "e"]);
+        assert_eq!(closure_up_to("a", 3).unwrap(), vec!["b", "c", "d", "e"]);
+        assert!(closure_up_to("f", 1).is_none());
+    }
+
+    /// Only registry packages with multiple dependents should be pinned, with self-dependencies
+    /// ignored.
+    #[test]
+    fn to_minimal_constraints() {
+        let mut resolution = graph(
+            &[
+                ("a", "1.0.0"),
+                ("b", "1.0.0"),
+                ("c", "1.0.0"),
+                ("d", "1.0.0"),
+                ("e", "2.0.0"),
+                ("f", "1.0.0"),
+            ],
+            &[
+                ("a", "c"),
+                ("b", "c"),
+                ("a", "d"),
+                ("d", "d"),
+                ("b", "e"),
+                ("d", "e"),
+                ("a", "f"),
+                ("b", "f"),
+            ],
+        );
+        for dist in resolution.petgraph.node_weights_mut() {
+            if dist.name().as_ref() == "f" {
+                let url = VerbatimUrl::from_url(
+                    Url::parse("https://example.com/f-1.0.0.tar.gz").unwrap(),
+                );
+                *dist = ResolvedDist::Installable(
+                    Dist::from_url(PackageName::from_str("f").unwrap(), url).unwrap(),
+                );
             }
+        }
 
-            // Determine the annotation comment and separator (between comment and requirement).
-            let mut annotation = None;
+        assert_eq!(resolution.to_minimal_constraints(), "c==1.0.0\ne==2.0.0\n");
+    }
 
-            // If enabled, include annotations to indicate the dependencies that requested each
-            // package (e.g., `# via mypy`).
-            if self.include_annotations {
-                // Display all dependencies.
-                let mut edges = self
-                    .resolution
-                    .petgraph
-                    .edges_directed(index, Direction::Incoming)
-                    .map(|edge| &self.resolution.petgraph[edge.source()])
-                    .collect::<Vec<_>>();
-                edges.sort_unstable_by_key(|package| package.name());
+    /// Pinning a yanked distribution should be reported, with the reason if one was given.
+    #[test]
+    fn from_state_yanked() {
+        let yank = |name: &str, yanked: Option<Yanked>| {
+            let mut dist = registry_dist(name, "1.0.0");
+            let ResolvedDist::Installable(Dist::Built(BuiltDist::Registry(wheel))) = &mut dist
+            else {
+                unreachable!()
+            };
+            wheel.file.yanked = yanked;
+            dist
+        };
+        let pins = vec![
+            yank("a", Some(Yanked::Reason("Broken".to_string()))),
+            yank("b", Some(Yanked::Bool(true))),
+            yank("c", Some(Yanked::Bool(false))),
+            yank("d", None),
+        ];
+        let selection = ["a", "b", "c", "d"].map(|name| (pubgrub_package(name, None), "1.0.0"));
 
-                // Include all external sources (e.g., requirements files).
-                let default = BTreeSet::default();
-                let source = match node {
-                    Node::Editable(_, editable) => {
-                        self.sources.get_editable(&editable.url).unwrap_or(&default)
-                    }
-                    Node::Distribution(name, _, _) => self.sources.get(name).unwrap_or(&default),
-                };
+        let resolution =
+            from_state(&selection, pins, &InMemoryIndex::default(), "3.12", None).unwrap();
 
-                match self.annotation_style {
-                    AnnotationStyle::Line => {
-                        if !edges.is_empty() {
-                            let separator = if has_hashes { "\n    " } else { "  " };
-                            let deps = edges
-                                .into_iter()
-                                .map(|dependency| format!("{}", dependency.name()))
-                                .chain(source.iter().map(std::string::ToString::to_string))
-                                .collect::<Vec<_>>()
-                                .join(", ");
-                            let comment = format!("# via {deps}").green().to_string();
-                            annotation = Some((separator, comment));
-                        }
-                    }
-                    AnnotationStyle::Split => match edges.as_slice() {
-                        [] if source.is_empty() => {}
-                        [] if source.len() == 1 => {
-                            let separator = "\n";
-                            let comment = format!("    # via {}", source.iter().next().unwrap())
-                                .green()
-                                .to_string();
-                            annotation = Some((separator, comment));
-                        }
-                        [edge] if source.is_empty() => {
-                            let separator = "\n";
-                            let comment = format!("    # via {}", edge.name()).green().to_string();
-                            annotation = Some((separator, comment));
-                        }
-                        edges => {
-                            let separator = "\n";
-                            let deps = source
-                                .iter()
-                                .map(std::string::ToString::to_string)
-                                .chain(
-                                    edges
-                                        .iter()
-                                        .map(|dependency| format!("{}", dependency.name())),
-                                )
-                                .map(|name| format!("    #   {name}"))
-                                .collect::<Vec<_>>()
-                                .join("\n");
-                            let comment = format!("    # via\n{deps}").green().to_string();
-                            annotation = Some((separator, comment));
-                        }
-                    },
+        let mut yanked = resolution
+            .diagnostics()
+            .iter()
+            .filter_map(|diagnostic| match diagnostic {
+                Diagnostic::YankedPackage { dist, reason } => {
+                    Some((dist.name().to_string(), reason.clone()))
                 }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        yanked.sort();
+        assert_eq!(
+            yanked,
+            vec![
+                ("a".to_string(), Some("Broken".to_string())),
+                ("b".to_string(), None),
+            ]
+        );
+    }
+
+    /// Packages whose `Requires-Python` excludes the installed interpreter should be reported,
+    /// while those without metadata or with a compatible `Requires-Python` should not.
+    #[test]
+    fn from_state_incompatible_python_version() {
+        let index = InMemoryIndex::default();
+        for (name, requires_python) in [("a", ">=3.13"), ("b", ">=3.8")] {
+            index.insert_metadata(
+                registry_dist(name, "1.0.0").version_id(),
+                MetadataResponse::Found(ArchiveMetadata::from(Metadata23 {
+                    name: PackageName::from_str(name).unwrap(),
+                    version: Version::from_str("1.0.0").unwrap(),
+                    requires_dist: vec![],
+                    requires_python: Some(VersionSpecifiers::from_str(requires_python).unwrap()),
+                    provides_extras: vec![],
+                })),
+            );
+        }
+        let pins = ["a", "b", "c"].map(|name| registry_dist(name, "1.0.0"));
+        let selection = ["a", "b", "c"].map(|name| (pubgrub_package(name, None), "1.0.0"));
+
+        let resolution = from_state(&selection, pins.to_vec(), &index, "3.12", None).unwrap();
+
+        let incompatible = resolution
+            .diagnostics()
+            .iter()
+            .filter_map(|diagnostic| match diagnostic {
+                Diagnostic::IncompatiblePythonVersion {
+                    dist,
+                    requires_python,
+                    installed_python,
+                } => Some((
+                    dist.name().to_string(),
+                    requires_python.to_string(),
+                    installed_python.to_string(),
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            incompatible,
+            vec![("a".to_string(), ">=3.13".to_string(), "3.12".to_string())]
+        );
+    }
+
+    /// The reporter should be notified once for each package added to the graph, but not for the
+    /// root or for extras.
+    #[test]
+    fn from_state_on_pinned() {
+        #[derive(Default)]
+        struct PinReporter(Mutex<Vec<String>>);
+
+        impl ResolverReporter for PinReporter {
+            fn on_progress(&self, _name: &PackageName, _version: &VersionOrUrlRef) {}
+
+            fn on_pinned(&self, name: &PackageName, version: &VersionOrUrlRef) {
+                self.0.lock().unwrap().push(format!("{name}{version}"));
             }
 
-            if let Some((separator, comment)) = annotation {
-                // Assemble the line with the annotations and remove trailing whitespaces.
-                for line in format!("{line:24}{separator}{comment}").lines() {
-                    let line = line.trim_end();
-                    writeln!(f, "{line}")?;
-                }
-            } else {
-                // Write the line as is.
-                writeln!(f, "{line}")?;
+            fn on_complete(&self) {}
+
+            fn on_build_start(&self, _source: &BuildableSource) -> usize {
+                0
             }
 
-            // If enabled, include indexes to indicate which index was used for each package (e.g.,
-            // `# from https://pypi.org/simple`).
-            if self.include_index_annotation {
-                if let Some(index) = node.index() {
-                    let url = index.redacted();
-                    writeln!(f, "{}", format!("    # from {url}").green())?;
-                }
+            fn on_build_complete(&self, _source: &BuildableSource, _id: usize) {}
+
+            fn on_checkout_start(&self, _url: &Url, _rev: &str) -> usize {
+                0
             }
+
+            fn on_checkout_complete(&self, _url: &Url, _rev: &str, _index: usize) {}
         }
 
-        Ok(())
-    }
-}
+        let index = InMemoryIndex::default();
+        index.insert_metadata(
+            registry_dist("a", "1.0.0").version_id(),
+            MetadataResponse::Found(ArchiveMetadata::from(Metadata23 {
+                name: PackageName::from_str("a").unwrap(),
+                version: Version::from_str("1.0.0").unwrap(),
+                requires_dist: vec![],
+                requires_python: None,
+                provides_extras: vec![ExtraName::from_str("dev").unwrap()],
+            })),
+        );
+        let pins = vec![registry_dist("a", "1.0.0"), registry_dist("b", "2.0.0")];
+        let selection = [
+            (pubgrub_package("a", None), "1.0.0"),
+            (pubgrub_package("a", Some("dev")), "1.0.0"),
+            (pubgrub_package("b", None), "2.0.0"),
+        ];
 
-impl From<ResolutionGraph> for distribution_types::Resolution {
-    fn from(graph: ResolutionGraph) -> Self {
-        Self::new(
-            graph
-                .petgraph
-                .node_indices()
-                .map(|node| {
-                    (
-                        graph.petgraph[node].name().clone(),
-                        graph.petgraph[node].clone(),
-                    )
-                })
-                .collect(),
+        let reporter = Arc::new(PinReporter::default());
+        let resolution = from_state(
+            &selection,
+            pins,
+            &index,
+            "3.12",
+            Some(&(reporter.clone() as Arc<dyn ResolverReporter>)),
         )
+        .unwrap();
+        assert_eq!(resolution.len(), 2);
+
+        let mut pinned = reporter.0.lock().unwrap().clone();
+        pinned.sort();
+        assert_eq!(pinned, vec!["a==1.0.0", "b==2.0.0"]);
     }
-}
 
-#[derive(Debug)]
-pub enum Diagnostic {
-    MissingExtra {
-        /// The distribution that was requested with an non-existent extra. For example,
-        /// `black==23.10.0`.
-        dist: ResolvedDist,
-        /// The extra that was requested. For example, `colorama` in `black[colorama]`.
-        extra: ExtraName,
-    },
-}
+    /// A custom indentation should be applied to every `# via` line in the split style.
+    #[test]
+    fn split_annotation_indent() {
+        let resolution = graph(
+            &[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")],
+            &[("a", "b"), ("a", "c"), ("b", "c")],
+        );
 
-impl Diagnostic {
-    /// Convert the diagnostic into a user-facing message.
-    pub fn message(&self) -> String {
-        match self {
-            Self::MissingExtra { dist, extra } => {
-                format!("The package `{dist}` does not have an extra named `{extra}`.")
-            }
-        }
-    }
+        let display = DisplayResolutionGraph::new(
+            &resolution,
+            &[],
+            false,
+            false,
+            false,
+            true,
+            false,
+            AnnotationStyle::Split,
+            SourceAnnotations::default(),
+        )
+        .with_indent("  ");
+        let output = anstream::adapter::strip_str(&display.to_string()).to_string();
 
-    /// Returns `true` if the [`PackageName`] is involved in this diagnostic.
-    pub fn includes(&self, name: &PackageName) -> bool {
-        match self {
-            Self::MissingExtra { dist, .. } => name == dist.name(),
-        }
+        insta::assert_snapshot!(output, @r###"
+        a==1.0.0
+        b==1.0.0
+          # via a
+        c==1.0.0
+          # via
+          #   a
+          #   b
+        "###);
     }
 }
@@ -38,6 +38,7 @@ use crate::candidate_selector::{CandidateDist, CandidateSelector};
 use crate::dependency_provider::UvDependencyProvider;
 use crate::editables::Editables;
 use crate::error::ResolveError;
+use crate::exclude_newer::ExcludeNewerMap;
 use crate::manifest::Manifest;
 use crate::pins::FilePins;
 use crate::preferences::Preferences;
@@ -46,7 +47,7 @@ use crate::pubgrub::{
     PubGrubRequirement, PubGrubSpecifier,
 };
 use crate::python_requirement::PythonRequirement;
-use crate::resolution::ResolutionGraph;
+use crate::resolution::{Diagnostic, ResolutionGraph};
 use crate::resolver::batch_prefetch::BatchPrefetcher;
 pub use crate::resolver::index::InMemoryIndex;
 pub use crate::resolver::provider::{
@@ -56,7 +57,7 @@ pub use crate::resolver::provider::{
 use crate::resolver::reporter::Facade;
 pub use crate::resolver::reporter::{BuildId, Reporter};
 use crate::yanks::AllowedYanks;
-use crate::{DependencyMode, Exclusions, FlatIndex, Options};
+use crate::{DependencyMode, Exclusions, FlatIndex, Options, VersionMap};
 
 mod batch_prefetch;
 mod index;
@@ -190,6 +191,8 @@ pub struct Resolver<'a, Provider: ResolverProvider, InstalledPackages: Installed
     /// When not set, the resolver is in "universal" mode.
     markers: Option<&'a MarkerEnvironment>,
     python_requirement: &'a PythonRequirement,
+    /// The tags of the target interpreter, if known.
+    tags: Option<&'a Tags>,
     selector: CandidateSelector,
     index: &'a InMemoryIndex,
     installed_packages: &'a InstalledPackages,
@@ -245,21 +248,24 @@ impl<'a, Context: BuildContext, InstalledPackages: InstalledPackagesProvider>
             python_requirement.clone(),
             AllowedYanks::from_manifest(&manifest, markers, options.dependency_mode),
             hasher,
-            options.exclude_newer,
+            ExcludeNewerMap::new(options.exclude_newer, options.exclude_newer_package.clone()),
             build_context.no_binary(),
             build_context.no_build(),
         );
 
-        Self::new_custom_io(
-            manifest,
-            options,
-            hasher,
-            markers,
-            python_requirement,
-            index,
-            provider,
-            installed_packages,
-        )
+        Ok(Self {
+            tags: Some(tags),
+            ..Self::new_custom_io(
+                manifest,
+                options,
+                hasher,
+                markers,
+                python_requirement,
+                index,
+                provider,
+                installed_packages,
+            )?
+        })
     }
 }
 
@@ -283,7 +289,7 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
             unavailable_packages: SharedMap::default(),
             incomplete_packages: SharedMap::default(),
             visited: SharedSet::default(),
-            selector: CandidateSelector::for_resolution(options, &manifest, markers),
+            selector: CandidateSelector::for_resolution(&options, &manifest, markers),
             dependency_mode: options.dependency_mode,
             urls: Urls::from_manifest(&manifest, markers, options.dependency_mode)?,
             locals: Locals::from_manifest(&manifest, markers, options.dependency_mode),
@@ -297,6 +303,7 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
             hasher,
             markers,
             python_requirement,
+            tags: None,
             reporter: None,
             provider,
             installed_packages,
@@ -367,6 +374,7 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
             pubgrub: State::init(root.clone(), MIN_VERSION.clone()),
             next: root,
             pins: FilePins::default(),
+            diagnostics: FxHashMap::default(),
             priorities: PubGrubPriorities::default(),
             added_dependencies: FxHashMap::default(),
         };
@@ -403,11 +411,17 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
                 return ResolutionGraph::from_state(
                     &selection,
                     &state.pins,
+                    &state.diagnostics,
                     &self.index.packages,
                     &self.index.distributions,
                     &state.pubgrub,
                     &self.preferences,
+                    self.hasher,
+                    self.python_requirement,
+                    self.tags,
+                    &self.requirements,
                     self.editables.clone(),
+                    self.reporter.as_ref(),
                 );
             };
             state.next = highest_priority_pkg;
@@ -426,6 +440,7 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
                     &state.next,
                     term_intersection.unwrap_positive(),
                     &mut state.pins,
+                    &mut state.diagnostics,
                     &request_sink,
                 )
                 .await?;
@@ -655,6 +670,7 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
         package: &'a PubGrubPackage,
         range: &Range<Version>,
         pins: &mut FilePins,
+        diagnostics: &mut FxHashMap<PackageName, FxHashMap<Version, Diagnostic>>,
         request_sink: &tokio::sync::mpsc::Sender<Request>,
     ) -> Result<Option<ResolverVersion>, ResolveError> {
         match package {
@@ -848,6 +864,27 @@ impl<'a, Provider: ResolverProvider, InstalledPackages: InstalledPackagesProvide
                 // store the exact file that we selected to satisfy that version.
                 pins.insert(&candidate, dist);
 
+                // Flag pre-releases that were selected as a fallback, since no stable version
+                // satisfies the requirements.
+                if candidate.version().any_prerelease()
+                    && self.selector.prerelease_if_necessary(package_name)
+                {
+                    let stable_version = version_maps
+                        .iter()
+                        .flat_map(VersionMap::iter)
+                        .map(|(version, _)| version)
+                        .filter(|version| !version.any_prerelease())
+                        .max()
+                        .cloned();
+                    diagnostics.entry(package_name.clone()).or_default().insert(
+                        candidate.version().clone(),
+                        Diagnostic::PreReleaseIncluded {
+                            dist: dist.for_installation().to_owned(),
+                            stable_version,
+                        },
+                    );
+                }
+
                 let version = candidate.version().clone();
 
                 // Emit a request to fetch the metadata for this version.
@@ -1407,6 +1444,12 @@ struct ResolverState {
     /// After resolution is finished, this maps is consulted in order to select
     /// the wheel chosen during resolution.
     pins: FilePins,
+    /// The diagnostics encountered while choosing each version of each package, such as the
+    /// selection of a pre-release as a fallback.
+    ///
+    /// Like [`FilePins`], these are keyed by package name and version, such that only those
+    /// for the versions in the final solution are reported.
+    diagnostics: FxHashMap<PackageName, FxHashMap<Version, Diagnostic>>,
     /// When dependencies for a package are retrieved, this map of priorities
     /// is updated based on how each dependency was specified. Certain types
     /// of dependencies have more "priority" than others (like direct URL
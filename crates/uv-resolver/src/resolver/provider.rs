@@ -13,7 +13,7 @@ use crate::flat_index::FlatIndex;
 use crate::python_requirement::PythonRequirement;
 use crate::version_map::VersionMap;
 use crate::yanks::AllowedYanks;
-use crate::ExcludeNewer;
+use crate::ExcludeNewerMap;
 
 pub type PackageVersionsResult = Result<VersionsResponse, uv_client::Error>;
 pub type WheelMetadataResult = Result<MetadataResponse, uv_distribution::Error>;
@@ -80,7 +80,7 @@ pub struct DefaultResolverProvider<'a, Context: BuildContext> {
     python_requirement: PythonRequirement,
     allowed_yanks: AllowedYanks,
     hasher: HashStrategy,
-    exclude_newer: Option<ExcludeNewer>,
+    exclude_newer: ExcludeNewerMap,
     no_binary: NoBinary,
     no_build: NoBuild,
 }
@@ -95,7 +95,7 @@ impl<'a, Context: BuildContext> DefaultResolverProvider<'a, Context> {
         python_requirement: PythonRequirement,
         allowed_yanks: AllowedYanks,
         hasher: &'a HashStrategy,
-        exclude_newer: Option<ExcludeNewer>,
+        exclude_newer: ExcludeNewerMap,
         no_binary: &'a NoBinary,
         no_build: &'a NoBuild,
     ) -> Self {
@@ -138,7 +138,7 @@ impl<'a, Context: BuildContext> ResolverProvider for DefaultResolverProvider<'a,
                             &self.python_requirement,
                             &self.allowed_yanks,
                             &self.hasher,
-                            self.exclude_newer.as_ref(),
+                            self.exclude_newer.get(package_name),
                             self.flat_index.get(package_name).cloned(),
                             &self.no_binary,
                             &self.no_build,
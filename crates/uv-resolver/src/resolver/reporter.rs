@@ -11,6 +11,12 @@ pub trait Reporter: Send + Sync {
     /// Callback to invoke when a dependency is resolved.
     fn on_progress(&self, name: &PackageName, version: &VersionOrUrlRef);
 
+    /// Callback to invoke when a resolved package is added to the resolution graph.
+    ///
+    /// Invoked once per package while the final graph is constructed, after the resolution itself
+    /// has completed.
+    fn on_pinned(&self, _name: &PackageName, _version: &VersionOrUrlRef) {}
+
     /// Callback to invoke when the resolution is complete.
     fn on_complete(&self);
 
@@ -0,0 +1,612 @@
+//! Export a [`ResolutionGraph`] as a software bill of materials (SBOM), in either the
+//! [CycloneDX](https://cyclonedx.org/) or [SPDX](https://spdx.dev/) format.
+
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use distribution_types::{Dist, Name, ResolvedDist, SourceDist, VersionOrUrlRef};
+use pep508_rs::PackageName;
+use pypi_types::HashAlgorithm;
+
+use crate::ResolutionGraph;
+
+/// The CycloneDX specification version targeted by the export.
+const SPEC_VERSION: &str = "1.5";
+
+/// A CycloneDX bill of materials, serializable to CycloneDX JSON.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxBom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    components: Vec<Component>,
+    dependencies: Vec<Dependency>,
+}
+
+/// A single component (i.e., package) in the bill of materials.
+#[derive(Debug, Clone, serde::Serialize)]
+struct Component {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<Property>,
+}
+
+/// A hash of a component's artifact.
+#[derive(Debug, Clone, serde::Serialize)]
+struct Hash {
+    alg: &'static str,
+    content: String,
+}
+
+/// A name-value property attached to a component.
+#[derive(Debug, Clone, serde::Serialize)]
+struct Property {
+    name: &'static str,
+    value: String,
+}
+
+/// The direct dependencies of a component, identified by their `bom-ref`.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Dependency {
+    #[serde(rename = "ref")]
+    reference: String,
+    depends_on: Vec<String>,
+}
+
+/// Convert a [`ResolutionGraph`] into a CycloneDX bill of materials.
+///
+/// Each distribution is represented as a `library` component, identified by a `pkg:pypi` package
+/// URL (when the version is known) and annotated with its hashes. Editable distributions include a
+/// `uv:editable-path` property indicating their location on disk.
+pub fn to_cyclonedx(graph: &ResolutionGraph) -> CycloneDxBom {
+    let petgraph = graph.petgraph();
+    let hashes = graph.hash_map();
+
+    let mut nodes = petgraph.node_indices().collect::<Vec<_>>();
+    nodes.sort_unstable_by_key(|index| petgraph[*index].name());
+
+    let components = nodes
+        .iter()
+        .map(|index| {
+            let dist = &petgraph[*index];
+            let name = dist.name();
+            let version = match dist.version_or_url() {
+                VersionOrUrlRef::Version(version) => Some(version.to_string()),
+                VersionOrUrlRef::Url(_) => None,
+            };
+            let purl = version
+                .as_ref()
+                .map(|version| format!("pkg:pypi/{name}@{version}"));
+            let hashes = hashes
+                .get(name)
+                .into_iter()
+                .flat_map(|hashes| hashes.iter())
+                .map(|hash| Hash {
+                    alg: match hash.algorithm() {
+                        HashAlgorithm::Md5 => "MD5",
+                        HashAlgorithm::Sha256 => "SHA-256",
+                        HashAlgorithm::Sha384 => "SHA-384",
+                        HashAlgorithm::Sha512 => "SHA-512",
+                    },
+                    content: hash.digest.to_string(),
+                })
+                .collect();
+            let properties = match dist {
+                ResolvedDist::Installable(Dist::Source(SourceDist::Directory(directory)))
+                    if directory.editable =>
+                {
+                    vec![Property {
+                        name: "uv:editable-path",
+                        value: directory.path.display().to_string(),
+                    }]
+                }
+                _ => vec![],
+            };
+            Component {
+                kind: "library",
+                bom_ref: name.to_string(),
+                name: name.to_string(),
+                version,
+                purl,
+                hashes,
+                properties,
+            }
+        })
+        .collect();
+
+    let dependencies = nodes
+        .iter()
+        .map(|index| {
+            let mut depends_on = petgraph
+                .edges_directed(*index, Direction::Outgoing)
+                .map(|edge| petgraph[edge.target()].name().to_string())
+                .collect::<Vec<_>>();
+            depends_on.sort_unstable();
+            depends_on.dedup();
+            Dependency {
+                reference: petgraph[*index].name().to_string(),
+                depends_on,
+            }
+        })
+        .collect();
+
+    CycloneDxBom {
+        bom_format: "CycloneDX",
+        spec_version: SPEC_VERSION,
+        version: 1,
+        components,
+        dependencies,
+    }
+}
+
+/// The SPDX specification version targeted by the export.
+const SPDX_VERSION: &str = "SPDX-2.3";
+
+/// The placeholder used for fields that we can't (or don't) populate.
+const NOASSERTION: &str = "NOASSERTION";
+
+/// An SPDX document, serializable to SPDX JSON and displayable in the SPDX tag-value format.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxDocument {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<SpdxPackage>,
+    relationships: Vec<Relationship>,
+}
+
+/// The creation metadata for an SPDX document.
+#[derive(Debug, Clone, serde::Serialize)]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+/// A single package in an SPDX document.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version_info: Option<String>,
+    download_location: String,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checksums: Vec<Checksum>,
+    license_concluded: &'static str,
+    license_declared: &'static str,
+    copyright_text: &'static str,
+}
+
+/// A checksum of a package's artifact.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checksum {
+    algorithm: &'static str,
+    checksum_value: String,
+}
+
+/// A relationship between two SPDX elements.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+/// Return the SPDX identifier for the given package.
+fn spdx_id(name: &PackageName) -> String {
+    // Normalized package names only contain ASCII letters, digits, and `-`, all of which are
+    // valid in an SPDX identifier.
+    format!("SPDXRef-Package-{name}")
+}
+
+impl ResolutionGraph {
+    /// Convert the resolution into an SPDX document with the given name, namespace, and creation
+    /// time.
+    ///
+    /// The namespace must be a URI that's unique to this document (e.g.,
+    /// `https://spdx.org/spdxdocs/{name}-{uuid}`), such that documents generated at different times
+    /// can be distinguished.
+    ///
+    /// Each distribution is represented as a package, with its download location set to the index
+    /// (for registry distributions) or the direct URL (for Git, URL, and path distributions).
+    /// Dependencies are represented as `DEPENDS_ON` relationships. As license metadata isn't
+    /// extracted during resolution, all license fields are set to `NOASSERTION`.
+    pub fn to_spdx(
+        &self,
+        document_name: &str,
+        document_namespace: &str,
+        created: DateTime<Utc>,
+    ) -> SpdxDocument {
+        let petgraph = self.petgraph();
+        let hashes = self.hash_map();
+
+        let mut nodes = petgraph.node_indices().collect::<Vec<_>>();
+        nodes.sort_unstable_by_key(|index| petgraph[*index].name());
+
+        let packages = nodes
+            .iter()
+            .map(|index| {
+                let dist = &petgraph[*index];
+                let (version_info, download_location) = match dist.version_or_url() {
+                    VersionOrUrlRef::Version(version) => (
+                        Some(version.to_string()),
+                        dist.index().map_or_else(
+                            || NOASSERTION.to_string(),
+                            |index| index.redacted().to_string(),
+                        ),
+                    ),
+                    VersionOrUrlRef::Url(url) => (None, url.to_string()),
+                };
+                let checksums = hashes
+                    .get(dist.name())
+                    .into_iter()
+                    .flat_map(|hashes| hashes.iter())
+                    .map(|hash| Checksum {
+                        algorithm: match hash.algorithm() {
+                            HashAlgorithm::Md5 => "MD5",
+                            HashAlgorithm::Sha256 => "SHA256",
+                            HashAlgorithm::Sha384 => "SHA384",
+                            HashAlgorithm::Sha512 => "SHA512",
+                        },
+                        checksum_value: hash.digest.to_string(),
+                    })
+                    .collect();
+                SpdxPackage {
+                    name: dist.name().to_string(),
+                    spdx_id: spdx_id(dist.name()),
+                    version_info,
+                    download_location,
+                    files_analyzed: false,
+                    checksums,
+                    license_concluded: NOASSERTION,
+                    license_declared: NOASSERTION,
+                    copyright_text: NOASSERTION,
+                }
+            })
+            .collect();
+
+        // The document describes the roots of the resolution, each of which depends on the rest.
+        let mut roots = self.roots().map(Name::name).collect::<Vec<_>>();
+        roots.sort_unstable();
+        let mut relationships = roots
+            .into_iter()
+            .map(|name| Relationship {
+                spdx_element_id: "SPDXRef-DOCUMENT".to_string(),
+                relationship_type: "DESCRIBES",
+                related_spdx_element: spdx_id(name),
+            })
+            .collect::<Vec<_>>();
+        for index in &nodes {
+            let mut dependencies = petgraph
+                .edges_directed(*index, Direction::Outgoing)
+                .map(|edge| petgraph[edge.target()].name())
+                .collect::<Vec<_>>();
+            dependencies.sort_unstable();
+            dependencies.dedup();
+            relationships.extend(dependencies.into_iter().map(|dependency| Relationship {
+                spdx_element_id: spdx_id(petgraph[*index].name()),
+                relationship_type: "DEPENDS_ON",
+                related_spdx_element: spdx_id(dependency),
+            }));
+        }
+
+        SpdxDocument {
+            spdx_version: SPDX_VERSION,
+            data_license: "CC0-1.0",
+            spdx_id: "SPDXRef-DOCUMENT",
+            name: document_name.to_string(),
+            document_namespace: document_namespace.to_string(),
+            creation_info: CreationInfo {
+                created: created.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                creators: vec![format!("Tool: uv-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            packages,
+            relationships,
+        }
+    }
+}
+
+/// Write the document in the SPDX tag-value format.
+impl Display for SpdxDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "SPDXVersion: {}", self.spdx_version)?;
+        writeln!(f, "DataLicense: {}", self.data_license)?;
+        writeln!(f, "SPDXID: {}", self.spdx_id)?;
+        writeln!(f, "DocumentName: {}", self.name)?;
+        writeln!(f, "DocumentNamespace: {}", self.document_namespace)?;
+        for creator in &self.creation_info.creators {
+            writeln!(f, "Creator: {creator}")?;
+        }
+        writeln!(f, "Created: {}", self.creation_info.created)?;
+
+        for package in &self.packages {
+            writeln!(f)?;
+            writeln!(f, "PackageName: {}", package.name)?;
+            writeln!(f, "SPDXID: {}", package.spdx_id)?;
+            if let Some(version) = &package.version_info {
+                writeln!(f, "PackageVersion: {version}")?;
+            }
+            writeln!(f, "PackageDownloadLocation: {}", package.download_location)?;
+            writeln!(f, "FilesAnalyzed: {}", package.files_analyzed)?;
+            for checksum in &package.checksums {
+                writeln!(
+                    f,
+                    "PackageChecksum: {}: {}",
+                    checksum.algorithm, checksum.checksum_value
+                )?;
+            }
+            writeln!(f, "PackageLicenseConcluded: {}", package.license_concluded)?;
+            writeln!(f, "PackageLicenseDeclared: {}", package.license_declared)?;
+            writeln!(f, "PackageCopyrightText: {}", package.copyright_text)?;
+        }
+
+        if !self.relationships.is_empty() {
+            writeln!(f)?;
+        }
+        for relationship in &self.relationships {
+            writeln!(
+                f,
+                "Relationship: {} {} {}",
+                relationship.spdx_element_id,
+                relationship.relationship_type,
+                relationship.related_spdx_element
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use pypi_types::{HashAlgorithm, HashDigest};
+
+    use crate::resolution::tests::{graph, set_hashes, set_requested};
+    use crate::ResolutionGraph;
+
+    use super::{to_cyclonedx, SpdxDocument};
+
+    /// A resolution in which `a` is requested directly, and depends on `b` and `c`; `b` also
+    /// depends on `c`, which has a known hash.
+    fn resolution() -> ResolutionGraph {
+        let mut resolution = graph(
+            &[("a", "1.0.0"), ("b", "2.0.0"), ("c", "3.0.0")],
+            &[("a", "b"), ("a", "c"), ("b", "c")],
+        );
+        set_requested(&mut resolution, &["a"]);
+        set_hashes(
+            &mut resolution,
+            "c",
+            vec![HashDigest {
+                algorithm: HashAlgorithm::Sha256,
+                digest: "abc123".into(),
+            }],
+        );
+        resolution
+    }
+
+    /// Convert the resolution into an SPDX document with a fixed namespace and creation time.
+    fn spdx(resolution: &ResolutionGraph) -> SpdxDocument {
+        let created = DateTime::parse_from_rfc3339("2024-03-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        resolution.to_spdx("example", "https://example.org/spdxdocs/example", created)
+    }
+
+    #[test]
+    fn cyclonedx_json() {
+        let bom = to_cyclonedx(&resolution());
+        insta::assert_snapshot!(serde_json::to_string_pretty(&bom).unwrap(), @r###"
+        {
+          "bomFormat": "CycloneDX",
+          "specVersion": "1.5",
+          "version": 1,
+          "components": [
+            {
+              "type": "library",
+              "bom-ref": "a",
+              "name": "a",
+              "version": "1.0.0",
+              "purl": "pkg:pypi/a@1.0.0"
+            },
+            {
+              "type": "library",
+              "bom-ref": "b",
+              "name": "b",
+              "version": "2.0.0",
+              "purl": "pkg:pypi/b@2.0.0"
+            },
+            {
+              "type": "library",
+              "bom-ref": "c",
+              "name": "c",
+              "version": "3.0.0",
+              "purl": "pkg:pypi/c@3.0.0",
+              "hashes": [
+                {
+                  "alg": "SHA-256",
+                  "content": "abc123"
+                }
+              ]
+            }
+          ],
+          "dependencies": [
+            {
+              "ref": "a",
+              "dependsOn": [
+                "b",
+                "c"
+              ]
+            },
+            {
+              "ref": "b",
+              "dependsOn": [
+                "c"
+              ]
+            },
+            {
+              "ref": "c",
+              "dependsOn": []
+            }
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn spdx_json() {
+        let document = serde_json::to_string_pretty(&spdx(&resolution()))
+            .unwrap()
+            .replace(env!("CARGO_PKG_VERSION"), "[VERSION]");
+        insta::assert_snapshot!(document, @r###"
+        {
+          "spdxVersion": "SPDX-2.3",
+          "dataLicense": "CC0-1.0",
+          "SPDXID": "SPDXRef-DOCUMENT",
+          "name": "example",
+          "documentNamespace": "https://example.org/spdxdocs/example",
+          "creationInfo": {
+            "created": "2024-03-25T00:00:00Z",
+            "creators": [
+              "Tool: uv-[VERSION]"
+            ]
+          },
+          "packages": [
+            {
+              "name": "a",
+              "SPDXID": "SPDXRef-Package-a",
+              "versionInfo": "1.0.0",
+              "downloadLocation": "https://pypi.org/simple",
+              "filesAnalyzed": false,
+              "licenseConcluded": "NOASSERTION",
+              "licenseDeclared": "NOASSERTION",
+              "copyrightText": "NOASSERTION"
+            },
+            {
+              "name": "b",
+              "SPDXID": "SPDXRef-Package-b",
+              "versionInfo": "2.0.0",
+              "downloadLocation": "https://pypi.org/simple",
+              "filesAnalyzed": false,
+              "licenseConcluded": "NOASSERTION",
+              "licenseDeclared": "NOASSERTION",
+              "copyrightText": "NOASSERTION"
+            },
+            {
+              "name": "c",
+              "SPDXID": "SPDXRef-Package-c",
+              "versionInfo": "3.0.0",
+              "downloadLocation": "https://pypi.org/simple",
+              "filesAnalyzed": false,
+              "checksums": [
+                {
+                  "algorithm": "SHA256",
+                  "checksumValue": "abc123"
+                }
+              ],
+              "licenseConcluded": "NOASSERTION",
+              "licenseDeclared": "NOASSERTION",
+              "copyrightText": "NOASSERTION"
+            }
+          ],
+          "relationships": [
+            {
+              "spdxElementId": "SPDXRef-DOCUMENT",
+              "relationshipType": "DESCRIBES",
+              "relatedSpdxElement": "SPDXRef-Package-a"
+            },
+            {
+              "spdxElementId": "SPDXRef-Package-a",
+              "relationshipType": "DEPENDS_ON",
+              "relatedSpdxElement": "SPDXRef-Package-b"
+            },
+            {
+              "spdxElementId": "SPDXRef-Package-a",
+              "relationshipType": "DEPENDS_ON",
+              "relatedSpdxElement": "SPDXRef-Package-c"
+            },
+            {
+              "spdxElementId": "SPDXRef-Package-b",
+              "relationshipType": "DEPENDS_ON",
+              "relatedSpdxElement": "SPDXRef-Package-c"
+            }
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn spdx_tag_value() {
+        let document = spdx(&resolution())
+            .to_string()
+            .replace(env!("CARGO_PKG_VERSION"), "[VERSION]");
+        insta::assert_snapshot!(document, @r###"
+        SPDXVersion: SPDX-2.3
+        DataLicense: CC0-1.0
+        SPDXID: SPDXRef-DOCUMENT
+        DocumentName: example
+        DocumentNamespace: https://example.org/spdxdocs/example
+        Creator: Tool: uv-[VERSION]
+        Created: 2024-03-25T00:00:00Z
+
+        PackageName: a
+        SPDXID: SPDXRef-Package-a
+        PackageVersion: 1.0.0
+        PackageDownloadLocation: https://pypi.org/simple
+        FilesAnalyzed: false
+        PackageLicenseConcluded: NOASSERTION
+        PackageLicenseDeclared: NOASSERTION
+        PackageCopyrightText: NOASSERTION
+
+        PackageName: b
+        SPDXID: SPDXRef-Package-b
+        PackageVersion: 2.0.0
+        PackageDownloadLocation: https://pypi.org/simple
+        FilesAnalyzed: false
+        PackageLicenseConcluded: NOASSERTION
+        PackageLicenseDeclared: NOASSERTION
+        PackageCopyrightText: NOASSERTION
+
+        PackageName: c
+        SPDXID: SPDXRef-Package-c
+        PackageVersion: 3.0.0
+        PackageDownloadLocation: https://pypi.org/simple
+        FilesAnalyzed: false
+        PackageChecksum: SHA256: abc123
+        PackageLicenseConcluded: NOASSERTION
+        PackageLicenseDeclared: NOASSERTION
+        PackageCopyrightText: NOASSERTION
+
+        Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-a
+        Relationship: SPDXRef-Package-a DEPENDS_ON SPDXRef-Package-b
+        Relationship: SPDXRef-Package-a DEPENDS_ON SPDXRef-Package-c
+        Relationship: SPDXRef-Package-b DEPENDS_ON SPDXRef-Package-c
+        "###);
+    }
+}
@@ -410,6 +410,125 @@ async fn black_flake8() -> Result<()> {
     Ok(())
 }
 
+/// Resolve `black` with constraints on `click`, which `black` depends on, and `tomli`, which it
+/// only depends on for Python versions prior to 3.11, to ensure that constraints narrow the
+/// versions of existing dependencies without adding packages to the resolution.
+#[tokio::test]
+async fn black_constraints() -> Result<()> {
+    let manifest = Manifest::new(
+        vec![
+            Requirement::from_pep508(pep508_rs::Requirement::from_str("black<=23.9.1").unwrap())
+                .unwrap(),
+        ],
+        Constraints::from_requirements(vec![
+            Requirement::from_pep508(pep508_rs::Requirement::from_str("click==8.1.3").unwrap())
+                .unwrap(),
+            Requirement::from_pep508(pep508_rs::Requirement::from_str("tomli==2.0.1").unwrap())
+                .unwrap(),
+        ]),
+        Overrides::default(),
+        vec![],
+        None,
+        vec![],
+        Exclusions::default(),
+        vec![],
+    );
+    let options = OptionsBuilder::new()
+        .exclude_newer(Some(*EXCLUDE_NEWER))
+        .build();
+
+    let resolution = resolve(manifest, options, &MARKERS_311, &TAGS_311).await?;
+
+    assert_snapshot!(DisplayResolutionGraph::from(&resolution), @r###"
+    black==23.9.1
+    click==8.1.3
+        # via black
+    mypy-extensions==1.0.0
+        # via black
+    packaging==23.2
+        # via black
+    pathspec==0.11.2
+        # via black
+    platformdirs==4.0.0
+        # via black
+    "###);
+
+    Ok(())
+}
+
+/// Resolve `black` with an override on `mypy-extensions` that falls outside the range `black`
+/// itself declares, to ensure that overrides take precedence over transitive requirements.
+#[tokio::test]
+async fn black_mypy_extensions_override() -> Result<()> {
+    let manifest = Manifest::new(
+        vec![
+            Requirement::from_pep508(pep508_rs::Requirement::from_str("black<=23.9.1").unwrap())
+                .unwrap(),
+        ],
+        Constraints::default(),
+        Overrides::from_requirements(vec![Requirement::from_pep508(
+            pep508_rs::Requirement::from_str("mypy-extensions==0.4.2").unwrap(),
+        )
+        .unwrap()]),
+        vec![],
+        None,
+        vec![],
+        Exclusions::default(),
+        vec![],
+    );
+    let options = OptionsBuilder::new()
+        .exclude_newer(Some(*EXCLUDE_NEWER))
+        .build();
+
+    let resolution = resolve(manifest, options, &MARKERS_311, &TAGS_311).await?;
+
+    assert_snapshot!(DisplayResolutionGraph::from(&resolution), @r###"
+    black==23.9.1
+    click==8.1.7
+        # via black
+    mypy-extensions==0.4.2
+        # via black
+    packaging==23.2
+        # via black
+    pathspec==0.11.2
+        # via black
+    platformdirs==4.0.0
+        # via black
+    "###);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn black_highest() -> Result<()> {
+    let manifest = Manifest::simple(vec![Requirement::from_pep508(
+        pep508_rs::Requirement::from_str("black>21").unwrap(),
+    )
+    .unwrap()]);
+    let options = OptionsBuilder::new()
+        .resolution_mode(ResolutionMode::Highest)
+        .exclude_newer(Some(*EXCLUDE_NEWER))
+        .build();
+
+    let resolution = resolve(manifest, options, &MARKERS_311, &TAGS_311).await?;
+
+    assert_snapshot!(DisplayResolutionGraph::from(&resolution), @r###"
+    black==23.11.0
+    click==8.1.7
+        # via black
+    mypy-extensions==1.0.0
+        # via black
+    packaging==23.2
+        # via black
+    pathspec==0.11.2
+        # via black
+    platformdirs==4.0.0
+        # via black
+    "###);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn black_lowest() -> Result<()> {
     let manifest = Manifest::simple(vec![Requirement::from_pep508(
@@ -15,7 +15,7 @@ use uv_interpreter::PythonVersion;
 use uv_normalize::{ExtraName, PackageName};
 use uv_resolver::{AnnotationStyle, ExcludeNewer, PreReleaseMode, ResolutionMode};
 
-use crate::commands::{extra_name_with_clap_error, ListFormat, VersionFormat};
+use crate::commands::{extra_name_with_clap_error, ListFormat, LockFormat, VersionFormat};
 use crate::compat;
 
 #[derive(Parser)]
@@ -619,6 +619,22 @@ pub(crate) struct PipCompileArgs {
     #[arg(long, overrides_with("emit_index_annotation"), hide = true)]
     pub(crate) no_emit_index_annotation: bool,
 
+    /// Warn about direct requirements that lack a lower bound (e.g., `requests` or `requests<3`),
+    /// since their resolution can change as new versions are published.
+    #[arg(long, overrides_with("no_warn_unbounded"))]
+    pub(crate) warn_unbounded: bool,
+
+    #[arg(long, overrides_with("warn_unbounded"), hide = true)]
+    pub(crate) no_warn_unbounded: bool,
+
+    /// Warn about every issue detected in the resolution, including advisory ones (e.g.,
+    /// dependency cycles, unused extras, or pre-releases selected as a fallback).
+    #[arg(long, overrides_with("no_warn_all"))]
+    pub(crate) warn_all: bool,
+
+    #[arg(long, overrides_with("warn_all"), hide = true)]
+    pub(crate) no_warn_all: bool,
+
     #[arg(long, overrides_with("no_unstable_uv_lock_file"), hide = true)]
     pub(crate) unstable_uv_lock_file: bool,
 
@@ -1337,6 +1353,14 @@ pub(crate) struct PipInstallArgs {
     #[arg(long)]
     pub(crate) dry_run: bool,
 
+    /// Warn about every issue detected in the resolution, including advisory ones (e.g.,
+    /// dependency cycles, unused extras, or pre-releases selected as a fallback).
+    #[arg(long, overrides_with("no_warn_all"))]
+    pub(crate) warn_all: bool,
+
+    #[arg(long, overrides_with("warn_all"), hide = true)]
+    pub(crate) no_warn_all: bool,
+
     #[arg(long, hide = true)]
     pub(crate) unstable_uv_lock_file: Option<String>,
 
@@ -1886,6 +1910,11 @@ pub(crate) struct SyncArgs {
 #[derive(Args)]
 #[allow(clippy::struct_excessive_bools)]
 pub(crate) struct LockArgs {
+    /// Select the output format between: `toml` (default), which writes a `uv.lock`, or
+    /// `pylock-toml`, which writes a PEP 751 `pylock.toml`.
+    #[arg(long, value_enum, default_value_t = LockFormat::default())]
+    pub(crate) format: LockFormat,
+
     /// The Python interpreter to use to build the run environment.
     ///
     /// By default, `uv` uses the virtual environment in the current working directory or any parent
@@ -126,6 +126,25 @@ pub(crate) enum ListFormat {
     Json,
 }
 
+#[derive(Debug, Default, Clone, Copy, clap::ValueEnum)]
+pub(crate) enum LockFormat {
+    /// Write the lockfile in uv's native format, to `uv.lock`.
+    #[default]
+    Toml,
+    /// Write the lockfile in the PEP 751 format, to `pylock.toml`.
+    PylockToml,
+}
+
+impl LockFormat {
+    /// The name of the file to which the lockfile is written.
+    pub(crate) fn filename(self) -> &'static str {
+        match self {
+            Self::Toml => "uv.lock",
+            Self::PylockToml => "pylock.toml",
+        }
+    }
+}
+
 /// Compile all Python source files in site-packages to bytecode, to speed up the
 /// initial run of any subsequent executions.
 ///
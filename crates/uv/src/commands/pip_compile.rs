@@ -44,9 +44,9 @@ use uv_requirements::{
     RequirementsSource, RequirementsSpecification, SourceTreeResolver,
 };
 use uv_resolver::{
-    AnnotationStyle, DependencyMode, DisplayResolutionGraph, ExcludeNewer, Exclusions, FlatIndex,
-    InMemoryIndex, Manifest, OptionsBuilder, PreReleaseMode, PythonRequirement, ResolutionMode,
-    Resolver,
+    AnnotationStyle, DependencyMode, Diagnostic, DisplayResolutionGraph, ExcludeNewer, Exclusions,
+    FlatIndex, InMemoryIndex, Manifest, OptionsBuilder, PreReleaseMode, PythonRequirement,
+    ResolutionMode, Resolver,
 };
 use uv_types::{BuildIsolation, EmptyInstalledPackages, HashStrategy, InFlight};
 use uv_warnings::warn_user;
@@ -93,6 +93,8 @@ pub(crate) async fn pip_compile(
     python: Option<String>,
     system: bool,
     concurrency: Concurrency,
+    warn_unbounded: bool,
+    warn_all: bool,
     uv_lock: bool,
     native_tls: bool,
     quiet: bool,
@@ -330,6 +332,9 @@ pub(crate) async fn pip_compile(
             &top_level_index,
             DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
         )
+        .with_cache(&cache)
+        .with_refresh(cache.refresh().clone())
+        .with_concurrency_limit(concurrency.downloads)
         .with_reporter(ResolverReporter::from(printer))
         .resolve()
         .await?;
@@ -360,6 +365,9 @@ pub(crate) async fn pip_compile(
         &top_level_index,
         DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
     )
+    .with_cache(&cache)
+    .with_refresh(cache.refresh().clone())
+    .with_concurrency_limit(concurrency.downloads)
     .with_reporter(ResolverReporter::from(printer))
     .resolve()
     .await?;
@@ -572,8 +580,17 @@ pub(crate) async fn pip_compile(
         .dimmed()
     )?;
 
-    // Notify the user of any diagnostics.
-    for diagnostic in resolution.diagnostics() {
+    // Notify the user of any diagnostics. Advisory diagnostics, like unbounded requirements, are
+    // only reported on request.
+    for diagnostic in resolution
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| match diagnostic {
+            _ if warn_all => true,
+            Diagnostic::MissingLowerBound { .. } => warn_unbounded,
+            diagnostic => !diagnostic.is_advisory(),
+        })
+    {
         writeln!(
             printer.stderr(),
             "{}{} {}",
@@ -618,8 +635,7 @@ pub(crate) async fn pip_compile(
     }
 
     if uv_lock {
-        let lock = resolution.lock()?;
-        let encoded = toml::to_string_pretty(&lock)?;
+        let encoded = resolution.to_lock_toml()?;
         fs::tokio::write("uv.lock", encoded.as_bytes()).await?;
     }
 
@@ -659,6 +675,7 @@ pub(crate) async fn pip_compile(
             &no_emit_packages,
             generate_hashes,
             include_extras,
+            false,
             include_annotations,
             include_index_annotation,
             annotation_style,
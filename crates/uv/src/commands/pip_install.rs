@@ -45,9 +45,9 @@ use uv_requirements::{
     RequirementsSpecification, SourceTreeResolver,
 };
 use uv_resolver::{
-    DependencyMode, ExcludeNewer, Exclusions, FlatIndex, InMemoryIndex, Lock, Manifest, Options,
-    OptionsBuilder, PreReleaseMode, Preference, PythonRequirement, ResolutionGraph, ResolutionMode,
-    Resolver,
+    DependencyMode, Diagnostic, ExcludeNewer, Exclusions, FlatIndex, InMemoryIndex, Lock, Manifest,
+    Options, OptionsBuilder, PreReleaseMode, Preference, PythonRequirement, ResolutionGraph,
+    ResolutionMode, Resolver,
 };
 use uv_types::{BuildIsolation, HashStrategy, InFlight};
 use uv_warnings::warn_user;
@@ -91,6 +91,7 @@ pub(crate) async fn pip_install(
     break_system_packages: bool,
     target: Option<Target>,
     concurrency: Concurrency,
+    warn_all: bool,
     uv_lock: Option<String>,
     native_tls: bool,
     preview: PreviewMode,
@@ -367,7 +368,7 @@ pub(crate) async fn pip_install(
     let resolution = if let Some(ref root) = uv_lock {
         let root = PackageName::new(root.to_string())?;
         let encoded = fs::tokio::read_to_string("uv.lock").await?;
-        let lock: Lock = toml::from_str(&encoded)?;
+        let lock = Lock::migrate(&encoded)?;
         lock.to_resolution(&markers, &tags, &root)
     } else {
         // Resolve the requirements from the provided sources.
@@ -379,6 +380,9 @@ pub(crate) async fn pip_install(
                 &index,
                 DistributionDatabase::new(&client, &resolve_dispatch, concurrency.downloads),
             )
+            .with_cache(&cache)
+            .with_refresh(cache.refresh().clone())
+            .with_concurrency_limit(concurrency.downloads)
             .with_reporter(ResolverReporter::from(printer))
             .resolve()
             .await?;
@@ -413,6 +417,9 @@ pub(crate) async fn pip_install(
             &index,
             DistributionDatabase::new(&client, &resolve_dispatch, concurrency.downloads),
         )
+        .with_cache(&cache)
+        .with_refresh(cache.refresh().clone())
+        .with_concurrency_limit(concurrency.downloads)
         .with_reporter(ResolverReporter::from(printer))
         .resolve()
         .await?;
@@ -444,6 +451,7 @@ pub(crate) async fn pip_install(
             &resolve_dispatch,
             concurrency,
             options,
+            warn_all,
             printer,
         )
         .await
@@ -666,6 +674,7 @@ async fn resolve(
     build_dispatch: &BuildDispatch<'_>,
     concurrency: Concurrency,
     options: Options,
+    warn_all: bool,
     printer: Printer,
 ) -> Result<ResolutionGraph, Error> {
     let start = std::time::Instant::now();
@@ -794,8 +803,12 @@ async fn resolve(
         .dimmed()
     )?;
 
-    // Notify the user of any diagnostics.
-    for diagnostic in resolution.diagnostics() {
+    // Notify the user of any diagnostics. Yanked packages are reported prior to installation, and
+    // advisory diagnostics (e.g., dependency cycles) are only reported on request.
+    for diagnostic in resolution.diagnostics().iter().filter(|diagnostic| {
+        !matches!(diagnostic, Diagnostic::YankedPackage { .. })
+            && (warn_all || !diagnostic.is_advisory())
+    }) {
         writeln!(
             printer.stderr(),
             "{}{} {}",
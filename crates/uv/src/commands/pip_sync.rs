@@ -276,6 +276,9 @@ pub(crate) async fn pip_sync(
             &index,
             DistributionDatabase::new(&client, &build_dispatch, concurrency.downloads),
         )
+        .with_cache(&cache)
+        .with_refresh(cache.refresh().clone())
+        .with_concurrency_limit(concurrency.downloads)
         .with_reporter(ResolverReporter::from(printer))
         .resolve()
         .await?;
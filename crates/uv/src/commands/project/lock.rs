@@ -16,12 +16,13 @@ use uv_warnings::warn_user;
 
 use crate::commands::project::discovery::Project;
 use crate::commands::project::Error;
-use crate::commands::{project, ExitStatus};
+use crate::commands::{project, ExitStatus, LockFormat};
 use crate::printer::Printer;
 
 /// Resolve the project requirements into a lockfile.
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn lock(
+    format: LockFormat,
     preview: PreviewMode,
     cache: &Cache,
     printer: Printer,
@@ -136,9 +137,11 @@ pub(crate) async fn lock(
     }?;
 
     // Write the lockfile to disk.
-    let lock = resolution.lock()?;
-    let encoded = toml::to_string_pretty(&lock)?;
-    fs_err::tokio::write(project.root().join("uv.lock"), encoded.as_bytes()).await?;
+    let encoded = match format {
+        LockFormat::Toml => resolution.to_lock_toml()?,
+        LockFormat::PylockToml => resolution.lock()?.to_pylock_toml()?,
+    };
+    fs_err::tokio::write(project.root().join(format.filename()), encoded.as_bytes()).await?;
 
     Ok(ExitStatus::Success)
 }
@@ -22,10 +22,10 @@ use uv_requirements::{
     SourceTreeResolver,
 };
 use uv_resolver::{
-    Exclusions, FlatIndex, InMemoryIndex, Manifest, Options, PythonRequirement, ResolutionGraph,
-    Resolver,
+    Diagnostic, Exclusions, FlatIndex, InMemoryIndex, Manifest, Options, PythonRequirement,
+    ResolutionGraph, Resolver,
 };
-use uv_types::{EmptyInstalledPackages, HashStrategy, InFlight};
+use uv_types::{BuildContext, EmptyInstalledPackages, HashStrategy, InFlight};
 
 use crate::commands::project::discovery::Project;
 use crate::commands::reporters::{DownloadReporter, InstallReporter, ResolverReporter};
@@ -146,6 +146,9 @@ pub(crate) async fn resolve(
             index,
             DistributionDatabase::new(client, build_dispatch, concurrency.downloads),
         )
+        .with_cache(build_dispatch.cache())
+        .with_refresh(build_dispatch.cache().refresh().clone())
+        .with_concurrency_limit(concurrency.downloads)
         .with_reporter(ResolverReporter::from(printer))
         .resolve()
         .await?;
@@ -224,8 +227,11 @@ pub(crate) async fn resolve(
         .dimmed()
     )?;
 
-    // Notify the user of any diagnostics.
-    for diagnostic in resolution.diagnostics() {
+    // Notify the user of any diagnostics. Yanked packages are reported prior to installation, and
+    // advisory diagnostics (e.g., unbounded requirements) are omitted.
+    for diagnostic in resolution.diagnostics().iter().filter(|diagnostic| {
+        !matches!(diagnostic, Diagnostic::YankedPackage { .. }) && !diagnostic.is_advisory()
+    }) {
         writeln!(
             printer.stderr(),
             "{}{} {}",
@@ -230,6 +230,8 @@ async fn run() -> Result<ExitStatus> {
                 args.shared.python,
                 args.shared.system,
                 args.shared.concurrency,
+                args.warn_unbounded,
+                args.warn_all,
                 args.uv_lock,
                 globals.native_tls,
                 globals.quiet,
@@ -345,6 +347,7 @@ async fn run() -> Result<ExitStatus> {
                 args.shared.break_system_packages,
                 args.shared.target,
                 args.shared.concurrency,
+                args.warn_all,
                 args.uv_lock,
                 globals.native_tls,
                 globals.preview,
@@ -539,9 +542,9 @@ async fn run() -> Result<ExitStatus> {
         }
         Commands::Lock(args) => {
             // Resolve the settings from the command-line arguments and workspace configuration.
-            let _args = settings::LockSettings::resolve(args, workspace);
+            let args = settings::LockSettings::resolve(args, workspace);
 
-            commands::lock(globals.preview, &cache, printer).await
+            commands::lock(args.format, globals.preview, &cache, printer).await
         }
         #[cfg(feature = "self-update")]
         Commands::Self_(SelfNamespace {
@@ -24,7 +24,7 @@ use crate::cli::{
     PipInstallArgs, PipListArgs, PipShowArgs, PipSyncArgs, PipUninstallArgs, RunArgs, SyncArgs,
     VenvArgs,
 };
-use crate::commands::ListFormat;
+use crate::commands::{ListFormat, LockFormat};
 
 /// The resolved global settings to use for any invocation of the CLI.
 #[allow(clippy::struct_excessive_bools)]
@@ -143,6 +143,7 @@ impl SyncSettings {
 #[derive(Debug, Clone)]
 pub(crate) struct LockSettings {
     // CLI-only settings.
+    pub(crate) format: LockFormat,
     pub(crate) python: Option<String>,
 }
 
@@ -150,10 +151,11 @@ impl LockSettings {
     /// Resolve the [`LockSettings`] from the CLI and workspace configuration.
     #[allow(clippy::needless_pass_by_value)]
     pub(crate) fn resolve(args: LockArgs, _workspace: Option<Workspace>) -> Self {
-        let LockArgs { python } = args;
+        let LockArgs { format, python } = args;
 
         Self {
             // CLI-only settings.
+            format,
             python,
         }
     }
@@ -169,6 +171,8 @@ pub(crate) struct PipCompileSettings {
     pub(crate) r#override: Vec<PathBuf>,
     pub(crate) refresh: Refresh,
     pub(crate) upgrade: Upgrade,
+    pub(crate) warn_unbounded: bool,
+    pub(crate) warn_all: bool,
     pub(crate) uv_lock: bool,
 
     // Shared settings.
@@ -239,6 +243,10 @@ impl PipCompileSettings {
             no_emit_marker_expression,
             emit_index_annotation,
             no_emit_index_annotation,
+            warn_unbounded,
+            no_warn_unbounded,
+            warn_all,
+            no_warn_all,
             unstable_uv_lock_file,
             no_unstable_uv_lock_file,
             compat_args: _,
@@ -254,6 +262,8 @@ impl PipCompileSettings {
             r#override,
             refresh: Refresh::from_args(flag(refresh, no_refresh), refresh_package),
             upgrade: Upgrade::from_args(flag(upgrade, no_upgrade), upgrade_package),
+            warn_unbounded: flag(warn_unbounded, no_warn_unbounded).unwrap_or(false),
+            warn_all: flag(warn_all, no_warn_all).unwrap_or(false),
             uv_lock: flag(unstable_uv_lock_file, no_unstable_uv_lock_file).unwrap_or(false),
 
             // Shared settings.
@@ -437,6 +447,7 @@ pub(crate) struct PipInstallSettings {
     pub(crate) reinstall: Reinstall,
     pub(crate) refresh: Refresh,
     pub(crate) dry_run: bool,
+    pub(crate) warn_all: bool,
     pub(crate) uv_lock: Option<String>,
     // Shared settings.
     pub(crate) shared: PipSharedSettings,
@@ -502,6 +513,8 @@ impl PipInstallSettings {
             no_strict,
             exclude_newer,
             dry_run,
+            warn_all,
+            no_warn_all,
             unstable_uv_lock_file,
             compat_args: _,
         } = args;
@@ -520,6 +533,7 @@ impl PipInstallSettings {
             reinstall: Reinstall::from_args(flag(reinstall, no_reinstall), reinstall_package),
             refresh: Refresh::from_args(flag(refresh, no_refresh), refresh_package),
             dry_run,
+            warn_all: flag(warn_all, no_warn_all).unwrap_or(false),
             uv_lock: unstable_uv_lock_file,
 
             // Shared settings.
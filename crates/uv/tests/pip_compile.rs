@@ -75,6 +75,133 @@ fn compile_requirements_in_annotation_line() -> Result<()> {
     Ok(())
 }
 
+/// Reject annotation styles that don't produce a valid requirements file (e.g., `json`).
+#[test]
+fn compile_requirements_in_annotation_json() -> Result<()> {
+    let context = TestContext::new("3.12");
+    let requirements_in = context.temp_dir.child("requirements.in");
+    requirements_in.write_str("anyio==3.7.0")?;
+
+    uv_snapshot!(context
+        .compile()
+        .arg("--annotation-style=json")
+        .arg("requirements.in"), @r###"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'json' for '--annotation-style <ANNOTATION_STYLE>'
+      [possible values: line, split, verbose]
+
+    For more information, try '--help'.
+    "###);
+
+    Ok(())
+}
+
+/// Warn about direct requirements without a lower bound, but only when requested.
+#[test]
+fn compile_requirements_in_warn_unbounded() -> Result<()> {
+    let context = TestContext::new("3.12");
+    let requirements_in = context.temp_dir.child("requirements.in");
+    requirements_in.write_str("iniconfig<3")?;
+
+    uv_snapshot!(context
+        .compile()
+        .arg("requirements.in"), @r###"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    # This file was autogenerated by uv via the following command:
+    #    uv pip compile --cache-dir [CACHE_DIR] --exclude-newer 2024-03-25T00:00:00Z requirements.in
+    iniconfig==2.0.0
+        # via -r requirements.in
+
+    ----- stderr -----
+    Resolved 1 package in [TIME]
+    "###);
+
+    uv_snapshot!(context
+        .compile()
+        .arg("--warn-unbounded")
+        .arg("requirements.in"), @r###"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    # This file was autogenerated by uv via the following command:
+    #    uv pip compile --cache-dir [CACHE_DIR] --exclude-newer 2024-03-25T00:00:00Z --warn-unbounded requirements.in
+    iniconfig==2.0.0
+        # via -r requirements.in
+
+    ----- stderr -----
+    Resolved 1 package in [TIME]
+    warning: The requirement `iniconfig` has no lower bound, so its resolution may change as new versions are published.
+    "###);
+
+    Ok(())
+}
+
+/// Report advisory diagnostics, like unbounded requirements, when `--warn-all` is provided.
+#[test]
+fn compile_requirements_in_warn_all() -> Result<()> {
+    let context = TestContext::new("3.12");
+    let requirements_in = context.temp_dir.child("requirements.in");
+    requirements_in.write_str("iniconfig<3")?;
+
+    uv_snapshot!(context
+        .compile()
+        .arg("--warn-all")
+        .arg("requirements.in"), @r###"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    # This file was autogenerated by uv via the following command:
+    #    uv pip compile --cache-dir [CACHE_DIR] --exclude-newer 2024-03-25T00:00:00Z --warn-all requirements.in
+    iniconfig==2.0.0
+        # via -r requirements.in
+
+    ----- stderr -----
+    Resolved 1 package in [TIME]
+    warning: The requirement `iniconfig` has no lower bound, so its resolution may change as new versions are published.
+    "###);
+
+    Ok(())
+}
+
+/// Warn when a hash in the existing output file contradicts the hash reported by the index.
+#[test]
+fn compile_requirements_in_invalid_preferred_hash() -> Result<()> {
+    let context = TestContext::new("3.12");
+    let requirements_in = context.temp_dir.child("requirements.in");
+    requirements_in.write_str("sniffio==1.3.1")?;
+    let requirements_txt = context.temp_dir.child("requirements.txt");
+    requirements_txt.write_str("sniffio==1.3.1 \\\n    --hash=sha256:0000000000000000000000000000000000000000000000000000000000000000\n")?;
+
+    uv_snapshot!(context
+        .compile()
+        .arg("requirements.in")
+        .arg("--output-file")
+        .arg("requirements.txt")
+        .arg("--generate-hashes"), @r###"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    # This file was autogenerated by uv via the following command:
+    #    uv pip compile --cache-dir [CACHE_DIR] --exclude-newer 2024-03-25T00:00:00Z requirements.in --output-file requirements.txt --generate-hashes
+    sniffio==1.3.1 \
+        --hash=sha256:0000000000000000000000000000000000000000000000000000000000000000
+        # via -r requirements.in
+        # warning: expected sha256:0000000000000000000000000000000000000000000000000000000000000000, but found sha256:2f6da418d1f1e0fddd844478f41680e794e6051915791a034ff65e5f100525a2
+
+    ----- stderr -----
+    Resolved 1 package in [TIME]
+    warning: The hash for `sniffio==1.3.1` does not match: expected `sha256:0000000000000000000000000000000000000000000000000000000000000000`, but found `sha256:2f6da418d1f1e0fddd844478f41680e794e6051915791a034ff65e5f100525a2`.
+    "###);
+
+    Ok(())
+}
+
 /// Resolve a specific version of `anyio` from a `requirements.in` file on stdin
 /// when passed a path of `-`.
 #[test]
@@ -2756,6 +2883,7 @@ fn compile_yanked_version_direct() -> Result<()> {
 
     ----- stderr -----
     Resolved 1 package in [TIME]
+    warning: The package `attrs==21.1.0` is yanked (reason: "Installable but not importable on Python 3.4").
     "###
     );
 
@@ -7360,7 +7488,7 @@ fn emit_marker_expression_direct() -> Result<()> {
     # This file was autogenerated by uv via the following command:
     #    uv pip compile --cache-dir [CACHE_DIR] --exclude-newer 2024-03-25T00:00:00Z requirements.in --emit-marker-expression
     # Pinned dependencies known to be valid for:
-    #    python_version == '3.12' and platform_python_implementation == 'CPython' and sys_platform == 'linux' and platform_system == 'Linux'
+    #    platform_system == 'Linux' and python_version == '3.12' and platform_python_implementation == 'CPython' and sys_platform == 'linux'
     anyio==4.3.0
         # via -r requirements.in
     idna==3.6
@@ -7440,7 +7568,7 @@ fn emit_marker_expression_pypy() -> Result<()> {
     # This file was autogenerated by uv via the following command:
     #    uv pip compile --cache-dir [CACHE_DIR] --exclude-newer 2024-03-25T00:00:00Z requirements.in --emit-marker-expression
     # Pinned dependencies known to be valid for:
-    #    python_version == '3.12' and implementation_name == 'cpython'
+    #    python_version == '3.12' and implementation_name != 'pypy'
     pendulum==3.0.0
         # via -r requirements.in
     python-dateutil==2.9.0.post0